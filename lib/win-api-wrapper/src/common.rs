@@ -1,61 +1,25 @@
-use core::mem::size_of;
-use widestring::U16String;
-use windows_sys::core::PCWSTR;
+/// Buffer length negotiation for Windows API functions that fill caller-allocated buffers.
+pub mod buffer;
+/// Command line parsing and quoting that is compatible with `CommandLineToArgvW`.
+pub mod command_line;
+/// Win32 path prefixes, normalization and NT device path translation.
+pub mod path;
+/// Plain old data types that can be copied from and to raw memory.
+pub mod pod;
+/// Null-terminated wide string conversions for Windows API inputs and outputs.
+///
+/// # Remarks
+///
+/// * The functions that return strings return the [`U16String`][`wide::U16String`] that the system filled,
+///   so no conversion is done unless the caller needs one.
+/// * [`U16Str::to_os_string`][`wide::U16Str::to_os_string`] and [`U16Str::to_string`][`wide::U16Str::to_string`]
+///   convert the result in a single pass. A conversion without any copy is not possible,
+///   because [`OsString`][`std::ffi::OsString`] and [`String`][`alloc::string::String`] don't store UTF-16.
+///
+pub mod wide;
 
 /// This trait defines a `to` method for Borrowed -> Owned conversion between two types.
 pub trait To<T> {
     /// Converts the borrowed type to an owned type of `T`.
     fn to(&self) -> T;
 }
-
-/// Returns a subslice of `slice` that doesn't contain any trailing whitespaces or null characters at the end.
-pub(crate) fn trim_wide_end(mut slice: &[u16]) -> &[u16] {
-    while let Some(char) = slice.last() {
-        match char {
-            0 | 32 | 9..=13 => slice = &slice[..slice.len() - 1],
-            _ => break,
-        }
-    }
-
-    slice
-}
-
-/// Gets the length of a [`PCWSTR`][`windows_sys::core::PCWSTR`].
-/// The ending null character is not included in the result.
-///
-/// # Safety
-///
-/// If any of the following conditions are violated, the result is Undefined Behavior:
-///
-/// * `pwstr` must point to an element of a wide string that is null-terminated.
-///
-pub unsafe fn get_pcwstr_len(pwstr: PCWSTR) -> usize {
-    let mut len = 0;
-    let type_size = size_of::<u16>();
-    unsafe {
-        while *pwstr.offset((len * type_size) as isize) != 0 {
-            len += 1;
-        }
-    };
-    len
-}
-
-/// Copies the elements of a `len` long [`PCWSTR`][`windows_sys::core::PCWSTR`] to a newly allocated [`U16String`].
-/// If `trim_end` is `true` the end of the result string will be trimmed.
-///
-/// # Safety
-///
-/// If any of the following conditions are violated, the result is Undefined Behavior:
-///
-/// * `pwstr` must point to a `u16` value.
-/// * `pwstr` must be valid for `len` number of consecutive values of type `u16`.
-/// * `len * core::mem::size_of::<u16>()` must be no larger than `isize::MAX`.
-///
-pub(crate) unsafe fn pcwstr_to_u16_string(pwstr: PCWSTR, len: usize, trim_end: bool) -> U16String {
-    let mut message_slice = unsafe { core::slice::from_raw_parts(pwstr, len) }; // '\0' is ignored
-    if trim_end {
-        message_slice = trim_wide_end(message_slice);
-    }
-
-    unsafe { U16String::from_ptr(message_slice.as_ptr(), message_slice.len()) }
-}
@@ -0,0 +1,359 @@
+use crate::win32::core::{Result, Win32Error};
+use alloc::vec::Vec;
+use windows_sys::Win32::Foundation::ERROR_INSUFFICIENT_BUFFER;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Controls how [`negotiate`] allocates and grows the buffer.
+pub struct BufferPolicy {
+    /// The length of the first buffer.
+    initial_len: usize,
+    /// The maximum length of the buffer.
+    max_len: usize,
+    /// The factor that is used to grow the buffer when the required length is not known.
+    growth_factor: usize,
+}
+
+impl BufferPolicy {
+    /// The default growth factor.
+    pub const DEFAULT_GROWTH_FACTOR: usize = 2;
+
+    #[inline]
+    /// Creates a new [`BufferPolicy`] that starts with a buffer of `initial_len` elements and never allocates more than `max_len` elements.
+    ///
+    /// # Remarks
+    ///
+    /// * `initial_len` is capped at `max_len`.
+    /// * An `initial_len` of `0` can be used to query the required length first.
+    ///
+    pub const fn new(initial_len: usize, max_len: usize) -> Self {
+        Self {
+            initial_len: if initial_len < max_len {
+                initial_len
+            } else {
+                max_len
+            },
+            max_len,
+            growth_factor: Self::DEFAULT_GROWTH_FACTOR,
+        }
+    }
+
+    #[inline]
+    /// Sets the factor that is used to grow the buffer when the required length is not reported by the call.
+    ///
+    /// # Remarks
+    ///
+    /// * A `growth_factor` less than `2` is treated as `2`.
+    ///
+    #[must_use]
+    pub const fn with_growth_factor(mut self, growth_factor: usize) -> Self {
+        self.growth_factor = if growth_factor < 2 { 2 } else { growth_factor };
+        self
+    }
+
+    #[inline]
+    /// Gets the length of the first buffer.
+    pub const fn initial_len(&self) -> usize {
+        self.initial_len
+    }
+
+    #[inline]
+    /// Gets the maximum length of the buffer.
+    pub const fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    #[inline]
+    /// Gets the factor that is used to grow the buffer.
+    pub const fn growth_factor(&self) -> usize {
+        self.growth_factor
+    }
+
+    /// Gets the length of the next buffer after a buffer of `len` elements was too small.
+    /// If the buffer cannot grow anymore, the result is [`None`].
+    pub const fn next_len(&self, len: usize, required_len: Option<usize>) -> Option<usize> {
+        if len >= self.max_len {
+            return None;
+        }
+
+        let grown_len = if len == 0 {
+            1
+        } else {
+            len.saturating_mul(self.growth_factor)
+        };
+        let next_len = match required_len {
+            Some(required_len) if required_len > len => required_len,
+            _ => grown_len,
+        };
+        Some(if next_len < self.max_len {
+            next_len
+        } else {
+            self.max_len
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The outcome of a single call that tries to fill a buffer. See [`negotiate`].
+pub enum Fill {
+    /// The call succeeded and wrote the specified number of elements.
+    Written(usize),
+    /// The buffer is too small. The required length is included if it was reported by the call.
+    TooSmall(Option<usize>),
+}
+
+impl Fill {
+    /// Interprets the result of a call that reports the required length in `count` (e.g. `GetProcessGroupAffinity`).
+    ///
+    /// # Remarks
+    ///
+    /// * On success `count` is the number of written elements.
+    /// * On `ERROR_INSUFFICIENT_BUFFER` failure `count` is the required length.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the call if it failed with an error other than `ERROR_INSUFFICIENT_BUFFER`.
+    ///
+    pub fn required(result: Result<()>, count: usize) -> Result<Self> {
+        match result {
+            Ok(()) => Ok(Self::Written(count)),
+            Err(error) if error.code() == ERROR_INSUFFICIENT_BUFFER => {
+                Ok(Self::TooSmall(Some(count)))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Interprets the result of a call that returns the number of written elements
+    /// and fails with `ERROR_INSUFFICIENT_BUFFER` without reporting the required length (e.g. `QueryFullProcessImageNameW`).
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the call if it failed with an error other than `ERROR_INSUFFICIENT_BUFFER`.
+    ///
+    pub fn written(result: Result<usize>) -> Result<Self> {
+        match result {
+            Ok(count) => Ok(Self::Written(count)),
+            Err(error) if error.code() == ERROR_INSUFFICIENT_BUFFER => Ok(Self::TooSmall(None)),
+            Err(error) => Err(error),
+        }
+    }
+
+    #[inline]
+    /// Interprets the result of a call that silently fills the buffer until it is full (e.g. `EnumProcesses`).
+    /// A completely filled buffer is considered too small, because there might be more elements.
+    pub const fn until_full(count: usize, buffer_len: usize) -> Self {
+        if count < buffer_len {
+            Self::Written(count)
+        } else {
+            Self::TooSmall(None)
+        }
+    }
+
+    #[inline]
+    /// Interprets the result of a call that silently truncates a string to `buffer_len - 1` characters,
+    /// null-terminates it and returns its length (e.g. `GetModuleFileNameExW`).
+    /// A string that fills the buffer up to the null terminator is considered too small, because it might be truncated.
+    pub const fn truncated(len: usize, buffer_len: usize) -> Self {
+        if len + 1 < buffer_len {
+            Self::Written(len)
+        } else {
+            Self::TooSmall(None)
+        }
+    }
+}
+
+/// Calls `fill` with a growing buffer until the result fits into the buffer and returns the written elements.
+///
+/// # Arguments
+///
+/// * `policy`: Controls the initial length, the growth and the maximum length of the buffer.
+/// * `fill`: Fills the specified buffer and reports the outcome as a [`Fill`].
+///
+/// # Remarks
+///
+/// * If the required length is reported by `fill`, the buffer is resized to the required length.
+///   Otherwise it is grown by the growth factor of `policy`.
+/// * The buffer is never larger than the maximum length of `policy`.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if `fill` fails.
+///
+/// ## Possible errors
+///
+/// * The buffer is still too small when it reaches the maximum length of `policy`. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
+///
+/// # Examples
+/// TODO
+///
+pub fn negotiate<T, F>(policy: BufferPolicy, mut fill: F) -> Result<Vec<T>>
+where
+    T: Copy + Default,
+    F: FnMut(&mut [T]) -> Result<Fill>,
+{
+    let mut buffer = vec![T::default(); policy.initial_len()];
+    loop {
+        match fill(&mut buffer)? {
+            Fill::Written(count) => {
+                buffer.truncate(count);
+                return Ok(buffer);
+            }
+            Fill::TooSmall(required_len) => {
+                let Some(next_len) = policy.next_len(buffer.len(), required_len) else {
+                    return Err(Win32Error::new(ERROR_INSUFFICIENT_BUFFER));
+                };
+                buffer.resize(next_len, T::default());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED;
+
+    #[test]
+    fn fill_required_interprets_the_count() {
+        assert_eq!(Fill::required(Ok(()), 3), Ok(Fill::Written(3)));
+        assert_eq!(
+            Fill::required(Err(Win32Error::new(ERROR_INSUFFICIENT_BUFFER)), 8),
+            Ok(Fill::TooSmall(Some(8)))
+        );
+        assert_eq!(
+            Fill::required(Err(Win32Error::new(ERROR_ACCESS_DENIED)), 8),
+            Err(Win32Error::new(ERROR_ACCESS_DENIED))
+        );
+    }
+
+    #[test]
+    fn fill_written_doesnt_report_a_required_len() {
+        assert_eq!(Fill::written(Ok(5)), Ok(Fill::Written(5)));
+        assert_eq!(
+            Fill::written(Err(Win32Error::new(ERROR_INSUFFICIENT_BUFFER))),
+            Ok(Fill::TooSmall(None))
+        );
+        assert_eq!(
+            Fill::written(Err(Win32Error::new(ERROR_ACCESS_DENIED))),
+            Err(Win32Error::new(ERROR_ACCESS_DENIED))
+        );
+    }
+
+    #[test]
+    fn fill_until_full_treats_a_full_buffer_as_too_small() {
+        assert_eq!(Fill::until_full(3, 4), Fill::Written(3));
+        assert_eq!(Fill::until_full(0, 4), Fill::Written(0));
+        assert_eq!(Fill::until_full(4, 4), Fill::TooSmall(None));
+    }
+
+    #[test]
+    fn fill_truncated_treats_a_string_that_fills_the_buffer_as_too_small() {
+        assert_eq!(Fill::truncated(2, 4), Fill::Written(2));
+        assert_eq!(Fill::truncated(0, 4), Fill::Written(0));
+        assert_eq!(Fill::truncated(3, 4), Fill::TooSmall(None));
+        assert_eq!(Fill::truncated(4, 4), Fill::TooSmall(None));
+        assert_eq!(Fill::truncated(0, 0), Fill::TooSmall(None));
+    }
+
+    #[test]
+    fn policy_caps_the_initial_len() {
+        assert_eq!(BufferPolicy::new(16, 8).initial_len(), 8);
+        assert_eq!(BufferPolicy::new(4, 8).initial_len(), 4);
+        assert_eq!(
+            BufferPolicy::new(4, 8)
+                .with_growth_factor(1)
+                .growth_factor(),
+            2
+        );
+    }
+
+    #[test]
+    fn next_len_grows_without_a_reported_len() {
+        let policy = BufferPolicy::new(0, 100);
+        assert_eq!(policy.next_len(0, None), Some(1));
+        assert_eq!(policy.next_len(4, None), Some(8));
+        assert_eq!(policy.with_growth_factor(3).next_len(4, None), Some(12));
+        // A reported length that is not larger than the buffer is ignored.
+        assert_eq!(policy.next_len(4, Some(4)), Some(8));
+    }
+
+    #[test]
+    fn next_len_uses_the_reported_len() {
+        let policy = BufferPolicy::new(0, 100);
+        assert_eq!(policy.next_len(0, Some(7)), Some(7));
+        assert_eq!(policy.next_len(4, Some(5)), Some(5));
+    }
+
+    #[test]
+    fn next_len_is_clamped_to_the_max_len() {
+        let policy = BufferPolicy::new(0, 10);
+        assert_eq!(policy.next_len(8, None), Some(10));
+        assert_eq!(policy.next_len(4, Some(50)), Some(10));
+        assert_eq!(policy.next_len(10, None), None);
+        assert_eq!(policy.next_len(10, Some(11)), None);
+        assert_eq!(
+            BufferPolicy::new(0, usize::MAX).next_len(usize::MAX / 2 + 1, None),
+            Some(usize::MAX)
+        );
+    }
+
+    #[test]
+    fn negotiate_resizes_to_the_reported_len() {
+        let mut lens = vec![];
+        let buffer = negotiate(BufferPolicy::new(0, 100), |buffer: &mut [u32]| {
+            lens.push(buffer.len());
+            if buffer.len() < 3 {
+                return Ok(Fill::TooSmall(Some(3)));
+            }
+            buffer[..3].copy_from_slice(&[1, 2, 3]);
+            Ok(Fill::Written(3))
+        })
+        .unwrap();
+
+        assert_eq!(buffer, [1, 2, 3]);
+        assert_eq!(lens, [0, 3]);
+    }
+
+    #[test]
+    fn negotiate_grows_without_a_reported_len() {
+        let values: Vec<u32> = (0..10).collect();
+        let mut lens = vec![];
+        let buffer = negotiate(BufferPolicy::new(2, 100), |buffer: &mut [u32]| {
+            lens.push(buffer.len());
+            let count = values.len().min(buffer.len());
+            buffer[..count].copy_from_slice(&values[..count]);
+            Ok(Fill::until_full(count, buffer.len()))
+        })
+        .unwrap();
+
+        assert_eq!(buffer, values);
+        assert_eq!(lens, [2, 4, 8, 16]);
+    }
+
+    #[test]
+    fn negotiate_fails_at_the_max_len() {
+        let mut lens = vec![];
+        let error = negotiate(BufferPolicy::new(2, 6), |buffer: &mut [u8]| {
+            lens.push(buffer.len());
+            Ok(Fill::TooSmall(None))
+        })
+        .unwrap_err();
+
+        assert_eq!(error.code(), ERROR_INSUFFICIENT_BUFFER);
+        assert_eq!(lens, [2, 4, 6]);
+    }
+
+    #[test]
+    fn negotiate_passes_errors_through() {
+        let mut calls = 0;
+        let error = negotiate(BufferPolicy::new(2, 6), |_: &mut [u8]| {
+            calls += 1;
+            Err(Win32Error::new(ERROR_ACCESS_DENIED))
+        })
+        .unwrap_err();
+
+        assert_eq!(error.code(), ERROR_ACCESS_DENIED);
+        assert_eq!(calls, 1);
+    }
+}
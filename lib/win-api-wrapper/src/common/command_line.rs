@@ -0,0 +1,347 @@
+use crate::win32::core::{Result, Win32Error};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::iter;
+use widestring::{U16Str, U16String};
+use windows_sys::Win32::Foundation::ERROR_INVALID_PARAMETER;
+
+/// The space character that separates the arguments.
+const SPACE: u16 = b' ' as u16;
+/// The tab character that separates the arguments.
+const TAB: u16 = b'\t' as u16;
+/// The quotation mark that encloses arguments that contain separators.
+const QUOTE: u16 = b'"' as u16;
+/// The backslash that escapes quotation marks.
+const BACKSLASH: u16 = b'\\' as u16;
+
+#[inline]
+/// Determines whether `unit` separates the arguments of a command line (space or tab).
+const fn is_separator(unit: u16) -> bool {
+    unit == SPACE || unit == TAB
+}
+
+/// Splits a command line into arguments like `CommandLineToArgvW` does.
+///
+/// # Remarks
+///
+/// * The first argument is the program name. It ends at the first space or tab that is not enclosed in quotation marks
+///   and the quotation marks in it are removed. Backslashes are always taken literally in the program name.
+/// * In the other arguments:
+///   * Spaces and tabs separate the arguments unless they are enclosed in quotation marks.
+///   * `2n` backslashes followed by a quotation mark produce `n` backslashes and the quotation mark starts or ends a quoted part.
+///   * `2n + 1` backslashes followed by a quotation mark produce `n` backslashes and a literal quotation mark.
+///   * Backslashes that are not followed by a quotation mark are taken literally.
+///   * Two consecutive quotation marks in a quoted part produce a literal quotation mark.
+/// * Unlike `CommandLineToArgvW`, an empty command line produces no arguments instead of the path of the current executable.
+///
+pub fn parse(command_line: &U16Str) -> Vec<U16String> {
+    let mut units = command_line.as_slice().iter().copied().peekable();
+    let mut args = Vec::new();
+    if units.peek().is_none() {
+        return args;
+    }
+
+    // The program name has no escape sequences: every quotation mark toggles the quoted part.
+    let mut program = Vec::new();
+    let mut in_quotes = false;
+    for unit in units.by_ref() {
+        match unit {
+            QUOTE => in_quotes = !in_quotes,
+            unit if is_separator(unit) && !in_quotes => break,
+            unit => program.push(unit),
+        }
+    }
+    args.push(U16String::from_vec(program));
+    while units.next_if(|&unit| is_separator(unit)).is_some() {}
+
+    let mut arg = Vec::new();
+    in_quotes = false;
+    while let Some(unit) = units.next() {
+        match unit {
+            unit if is_separator(unit) && !in_quotes => {
+                args.push(U16String::from_vec(core::mem::take(&mut arg)));
+                while units.next_if(|&next| is_separator(next)).is_some() {}
+            }
+            BACKSLASH => {
+                let mut backslashes = 1;
+                while units.next_if_eq(&BACKSLASH).is_some() {
+                    backslashes += 1;
+                }
+
+                if units.peek() == Some(&QUOTE) {
+                    arg.extend(iter::repeat_n(BACKSLASH, backslashes / 2));
+                    // An odd number of backslashes escapes the quotation mark.
+                    if backslashes % 2 == 1 {
+                        units.next();
+                        arg.push(QUOTE);
+                    }
+                } else {
+                    arg.extend(iter::repeat_n(BACKSLASH, backslashes));
+                }
+            }
+            QUOTE if in_quotes => match units.peek() {
+                Some(&QUOTE) => {
+                    units.next();
+                    arg.push(QUOTE);
+                }
+                Some(_) => in_quotes = false,
+                // The last argument is pushed even if it is empty, because it was quoted.
+                None => break,
+            },
+            QUOTE => in_quotes = true,
+            unit => arg.push(unit),
+        }
+    }
+    if !arg.is_empty() || in_quotes {
+        args.push(U16String::from_vec(arg));
+    }
+
+    args
+}
+
+/// Quotes and escapes an argument, so [`parse`] produces the same argument.
+/// The argument is returned unchanged if it doesn't need to be quoted.
+///
+/// # Remarks
+///
+/// * The argument is quoted if it is empty or it contains a space, a tab or a quotation mark.
+/// * This function must not be used for the program name. See [`quote_program`].
+///
+pub fn quote_arg(arg: &U16Str) -> Cow<'_, U16Str> {
+    let slice = arg.as_slice();
+    if !slice.is_empty()
+        && !slice
+            .iter()
+            .any(|&unit| is_separator(unit) || unit == QUOTE)
+    {
+        return Cow::Borrowed(arg);
+    }
+
+    let mut quoted = Vec::with_capacity(slice.len() + 2);
+    quoted.push(QUOTE);
+    let mut backslashes = 0;
+    for &unit in slice {
+        match unit {
+            BACKSLASH => {
+                backslashes += 1;
+                continue;
+            }
+            // The backslashes that precede a quotation mark and the quotation mark itself are escaped.
+            QUOTE => quoted.extend(iter::repeat_n(BACKSLASH, backslashes * 2 + 1)),
+            _ => quoted.extend(iter::repeat_n(BACKSLASH, backslashes)),
+        }
+        backslashes = 0;
+        quoted.push(unit);
+    }
+    // The backslashes that precede the closing quotation mark are escaped.
+    quoted.extend(iter::repeat_n(BACKSLASH, backslashes * 2));
+    quoted.push(QUOTE);
+    Cow::Owned(U16String::from_vec(quoted))
+}
+
+/// Quotes a program name, so [`parse`] produces the same program name.
+/// The program name is returned unchanged if it doesn't need to be quoted.
+///
+/// # Remarks
+///
+/// * The program name is quoted if it is empty or it contains a space or a tab.
+/// * The program name has no escape sequences, so it cannot contain a quotation mark.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the program name cannot be quoted.
+///
+/// ## Possible errors
+///
+/// * `program` contains a quotation mark. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+///
+pub fn quote_program(program: &U16Str) -> Result<Cow<'_, U16Str>> {
+    let slice = program.as_slice();
+    if slice.contains(&QUOTE) {
+        return Err(Win32Error::new(ERROR_INVALID_PARAMETER)
+            .with_context("the program name contains a quotation mark"));
+    }
+
+    if !slice.is_empty() && !slice.iter().copied().any(is_separator) {
+        return Ok(Cow::Borrowed(program));
+    }
+
+    let mut quoted = Vec::with_capacity(slice.len() + 2);
+    quoted.push(QUOTE);
+    quoted.extend_from_slice(slice);
+    quoted.push(QUOTE);
+    Ok(Cow::Owned(U16String::from_vec(quoted)))
+}
+
+/// Builds a command line from a program name and its arguments, so [`parse`] produces the same program name and arguments.
+///
+/// # Remarks
+///
+/// * The program name is quoted using [`quote_program`] and the arguments are quoted using [`quote_arg`].
+/// * The result is not null-terminated and it may contain null characters if the arguments do.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the program name cannot be quoted.
+///
+/// ## Possible errors
+///
+/// * `program` contains a quotation mark. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+///
+pub fn join<I, S>(program: &U16Str, args: I) -> Result<U16String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<U16Str>,
+{
+    let mut command_line = quote_program(program)?.into_owned();
+    for arg in args {
+        command_line.push_slice([SPACE]);
+        command_line.push(quote_arg(arg.as_ref()));
+    }
+
+    Ok(command_line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+    use widestring::u16str;
+
+    /// Parses `command_line` and converts the arguments to strings.
+    fn parse_str(command_line: &str) -> Vec<String> {
+        parse(&U16String::from_str(command_line))
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect()
+    }
+
+    #[test]
+    fn parse_table() {
+        let cases: &[(&str, &[&str])] = &[
+            ("", &[]),
+            ("p", &["p"]),
+            ("p  a\tb ", &["p", "a", "b"]),
+            // Backslash runs before a quotation mark.
+            (r#"p a\\"b c""#, &["p", r"a\b c"]),
+            (r#"p a\\\"b"#, &["p", r#"a\"b"#]),
+            (r#"p a\\\\"b""#, &["p", r"a\\b"]),
+            (r"p a\b", &["p", r"a\b"]),
+            // Backslash runs at the end of an argument.
+            (r"p a\\ b\", &["p", r"a\\", r"b\"]),
+            (r#"p "a b\\""#, &["p", r"a b\"]),
+            (r#"p "a\\" b"#, &["p", r"a\", "b"]),
+            // Two quotation marks outside and inside a quoted part.
+            (r#"p "" b"#, &["p", "", "b"]),
+            (r#"p a""b"#, &["p", "ab"]),
+            (r#"p "a""b""#, &["p", r#"a"b"#]),
+            (r#"p "a""b" c"#, &["p", r#"a"b"#, "c"]),
+            (r#"p """"#, &["p", r#"""#]),
+            // An empty trailing argument.
+            (r#"p a """#, &["p", "a", ""]),
+            (r#"p a ""#, &["p", "a", ""]),
+            // Leading whitespace produces an empty program name.
+            (" a b", &["", "a", "b"]),
+            ("\tp", &["", "p"]),
+            // The program name takes backslashes literally and quotation marks only toggle the quoted part.
+            (
+                r#""C:\Program Files\x.exe" a"#,
+                &[r"C:\Program Files\x.exe", "a"],
+            ),
+            (r#"C:\a\"b c" d"#, &[r"C:\a\b c", "d"]),
+            (r#"C:\a\\"b c"#, &[r"C:\a\\b c"]),
+        ];
+
+        for &(command_line, expected) in cases {
+            assert_eq!(parse_str(command_line), expected, "{command_line}");
+        }
+    }
+
+    #[test]
+    fn quote_arg_escapes_only_when_needed() {
+        let cases = [
+            ("a", "a"),
+            (r"a\b", r"a\b"),
+            ("", r#""""#),
+            (r"a\b c", r#""a\b c""#),
+            (r#"a\"b"#, r#""a\\\"b""#),
+            (r"a b\", r#""a b\\""#),
+            (r#"""#, r#""\"""#),
+        ];
+
+        for (arg, expected) in cases {
+            let quoted = quote_arg(&U16String::from_str(arg)).to_string_lossy();
+            assert_eq!(quoted, expected, "{arg}");
+        }
+        assert!(matches!(quote_arg(u16str!("a")), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn quote_program_rejects_quotation_marks() {
+        assert!(quote_program(u16str!(r#"a"b"#)).is_err());
+        assert_eq!(
+            quote_program(u16str!("a b")).unwrap().as_ref(),
+            u16str!(r#""a b""#)
+        );
+        assert_eq!(
+            quote_program(u16str!("")).unwrap().as_ref(),
+            u16str!(r#""""#)
+        );
+    }
+
+    #[test]
+    fn join_builds_a_command_line() {
+        let command_line = join(
+            u16str!(r"C:\Program Files\x.exe"),
+            [u16str!("a"), u16str!(""), u16str!(r#"b "c"\"#)],
+        )
+        .unwrap();
+
+        assert_eq!(
+            command_line.to_string_lossy(),
+            r#""C:\Program Files\x.exe" a "" "b \"c\"\\""#
+        );
+    }
+
+    /// A small xorshift generator, so the round-trip test is deterministic without extra dependencies.
+    struct XorShift(u64);
+
+    impl XorShift {
+        /// Gets the next pseudo-random value below `bound`.
+        fn below(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+
+        /// Generates a string of up to `max_len` units from `alphabet`.
+        fn string(&mut self, alphabet: &[u16], max_len: usize) -> U16String {
+            let len = self.below(max_len + 1);
+            (0..len)
+                .map(|_| alphabet[self.below(alphabet.len())])
+                .collect::<Vec<_>>()
+                .into()
+        }
+    }
+
+    #[test]
+    fn parse_round_trips_join() {
+        let program_alphabet = [u16::from(b'a'), SPACE, TAB, BACKSLASH, 0xE9];
+        let arg_alphabet = [u16::from(b'a'), SPACE, TAB, QUOTE, BACKSLASH, 0xE9];
+        let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+
+        for _ in 0..10_000 {
+            let program = rng.string(&program_alphabet, 6);
+            let args: Vec<U16String> = (0..rng.below(5))
+                .map(|_| rng.string(&arg_alphabet, 8))
+                .collect();
+
+            let command_line = join(&program, &args).unwrap();
+            let mut expected = vec![program];
+            expected.extend(args);
+            assert_eq!(parse(&command_line), expected, "{}", command_line.display());
+        }
+    }
+}
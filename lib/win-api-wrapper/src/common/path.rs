@@ -0,0 +1,615 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use widestring::{u16str, U16Str, U16String};
+
+/// The path separator (`\`).
+pub const SEPARATOR: u16 = b'\\' as u16;
+/// The alternative path separator (`/`) that is accepted by the Win32 path functions.
+pub const ALT_SEPARATOR: u16 = b'/' as u16;
+/// The prefix of Win32 paths that are passed to the file system without normalization (`\\?\`).
+pub const VERBATIM_PREFIX: &U16Str = u16str!(r"\\?\");
+/// The prefix of UNC paths that are passed to the file system without normalization (`\\?\UNC\`).
+pub const VERBATIM_UNC_PREFIX: &U16Str = u16str!(r"\\?\UNC\");
+/// The prefix of NT paths that refer to the DOS devices directory of the object manager (`\??\`).
+pub const NT_DOS_DEVICES_PREFIX: &U16Str = u16str!(r"\??\");
+/// The NT device path of the multiple UNC provider that handles UNC paths (`\Device\Mup`).
+pub const NT_MUP_DEVICE: &U16Str = u16str!(r"\Device\Mup");
+
+/// The colon (`:`) that follows the drive letter.
+const COLON: u16 = b':' as u16;
+/// The dot (`.`) that refers to the current directory.
+const DOT: u16 = b'.' as u16;
+/// The question mark (`?`) of the verbatim prefix.
+const QUESTION_MARK: u16 = b'?' as u16;
+/// The `UNC\` component that follows the verbatim prefix in verbatim UNC paths.
+const UNC_COMPONENT: &U16Str = u16str!(r"UNC\");
+
+#[inline]
+/// Determines whether `unit` is a path separator (`\` or `/`).
+pub const fn is_separator(unit: u16) -> bool {
+    unit == SEPARATOR || unit == ALT_SEPARATOR
+}
+
+/// Converts `unit` to upper case like the file system does when it compares names.
+///
+/// # Remarks
+///
+/// * Only characters that have a single upper case character in the Basic Multilingual Plane are converted.
+///   Other characters (including surrogates) are returned unchanged.
+///
+pub fn to_upper(unit: u16) -> u16 {
+    if unit < 0x80 {
+        return (unit as u8).to_ascii_uppercase().into();
+    }
+
+    let Some(c) = char::from_u32(unit.into()) else {
+        return unit;
+    };
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) => u16::try_from(u32::from(upper)).unwrap_or(unit),
+        _ => unit,
+    }
+}
+
+/// Determines whether `a` and `b` are equal ignoring the case like the file system does.
+pub fn eq_ignore_case(a: &[u16], b: &[u16]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(&a, &b)| a == b || to_upper(a) == to_upper(b))
+}
+
+/// Determines whether `path` starts with `prefix` ignoring the case.
+fn starts_with_ignore_case(path: &[u16], prefix: &[u16]) -> bool {
+    path.len() >= prefix.len() && eq_ignore_case(&path[..prefix.len()], prefix)
+}
+
+/// Determines whether `path` starts with a drive letter followed by a colon (e.g. `C:`).
+const fn has_drive(path: &[u16]) -> bool {
+    matches!(path, [letter, COLON, ..] if *letter < 0x80 && (*letter as u8).is_ascii_alphabetic())
+}
+
+/// Determines whether `path` is an absolute path that starts with a drive (e.g. `C:\`).
+const fn is_drive_absolute(path: &[u16]) -> bool {
+    has_drive(path) && path.len() > 2 && is_separator(path[2])
+}
+
+/// Determines whether `path` is a UNC path (e.g. `\\server\share`) that is not a verbatim or device path.
+const fn is_unc(path: &[u16]) -> bool {
+    matches!(path, [a, b, rest @ ..] if is_separator(*a) && is_separator(*b)
+        && !matches!(rest, [DOT | QUESTION_MARK, c, ..] if is_separator(*c)))
+}
+
+/// Determines whether `path` starts with the verbatim prefix (`\\?\`).
+pub fn has_long_path_prefix(path: &U16Str) -> bool {
+    path.as_slice().starts_with(VERBATIM_PREFIX.as_slice())
+}
+
+/// Adds the verbatim prefix (`\\?\` or `\\?\UNC\`) to an absolute path, so it can exceed `MAX_PATH` characters.
+///
+/// # Remarks
+///
+/// * `C:\dir\file` becomes `\\?\C:\dir\file` and `\\server\share\file` becomes `\\?\UNC\server\share\file`.
+/// * The verbatim prefix disables the normalization of the path,
+///   so the alternative separators (`/`) are replaced with `\` when the prefix is added.
+/// * Paths that already have the verbatim prefix, device paths (`\\.\`), relative paths
+///   and drive relative paths (e.g. `C:file`) are returned unchanged, because they cannot be prefixed.
+///
+pub fn add_long_path_prefix(path: &U16Str) -> Cow<'_, U16Str> {
+    let slice = path.as_slice();
+    let (prefix, rest) = if is_drive_absolute(slice) {
+        (VERBATIM_PREFIX, slice)
+    } else if is_unc(slice) {
+        (VERBATIM_UNC_PREFIX, &slice[2..])
+    } else {
+        return Cow::Borrowed(path);
+    };
+
+    let mut prefixed = Vec::with_capacity(prefix.len() + rest.len());
+    prefixed.extend_from_slice(prefix.as_slice());
+    prefixed.extend(rest.iter().map(|&unit| {
+        if unit == ALT_SEPARATOR {
+            SEPARATOR
+        } else {
+            unit
+        }
+    }));
+    Cow::Owned(U16String::from_vec(prefixed))
+}
+
+/// Removes the verbatim prefix (`\\?\` or `\\?\UNC\`) from a path if the result is still a valid Win32 path.
+///
+/// # Remarks
+///
+/// * `\\?\C:\dir\file` becomes `C:\dir\file` and `\\?\UNC\server\share\file` becomes `\\server\share\file`.
+/// * Paths that don't have the verbatim prefix and verbatim paths that don't refer
+///   to a drive or a UNC share (e.g. `\\?\Volume{...}\`) are returned unchanged.
+///
+pub fn strip_long_path_prefix(path: &U16Str) -> Cow<'_, U16Str> {
+    strip_prefix(path, VERBATIM_PREFIX)
+}
+
+/// Removes `prefix` (`\\?\` or `\??\`) from `path` like [`strip_long_path_prefix`] does.
+fn strip_prefix<'a>(path: &'a U16Str, prefix: &U16Str) -> Cow<'a, U16Str> {
+    let Some(rest) = path.as_slice().strip_prefix(prefix.as_slice()) else {
+        return Cow::Borrowed(path);
+    };
+
+    if is_drive_absolute(rest) || rest.len() == 2 && has_drive(rest) {
+        Cow::Borrowed(U16Str::from_slice(rest))
+    } else if starts_with_ignore_case(rest, UNC_COMPONENT.as_slice()) {
+        let mut unc = Vec::with_capacity(rest.len() - 2);
+        unc.extend_from_slice(&[SEPARATOR, SEPARATOR]);
+        unc.extend_from_slice(&rest[UNC_COMPONENT.len()..]);
+        Cow::Owned(U16String::from_vec(unc))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Splits an absolute or relative path into its root and the rest of the path.
+/// The root is one of `\\server\share`, `C:\`, `C:`, `\` or empty.
+fn split_root(path: &[u16]) -> (&[u16], &[u16]) {
+    if is_unc(path) {
+        // The server and the share names are part of the root.
+        let mut end = 2;
+        for _ in 0..2 {
+            while end < path.len() && is_separator(path[end]) {
+                end += 1;
+            }
+            while end < path.len() && !is_separator(path[end]) {
+                end += 1;
+            }
+        }
+        path.split_at(end)
+    } else if is_drive_absolute(path) {
+        path.split_at(3)
+    } else if has_drive(path) {
+        path.split_at(2)
+    } else if path.first().copied().is_some_and(is_separator) {
+        path.split_at(1)
+    } else {
+        path.split_at(0)
+    }
+}
+
+/// Normalizes a path, so equal paths can be compared by value.
+///
+/// # Remarks
+///
+/// * The verbatim prefix (`\\?\` or `\\?\UNC\`) is removed if possible (see [`strip_long_path_prefix`]).
+/// * The alternative separators (`/`) are replaced with `\` and repeated separators are collapsed.
+/// * The `.` components are removed and the `..` components remove the previous component.
+///   The root of the path (e.g. `C:\` or `\\server\share`) is never removed.
+/// * Trailing separators are removed unless the path is a root (e.g. `C:\`).
+/// * Every character is converted to upper case like the file system does when it compares names.
+/// * The path is not resolved against the current directory and links are not followed,
+///   so different paths that refer to the same file are not necessarily equal after the normalization.
+///
+pub fn normalize_for_comparison(path: &U16Str) -> U16String {
+    let path = strip_long_path_prefix(path);
+    let (root, rest) = split_root(path.as_slice());
+
+    let mut components: Vec<&[u16]> = Vec::new();
+    for component in rest.split(|&unit| is_separator(unit)) {
+        match component {
+            [] | [DOT] => {}
+            [DOT, DOT] => match components.last() {
+                Some(&[DOT, DOT]) => components.push(component),
+                Some(_) => {
+                    components.pop();
+                }
+                // A relative path can refer to the parents of the current directory, but the root has no parent.
+                None if root.is_empty() || root.ends_with(&[COLON]) => components.push(component),
+                None => {}
+            },
+            _ => components.push(component),
+        }
+    }
+
+    let mut normalized = Vec::with_capacity(path.len());
+    normalized.extend(root.iter().map(|&unit| {
+        if unit == ALT_SEPARATOR {
+            SEPARATOR
+        } else {
+            to_upper(unit)
+        }
+    }));
+    if is_unc(root) {
+        // Collapse the separators between the server and the share names.
+        normalized.dedup_by(|unit, previous| *unit == SEPARATOR && *previous == SEPARATOR);
+        normalized.insert(0, SEPARATOR);
+        if normalized.last() == Some(&SEPARATOR) {
+            normalized.pop();
+        }
+    }
+
+    let mut needs_separator = !(normalized.is_empty()
+        || normalized.ends_with(&[SEPARATOR])
+        || normalized.ends_with(&[COLON]));
+    for component in components {
+        if needs_separator {
+            normalized.push(SEPARATOR);
+        }
+        normalized.extend(component.iter().map(|&unit| to_upper(unit)));
+        needs_separator = true;
+    }
+
+    U16String::from_vec(normalized)
+}
+
+/// Determines whether `a` and `b` refer to the same path after [`normalize_for_comparison`].
+pub fn eq_normalized(a: &U16Str, b: &U16Str) -> bool {
+    normalize_for_comparison(a) == normalize_for_comparison(b)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A table that maps NT device paths (e.g. `\Device\HarddiskVolume3`) to DOS drives (e.g. `C:`).
+///
+/// # Remarks
+///
+/// * The table of the running system can be retrieved using
+///   [`get_device_map`][`crate::win32::storage::file_system::get_device_map`].
+/// * The translation itself is pure, so a table can also be built manually using [`DeviceMap::insert`].
+///
+pub struct DeviceMap {
+    /// The `(drive, device)` pairs.
+    entries: Vec<(U16String, U16String)>,
+}
+
+impl DeviceMap {
+    #[inline]
+    /// Creates a new empty [`DeviceMap`].
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Maps `drive` (e.g. `C:`) to the NT device path `device` (e.g. `\Device\HarddiskVolume3`).
+    ///
+    /// # Remarks
+    ///
+    /// * Trailing separators of `drive` and `device` are removed.
+    /// * If `drive` is already mapped, the previous mapping is replaced.
+    ///
+    pub fn insert(&mut self, drive: &U16Str, device: &U16Str) {
+        let drive = U16String::from_vec(trim_separators(drive.as_slice()));
+        let device = U16String::from_vec(trim_separators(device.as_slice()));
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| eq_ignore_case(existing.as_slice(), drive.as_slice()))
+        {
+            Some(entry) => entry.1 = device,
+            None => self.entries.push((drive, device)),
+        }
+    }
+
+    /// Gets the NT device path that `drive` (e.g. `C:`) is mapped to.
+    pub fn get(&self, drive: &U16Str) -> Option<&U16Str> {
+        let drive = trim_separators(drive.as_slice());
+        self.entries
+            .iter()
+            .find(|(existing, _)| eq_ignore_case(existing.as_slice(), &drive))
+            .map(|(_, device)| device.as_ustr())
+    }
+
+    #[inline]
+    /// Returns an iterator over the `(drive, device)` pairs in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&U16Str, &U16Str)> {
+        self.entries
+            .iter()
+            .map(|(drive, device)| (drive.as_ustr(), device.as_ustr()))
+    }
+
+    #[inline]
+    /// Gets the number of mapped drives.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    /// Determines whether no drives are mapped.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Translates an NT path (e.g. `\Device\HarddiskVolume3\Windows\notepad.exe`)
+    /// to a Win32 path (e.g. `C:\Windows\notepad.exe`).
+    ///
+    /// # Remarks
+    ///
+    /// * The device with the longest matching path is used (the shortest rest of the path). The device path must match whole components and it is compared ignoring the case.
+    /// * The paths of the multiple UNC provider (`\Device\Mup\server\share`) are translated to UNC paths (`\\server\share`).
+    /// * The paths of the DOS devices directory (`\??\C:\dir`) are translated by removing the prefix like [`strip_long_path_prefix`] does.
+    /// * The result is [`None`] if the path cannot be translated.
+    ///
+    pub fn to_dos_path(&self, nt_path: &U16Str) -> Option<U16String> {
+        let slice = nt_path.as_slice();
+        if slice.starts_with(NT_DOS_DEVICES_PREFIX.as_slice()) {
+            return match strip_prefix(nt_path, NT_DOS_DEVICES_PREFIX) {
+                Cow::Borrowed(path) if path.len() == nt_path.len() => None,
+                path => Some(path.into_owned()),
+            };
+        }
+
+        if let Some(rest) = strip_device(slice, NT_MUP_DEVICE.as_slice()) {
+            return (!rest.is_empty()).then(|| {
+                let mut unc = Vec::with_capacity(rest.len() + 1);
+                unc.push(SEPARATOR);
+                unc.extend_from_slice(rest);
+                U16String::from_vec(unc)
+            });
+        }
+
+        let (drive, rest) = self
+            .entries
+            .iter()
+            .filter_map(|(drive, device)| {
+                strip_device(slice, device.as_slice()).map(|rest| (drive, rest))
+            })
+            .min_by_key(|(_, rest)| rest.len())?;
+
+        let mut dos_path = Vec::with_capacity(drive.len() + rest.len() + 1);
+        dos_path.extend_from_slice(drive.as_slice());
+        if rest.is_empty() {
+            dos_path.push(SEPARATOR);
+        } else {
+            dos_path.extend_from_slice(rest);
+        }
+        Some(U16String::from_vec(dos_path))
+    }
+
+    /// Translates a Win32 path that starts with a drive (e.g. `C:\Windows\notepad.exe`)
+    /// to an NT path (e.g. `\Device\HarddiskVolume3\Windows\notepad.exe`).
+    ///
+    /// # Remarks
+    ///
+    /// * The verbatim prefix (`\\?\`) is removed first if possible.
+    /// * The result is [`None`] if the path doesn't start with a mapped drive.
+    ///
+    pub fn to_nt_path(&self, dos_path: &U16Str) -> Option<U16String> {
+        let dos_path = strip_long_path_prefix(dos_path);
+        let slice = dos_path.as_slice();
+        if !has_drive(slice) {
+            return None;
+        }
+
+        let (drive, rest) = slice.split_at(2);
+        let device = self.get(U16Str::from_slice(drive))?;
+        let mut nt_path = Vec::with_capacity(device.len() + rest.len());
+        nt_path.extend_from_slice(device.as_slice());
+        nt_path.extend(rest.iter().map(|&unit| {
+            if unit == ALT_SEPARATOR {
+                SEPARATOR
+            } else {
+                unit
+            }
+        }));
+        Some(U16String::from_vec(nt_path))
+    }
+}
+
+/// Removes trailing separators from `path`.
+fn trim_separators(mut path: &[u16]) -> Vec<u16> {
+    while let Some((last, rest)) = path.split_last() {
+        if !is_separator(*last) {
+            break;
+        }
+        path = rest;
+    }
+
+    path.to_vec()
+}
+
+/// Removes the device path `device` from the start of `path` if it matches whole components.
+/// The rest of the path is empty or it starts with a separator.
+fn strip_device<'a>(path: &'a [u16], device: &[u16]) -> Option<&'a [u16]> {
+    if device.is_empty() || !starts_with_ignore_case(path, device) {
+        return None;
+    }
+
+    let rest = &path[device.len()..];
+    (rest.is_empty() || rest[0] == SEPARATOR).then_some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Converts `path` to a wide string.
+    fn wide(path: &str) -> U16String {
+        U16String::from_str(path)
+    }
+
+    /// Builds the device map that is used by the translation tests.
+    fn device_map() -> DeviceMap {
+        let mut map = DeviceMap::new();
+        map.insert(u16str!("C:"), u16str!(r"\Device\HarddiskVolume1"));
+        map.insert(u16str!(r"d:\"), u16str!(r"\Device\HarddiskVolume10\"));
+        map
+    }
+
+    #[test]
+    fn to_dos_path_table() {
+        let map = device_map();
+        let cases: &[(&str, Option<&str>)] = &[
+            (
+                r"\Device\HarddiskVolume1\Windows\x.exe",
+                Some(r"C:\Windows\x.exe"),
+            ),
+            (r"\Device\HarddiskVolume1", Some(r"C:\")),
+            // The device is compared ignoring the case.
+            (r"\DEVICE\harddiskvolume1\a", Some(r"C:\a")),
+            // A device name that is a prefix of another one must match whole components.
+            (r"\Device\HarddiskVolume10\a", Some(r"d:\a")),
+            (r"\Device\HarddiskVolume10", Some(r"d:\")),
+            (r"\Device\HarddiskVolume100\a", None),
+            (r"\Device\HarddiskVolume2\a", None),
+            (r"\Device\Mup\server\share\a", Some(r"\\server\share\a")),
+            (r"\Device\Mup", None),
+            (r"\??\C:\a", Some(r"C:\a")),
+            (r"\??\UNC\server\share", Some(r"\\server\share")),
+            (r"\??\Volume{1}\a", None),
+            (r"C:\a", None),
+        ];
+
+        for &(nt_path, expected) in cases {
+            let dos_path = map.to_dos_path(&wide(nt_path));
+            assert_eq!(
+                dos_path.map(|path| path.to_string_lossy()).as_deref(),
+                expected,
+                "{nt_path}"
+            );
+        }
+    }
+
+    #[test]
+    fn to_nt_path_table() {
+        let map = device_map();
+        let cases: &[(&str, Option<&str>)] = &[
+            (
+                r"C:\Windows\x.exe",
+                Some(r"\Device\HarddiskVolume1\Windows\x.exe"),
+            ),
+            (r"c:/a/b", Some(r"\Device\HarddiskVolume1\a\b")),
+            (r"D:\a", Some(r"\Device\HarddiskVolume10\a")),
+            (r"\\?\D:\a", Some(r"\Device\HarddiskVolume10\a")),
+            (r"C:", Some(r"\Device\HarddiskVolume1")),
+            (r"E:\a", None),
+            (r"\\server\share\a", None),
+            (r"a\b", None),
+        ];
+
+        for &(dos_path, expected) in cases {
+            let nt_path = map.to_nt_path(&wide(dos_path));
+            assert_eq!(
+                nt_path.map(|path| path.to_string_lossy()).as_deref(),
+                expected,
+                "{dos_path}"
+            );
+        }
+    }
+
+    #[test]
+    fn device_map_insert_replaces_drives_ignoring_the_case() {
+        let mut map = device_map();
+        map.insert(u16str!("c:"), u16str!(r"\Device\HarddiskVolume3"));
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(
+            map.get(u16str!(r"C:\")),
+            Some(u16str!(r"\Device\HarddiskVolume3"))
+        );
+        assert_eq!(map.get(u16str!("E:")), None);
+    }
+
+    #[test]
+    fn add_long_path_prefix_table() {
+        let cases = [
+            (r"C:\a\b", r"\\?\C:\a\b"),
+            (r"c:/a/b", r"\\?\c:\a\b"),
+            (r"\\server\share\a", r"\\?\UNC\server\share\a"),
+            (r"//server/share", r"\\?\UNC\server\share"),
+            // Paths that already have a prefix are unchanged.
+            (r"\\?\C:\a", r"\\?\C:\a"),
+            (r"\\?\UNC\server\share", r"\\?\UNC\server\share"),
+            (r"\\.\pipe\a", r"\\.\pipe\a"),
+            // Relative paths cannot be prefixed.
+            (r"a\b", r"a\b"),
+            (r"C:a", r"C:a"),
+            (r"\a", r"\a"),
+            ("", ""),
+        ];
+
+        for (path, expected) in cases {
+            let path = wide(path);
+            let prefixed = add_long_path_prefix(&path);
+            assert_eq!(prefixed.to_string_lossy(), expected, "{}", path.display());
+            if path.to_string_lossy() == expected {
+                assert!(matches!(prefixed, Cow::Borrowed(_)));
+            }
+        }
+    }
+
+    #[test]
+    fn strip_long_path_prefix_table() {
+        let cases = [
+            (r"\\?\C:\a\b", r"C:\a\b"),
+            (r"\\?\C:", r"C:"),
+            (r"\\?\UNC\server\share\a", r"\\server\share\a"),
+            (r"\\?\unc\server\share", r"\\server\share"),
+            // Paths that cannot be expressed without the prefix are unchanged.
+            (r"\\?\Volume{1}\a", r"\\?\Volume{1}\a"),
+            (r"\\?\C:a", r"\\?\C:a"),
+            // Paths without a prefix are unchanged.
+            (r"C:\a", r"C:\a"),
+            (r"\\server\share", r"\\server\share"),
+            (r"a\b", r"a\b"),
+        ];
+
+        for (path, expected) in cases {
+            let path = wide(path);
+            assert_eq!(
+                strip_long_path_prefix(&path).to_string_lossy(),
+                expected,
+                "{}",
+                path.display()
+            );
+        }
+        assert!(has_long_path_prefix(u16str!(r"\\?\C:\a")));
+        assert!(!has_long_path_prefix(u16str!(r"\??\C:\a")));
+    }
+
+    #[test]
+    fn long_path_prefix_round_trips() {
+        for path in [r"C:\a\b", r"\\server\share\a"] {
+            let path = wide(path);
+            let prefixed = add_long_path_prefix(&path);
+            assert_eq!(strip_long_path_prefix(&prefixed).as_ref(), path.as_ustr());
+        }
+    }
+
+    #[test]
+    fn normalize_for_comparison_table() {
+        let cases = [
+            (r"C:\a\b", r"C:\A\B"),
+            (r"c:/a//b/", r"C:\A\B"),
+            (r"C:\a\.\b\..\c", r"C:\A\C"),
+            (r"C:\..\a", r"C:\A"),
+            (r"C:\", r"C:\"),
+            (r"C:\a\..", r"C:\"),
+            (r"C:a\..\..\b", r"C:..\B"),
+            (r"\\?\C:\a", r"C:\A"),
+            (r"\\server\\share\a\", r"\\SERVER\SHARE\A"),
+            (r"\\?\UNC\server\share\a", r"\\SERVER\SHARE\A"),
+            (r"\a\..\..", r"\"),
+            (r"a\..\..\b", r"..\B"),
+            (r".\a", r"A"),
+            ("", ""),
+            ("\u{e9}", "\u{c9}"),
+        ];
+
+        for (path, expected) in cases {
+            let normalized = normalize_for_comparison(&wide(path)).to_string_lossy();
+            assert_eq!(normalized, expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn eq_normalized_compares_normalized_paths() {
+        assert!(eq_normalized(
+            u16str!(r"\\?\C:\Windows\System32\..\notepad.exe"),
+            u16str!(r"c:/windows/NOTEPAD.EXE")
+        ));
+        assert!(eq_normalized(
+            u16str!(r"\\?\UNC\server\share\a"),
+            u16str!(r"\\SERVER\share\a\")
+        ));
+        assert!(!eq_normalized(u16str!(r"C:\a"), u16str!(r"D:\a")));
+        assert!(!eq_normalized(u16str!(r"C:\a"), u16str!(r"C:a")));
+        assert!(!eq_normalized(u16str!(r"a\b"), u16str!(r"a\c")));
+    }
+}
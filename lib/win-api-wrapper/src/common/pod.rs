@@ -0,0 +1,38 @@
+use core::mem::MaybeUninit;
+
+/// A plain old data type that can be copied from and to raw memory, e.g. the memory of another process.
+///
+/// # Safety
+///
+/// Implementing this trait for a type that violates any of the following conditions is Undefined Behavior:
+///
+/// * Every bit pattern of `size_of::<Self>()` bytes must be a valid value of the type (e.g. `bool`, `char`,
+///   references and enums are not allowed).
+/// * The type must not own any resource, and it must not rely on the uniqueness of its values (e.g. handles).
+///
+pub unsafe trait Pod: Copy + 'static {}
+
+/// Implements [`Pod`] for every listed type.
+macro_rules! impl_pod {
+    ($($ty:ty), * $(,)?) => {
+        $(
+            // Safety: Every bit pattern is a valid value of a primitive numeric type.
+            unsafe impl Pod for $ty {}
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+// Safety: Every bit pattern of an array is valid if every bit pattern of its element type is valid.
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+// Safety: A zero-sized type has a single valid value.
+unsafe impl Pod for () {}
+
+#[inline]
+/// Creates a value of `T` where every byte is zero.
+pub const fn zeroed<T: Pod>() -> T {
+    // Safety: Every bit pattern, including all zeros, is a valid value of `T`.
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
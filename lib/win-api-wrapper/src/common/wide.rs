@@ -0,0 +1,310 @@
+use crate::win32::core::{Result, Win32Error};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use widestring::error::ContainsNul;
+use windows_sys::core::PCWSTR;
+use windows_sys::Win32::Foundation::ERROR_INVALID_PARAMETER;
+
+pub use widestring::{U16CStr, U16CString, U16Str, U16String};
+
+/// Gets the length of a null-terminated wide string in `u16` elements.
+/// The ending null character is not included in the result.
+///
+/// # Safety
+///
+/// If any of the following conditions are violated, the result is Undefined Behavior:
+///
+/// * `ptr` must be non-null and it must point to the first element of a null-terminated wide string.
+/// * The memory of the string must be readable until the null character.
+///
+pub const unsafe fn len(ptr: PCWSTR) -> usize {
+    let mut len = 0;
+    // Safety: The caller guarantees that every element is readable until the null character.
+    while unsafe { *ptr.add(len) } != 0 {
+        len += 1;
+    }
+
+    len
+}
+
+/// Borrows a null-terminated wide string as a [`U16CStr`] without copying it.
+///
+/// # Safety
+///
+/// If any of the following conditions are violated, the result is Undefined Behavior:
+///
+/// * `ptr` must be non-null and it must point to the first element of a null-terminated wide string.
+/// * The memory of the string must be readable until the null character.
+/// * The string must not be modified or freed during the lifetime `'a`.
+///
+pub unsafe fn from_ptr<'a>(ptr: PCWSTR) -> &'a U16CStr {
+    // Safety: The caller upholds the requirements.
+    unsafe { U16CStr::from_ptr_str(ptr) }
+}
+
+/// Returns a subslice of `slice` that doesn't contain any trailing whitespaces or null characters.
+pub const fn trim_end(mut slice: &[u16]) -> &[u16] {
+    while let Some((last, rest)) = slice.split_last() {
+        match last {
+            0 | 32 | 9..=13 => slice = rest,
+            _ => break,
+        }
+    }
+
+    slice
+}
+
+/// A value that can be passed to Windows API functions as a null-terminated wide string (`PCWSTR`).
+///
+/// # Remarks
+///
+/// * Null-terminated wide strings ([`U16CStr`] and [`U16CString`]) are borrowed without copying.
+/// * Other strings are encoded as UTF-16 and a null character is appended.
+/// * On other targets than Windows [`OsStr`][`std::ffi::OsStr`] and [`Path`][`std::path::Path`] values
+///   are converted lossily like [`OsStr::to_string_lossy`][`std::ffi::OsStr::to_string_lossy`] does.
+///
+pub trait IntoWideCStr {
+    /// Converts the value to a null-terminated wide string.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the conversion fails.
+    ///
+    /// ## Possible errors
+    ///
+    /// * The value contains a null character that is not the terminating one. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+    ///
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>>;
+}
+
+/// Converts the error of a string that contains a null character to a [`Win32Error`].
+fn interior_null_error(_error: ContainsNul<u16>) -> Win32Error {
+    Win32Error::new(ERROR_INVALID_PARAMETER)
+}
+
+impl IntoWideCStr for U16CStr {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl IntoWideCStr for U16CString {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        Ok(Cow::Borrowed(self.as_ucstr()))
+    }
+}
+
+impl IntoWideCStr for U16Str {
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        U16CString::from_ustr(self)
+            .map(Cow::Owned)
+            .map_err(interior_null_error)
+    }
+}
+
+impl IntoWideCStr for U16String {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        self.as_ustr().to_wide_c_str()
+    }
+}
+
+impl IntoWideCStr for str {
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        U16CString::from_str(self)
+            .map(Cow::Owned)
+            .map_err(interior_null_error)
+    }
+}
+
+impl IntoWideCStr for String {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        self.as_str().to_wide_c_str()
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoWideCStr for std::ffi::OsStr {
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        U16CString::from_os_str(self)
+            .map(Cow::Owned)
+            .map_err(interior_null_error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoWideCStr for std::ffi::OsString {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        self.as_os_str().to_wide_c_str()
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoWideCStr for std::path::Path {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        self.as_os_str().to_wide_c_str()
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoWideCStr for std::path::PathBuf {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        self.as_os_str().to_wide_c_str()
+    }
+}
+
+impl<T: IntoWideCStr + ?Sized> IntoWideCStr for &T {
+    #[inline]
+    fn to_wide_c_str(&self) -> Result<Cow<'_, U16CStr>> {
+        (**self).to_wide_c_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use widestring::{u16cstr, u16str};
+
+    /// Encodes `s` as UTF-16 and appends a null character.
+    fn null_terminated(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain([0]).collect()
+    }
+
+    #[test]
+    fn len_counts_every_element() {
+        for s in ["", "a", "abc", "\u{e9}t\u{e9}", "\u{1f600}"] {
+            let wide = null_terminated(s);
+            // Safety: `wide` is null-terminated.
+            assert_eq!(unsafe { len(wide.as_ptr()) }, wide.len() - 1, "{s}");
+        }
+
+        // Reading every other element would skip the null character and stop at the last one.
+        let wide = [u16::from(b'a'), 0, u16::from(b'b'), u16::from(b'c'), 0];
+        // Safety: `wide` is null-terminated.
+        assert_eq!(unsafe { len(wide.as_ptr()) }, 1);
+    }
+
+    #[test]
+    fn from_ptr_borrows_until_the_null_character() {
+        let wide = [u16::from(b'a'), u16::from(b'b'), 0, u16::from(b'c'), 0];
+
+        // Safety: `wide` is null-terminated and it outlives the borrow.
+        let borrowed = unsafe { from_ptr(wide.as_ptr()) };
+
+        assert_eq!(borrowed, u16cstr!("ab"));
+        assert_eq!(borrowed.as_ptr(), wide.as_ptr());
+    }
+
+    #[test]
+    fn trim_end_removes_whitespaces_and_null_characters() {
+        let cases = [
+            ("", ""),
+            ("abc", "abc"),
+            ("abc \t\r\n\u{b}\u{c}\0\0", "abc"),
+            (" \0 ", ""),
+            (" a b ", " a b"),
+            // Other whitespaces are kept.
+            ("a\u{a0}", "a\u{a0}"),
+        ];
+
+        for (s, expected) in cases {
+            let wide: Vec<u16> = s.encode_utf16().collect();
+            let expected: Vec<u16> = expected.encode_utf16().collect();
+            assert_eq!(trim_end(&wide), expected.as_slice(), "{s:?}");
+        }
+    }
+
+    /// Converts `value` and checks that the result is `expected` without the null character.
+    fn assert_converts<T: IntoWideCStr + ?Sized>(value: &T, expected: &str) {
+        let converted = value.to_wide_c_str().unwrap();
+        assert_eq!(
+            converted.as_slice(),
+            U16String::from_str(expected).as_slice()
+        );
+    }
+
+    /// Converts `value` and checks that it is rejected because of an interior null character.
+    fn assert_rejects_interior_null<T: IntoWideCStr + ?Sized>(value: &T) {
+        let error = value.to_wide_c_str().unwrap_err();
+        assert_eq!(error.code(), ERROR_INVALID_PARAMETER);
+    }
+
+    #[test]
+    fn null_terminated_strings_are_borrowed() {
+        let borrowed = u16cstr!("abc");
+        let Ok(Cow::Borrowed(from_borrowed)) = borrowed.to_wide_c_str() else {
+            panic!("a U16CStr was copied");
+        };
+        assert_eq!(from_borrowed.as_ptr(), borrowed.as_ptr());
+
+        let owned = U16CString::from(borrowed);
+        let Ok(Cow::Borrowed(from_owned)) = owned.to_wide_c_str() else {
+            panic!("a U16CString was copied");
+        };
+        assert_eq!(from_owned.as_ptr(), owned.as_ptr());
+
+        let reference = &owned;
+        let Ok(Cow::Borrowed(from_reference)) = <&U16CString>::to_wide_c_str(&reference) else {
+            panic!("a reference to a U16CString was copied");
+        };
+        assert_eq!(from_reference.as_ptr(), owned.as_ptr());
+    }
+
+    #[test]
+    fn other_strings_are_encoded() {
+        assert_converts(u16str!("a\u{e9}"), "a\u{e9}");
+        assert_converts(&U16String::from_str("a\u{e9}"), "a\u{e9}");
+        assert_converts("a\u{1f600}", "a\u{1f600}");
+        assert_converts(&String::from("a\u{1f600}"), "a\u{1f600}");
+        assert_converts(&"", "");
+    }
+
+    #[test]
+    fn interior_null_characters_are_rejected() {
+        assert_rejects_interior_null(u16str!("a\0b"));
+        assert_rejects_interior_null(&U16String::from_str("a\0b"));
+        assert_rejects_interior_null("\0b");
+        assert_rejects_interior_null(&String::from("a\0b"));
+
+        // A trailing null character is the terminating one.
+        assert_converts(u16str!("a\0"), "a");
+        assert_converts("a\0", "a");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn os_strings_and_paths_are_encoded() {
+        use std::ffi::{OsStr, OsString};
+        use std::path::{Path, PathBuf};
+
+        assert_converts(OsStr::new("a\u{e9}"), "a\u{e9}");
+        assert_converts(&OsString::from("a\u{e9}"), "a\u{e9}");
+        assert_converts(Path::new(r"C:\dir\file"), r"C:\dir\file");
+        assert_converts(&PathBuf::from(r"C:\dir\file"), r"C:\dir\file");
+
+        assert_rejects_interior_null(OsStr::new("a\0b"));
+        assert_rejects_interior_null(&OsString::from("a\0b"));
+        assert_rejects_interior_null(Path::new("a\0b"));
+        assert_rejects_interior_null(&PathBuf::from("a\0b"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn outputs_convert_to_os_strings_and_strings() {
+        let valid = U16String::from_str("a\u{e9}\u{1f600}");
+        assert_eq!(valid.to_os_string(), "a\u{e9}\u{1f600}");
+        assert_eq!(valid.to_string().unwrap(), "a\u{e9}\u{1f600}");
+
+        // An unpaired surrogate cannot be converted to a `String` without loss.
+        let unpaired = U16String::from_vec([u16::from(b'a'), 0xD800]);
+        assert!(unpaired.to_string().is_err());
+        assert_eq!(unpaired.to_string_lossy(), "a\u{fffd}");
+    }
+}
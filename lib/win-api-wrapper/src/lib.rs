@@ -1,6 +1,15 @@
 //! A library that uses the [windows-rs] crate to wrap unsafe Windows API calls, making them safer and easier to use.
 //!
 //! [windows-rs]: https://github.com/microsoft/windows-rs
+//!
+//! # Features
+//!
+//! * `std` (enabled by default): Enables integration with the standard library,
+//!   such as [`std::error::Error`] and [`std::io::Error`] support.
+//! * `mock`: Enables [`ScriptedBackend`][`crate::win32::backend::mock::ScriptedBackend`], a scripted fake
+//!   [`Backend`][`crate::win32::backend::Backend`] that can be used to test the `*_in` functions without Windows.
+//! * `async`: Enables [`WaitFuture`][`crate::win32::system::threading::wait::future::WaitFuture`], a [`Future`][`core::future::Future`]
+//!   that completes when a handle is signaled. Implies `std`.
 
 #![warn(
     clippy::await_holding_lock,
@@ -12,7 +21,7 @@
     clippy::debug_assert_with_mut_call,
     clippy::doc_link_with_quotes,
     clippy::doc_markdown,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::empty_line_after_outer_attr,
     clippy::empty_structs_with_brackets,
     clippy::enum_glob_use,
@@ -44,12 +53,10 @@
     clippy::map_err_ignore,
     clippy::map_flatten,
     clippy::map_unwrap_or,
-    clippy::match_on_vec_items,
     clippy::match_same_arms,
     clippy::match_wild_err_arm,
     clippy::match_wildcard_for_single_variants,
     clippy::mem_forget,
-    clippy::mismatched_target_os,
     clippy::missing_const_for_fn,
     clippy::missing_docs_in_private_items,
     clippy::missing_enforced_import_renames,
@@ -80,7 +87,6 @@
     clippy::string_add_assign,
     clippy::string_add,
     clippy::string_lit_as_bytes,
-    clippy::string_to_string,
     clippy::suspicious_operation_groupings,
     clippy::todo,
     clippy::trait_duplication_in_bounds,
@@ -108,6 +114,7 @@
     missing_debug_implementations,
     nonstandard_style
 )]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 #[macro_use]
 extern crate alloc;
@@ -92,7 +92,7 @@ macro_rules! handle_BOOL {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
             if res == 0 {
-                return Err($crate::win32::core::Win32Error::get_last())
+                return Err($crate::win32::core::Win32Error::get_last_from(stringify!($func)))
             }
         }
     };
@@ -101,7 +101,7 @@ macro_rules! handle_BOOL {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
             if res == 0 {
-                let error = $crate::win32::core::Win32Error::get_last();
+                let error = $crate::win32::core::Win32Error::get_last_from(stringify!($func));
                 if error.code == $error_val {
                     return Err(error);
                 }
@@ -115,7 +115,7 @@ macro_rules! handle_BOOL {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
             if res == 0 {
-                Err($crate::win32::core::Win32Error::get_last())
+                Err($crate::win32::core::Win32Error::get_last_from(stringify!($func)))
             } else {
                 Ok($ret_expr)
             }
@@ -139,7 +139,7 @@ macro_rules! handle_BOOL {
             match res as u32 {
                 0 => Ok(Some($ret_expr)),
                 $win_error => Ok(None),
-                _ => Err($crate::win32::core::Win32Error::get_last())
+                _ => Err($crate::win32::core::Win32Error::get_last_from(stringify!($func)))
             }
         }
     };
@@ -152,42 +152,42 @@ macro_rules! call_num {
         {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
-            $crate::handle_int!(Ok => res, res, $error_val)
+            $crate::handle_int!($func: Ok => res, res, $error_val)
         }
     };
     { $func:ident($($arg:expr), *) != $error_val:literal To } => {
         {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
-            $crate::handle_int!(Ok => res, res.to(), $error_val)
+            $crate::handle_int!($func: Ok => res, res.to(), $error_val)
         }
     };
     { $func:ident($($arg:expr), *) != $error_val:literal as $ret_type:ty } => {
         {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
-            $crate::handle_int!(Ok => res, res as $ret_type, $error_val)
+            $crate::handle_int!($func: Ok => res, res as $ret_type, $error_val)
         }
     };
     { $func:ident($($arg:expr), *) != $error_val:expr } => {
         {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
-            $crate::handle_int!(Ok => res, res, $error_val)
+            $crate::handle_int!($func: Ok => res, res, $error_val)
         }
     };
     { $func:ident($($arg:expr), *) == $success_val:literal } => {
         {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res = unsafe { $func($($arg),*) };
-            $crate::handle_int!(Err => res, $success_val)
+            $crate::handle_int!($func: Err => res, $success_val)
         }
     };
     { ($func:ident($($arg:expr), *) == $error_val:literal) => return Error $(;)? } => {
         {
             #[allow(clippy::undocumented_unsafe_blocks)]
             let res_val = unsafe { $func($($arg),*) };
-            $crate::handle_int!(return Error res_val, $error_val)
+            $crate::handle_int!($func: return Error res_val, $error_val)
         }
     };
 }
@@ -195,23 +195,29 @@ macro_rules! call_num {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! handle_int {
-    (Err => $res_val:ident, $success_val:literal) => {
+    ($func:ident: Err => $res_val:ident, $success_val:literal) => {
         if $res_val == $success_val {
             Ok(())
         } else {
-            Err($crate::win32::core::Win32Error::get_last())
+            Err($crate::win32::core::Win32Error::get_last_from(stringify!(
+                $func
+            )))
         }
     };
-    (Ok => $res_val:ident, $ret_expr:expr, $error_val:expr) => {
+    ($func:ident: Ok => $res_val:ident, $ret_expr:expr, $error_val:expr) => {
         if $res_val != $error_val {
             Ok($ret_expr)
         } else {
-            Err($crate::win32::core::Win32Error::get_last())
+            Err($crate::win32::core::Win32Error::get_last_from(stringify!(
+                $func
+            )))
         }
     };
-    (return Error $res_val:ident, $error_val:expr) => {
+    ($func:ident: return Error $res_val:ident, $error_val:expr) => {
         if $res_val == $error_val {
-            return Err($crate::win32::core::Win32Error::get_last());
+            return Err($crate::win32::core::Win32Error::get_last_from(stringify!(
+                $func
+            )));
         } else {
             $res_val
         }
@@ -1,9 +1,13 @@
+/// This module contains the backend abstraction over the raw Windows API calls.
+pub mod backend;
 /// This module contains the Win32 error and result types;
 pub mod core;
 /// `Win32::Foundation`
 pub mod foundation;
 /// `Win32::Security`
 pub mod security;
+/// `Win32::Storage`
+pub mod storage;
 /// `Win32::System`
 pub mod system;
 /*
@@ -0,0 +1,335 @@
+use crate::win32::core::Result;
+use crate::win32::foundation::BorrowedHandle;
+use crate::{call_BOOL, call_num};
+use core::ffi::c_void;
+use core::mem::size_of_val;
+use core::ptr::{self, addr_of_mut};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+    Process32FirstW, Process32NextW, Thread32First, Thread32Next, PROCESSENTRY32W, THREADENTRY32,
+};
+use windows_sys::Win32::System::ProcessStatus::{
+    EnumProcessModulesEx, EnumProcesses, GetMappedFileNameW, GetModuleBaseNameW,
+    GetModuleFileNameExW, ENUM_PROCESS_MODULES_EX_FLAGS,
+};
+use windows_sys::Win32::System::Threading::{GetProcessGroupAffinity, GetThreadSelectedCpuSets};
+
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+
+/// The raw Windows API calls that are used by the functions that contain non-trivial logic
+/// (e.g. buffer growth loops or snapshot iteration).
+///
+/// # Remarks
+///
+/// * Each method is a safe version of the corresponding Windows API function: pointer and length pairs are replaced by slices.
+/// * [`Native`] calls the real Windows API functions and it is used by the regular functions of this crate.
+/// * The `*_in` functions (e.g. [`get_pids_in`][`crate::win32::system::process_status::get_pids_in`]) accept any backend,
+///   so their logic can be exercised without Windows by using a fake implementation like
+///   [`ScriptedBackend`][`crate::win32::backend::mock::ScriptedBackend`] (requires the `mock` feature outside of the tests of this crate).
+///
+pub trait Backend {
+    /// Copies the process identifier for each process in the system to `buffer`
+    /// and returns the number of bytes written. See `EnumProcesses`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails.
+    ///
+    fn enum_processes(&self, buffer: &mut [u32]) -> Result<u32>;
+
+    /// Copies the module handles of a process that match `filter` to `buffer`. See `EnumProcessModulesEx`.
+    ///
+    /// # Remarks
+    ///
+    /// * `bytes_needed` receives the number of bytes that are required to store every module handle.
+    ///   The handles are silently truncated if `buffer` is too small.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails.
+    ///
+    fn enum_process_modules(
+        &self,
+        handle: BorrowedHandle<'_>,
+        buffer: &mut [isize],
+        filter: ENUM_PROCESS_MODULES_EX_FLAGS,
+        bytes_needed: &mut u32,
+    ) -> Result<()>;
+
+    /// Copies the name of the memory-mapped file that contains `address` in the virtual address space of a process
+    /// to `buffer` and returns the length of the name. See `GetMappedFileNameW`.
+    ///
+    /// # Remarks
+    ///
+    /// * If `buffer` is too small, the name is truncated to `buffer.len() - 1` characters and null-terminated,
+    ///   and the truncated length is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails.
+    ///
+    fn get_mapped_file_name(
+        &self,
+        handle: BorrowedHandle<'_>,
+        address: usize,
+        buffer: &mut [u16],
+    ) -> Result<usize>;
+
+    /// Copies the full path of the file that contains a module of a process to `buffer`
+    /// and returns the length of the path. See `GetModuleFileNameExW`.
+    ///
+    /// # Remarks
+    ///
+    /// * If `buffer` is too small, the path is truncated to `buffer.len() - 1` characters and null-terminated,
+    ///   and the truncated length is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails.
+    ///
+    fn get_module_file_name(
+        &self,
+        handle: BorrowedHandle<'_>,
+        module: isize,
+        buffer: &mut [u16],
+    ) -> Result<usize>;
+
+    /// Copies the base name of a module of a process to `buffer` and returns the length of the name.
+    /// See `GetModuleBaseNameW`.
+    ///
+    /// # Remarks
+    ///
+    /// * If `buffer` is too small, the name is truncated to `buffer.len() - 1` characters and null-terminated,
+    ///   and the truncated length is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails.
+    ///
+    fn get_module_base_name(
+        &self,
+        handle: BorrowedHandle<'_>,
+        module: isize,
+        buffer: &mut [u16],
+    ) -> Result<usize>;
+
+    /// Copies the processor group numbers of the process group affinity to `buffer`. See `GetProcessGroupAffinity`.
+    ///
+    /// # Remarks
+    ///
+    /// * `count` receives the number of copied group numbers, or the required buffer length if `buffer` is too small.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails.
+    ///
+    fn get_process_group_affinity(
+        &self,
+        handle: BorrowedHandle<'_>,
+        buffer: &mut [u16],
+        count: &mut u16,
+    ) -> Result<()>;
+
+    /// Copies the explicit CPU Set assignment of a thread to `buffer`. See `GetThreadSelectedCpuSets`.
+    ///
+    /// # Remarks
+    ///
+    /// * `count` receives the number of copied CPU Set identifiers, or the required buffer length if `buffer` is too small.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails.
+    ///
+    fn get_thread_selected_cpu_sets(
+        &self,
+        handle: BorrowedHandle<'_>,
+        buffer: &mut [u32],
+        count: &mut u32,
+    ) -> Result<()>;
+
+    /// Retrieves the first process entry of a toolhelp snapshot. See `Process32FirstW`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails
+    /// or if there are no entries (`ERROR_NO_MORE_FILES`).
+    ///
+    fn process32_first(
+        &self,
+        snapshot: BorrowedHandle<'_>,
+        entry: &mut PROCESSENTRY32W,
+    ) -> Result<()>;
+
+    /// Retrieves the next process entry of a toolhelp snapshot. See `Process32NextW`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails
+    /// or if there are no more entries (`ERROR_NO_MORE_FILES`).
+    ///
+    fn process32_next(
+        &self,
+        snapshot: BorrowedHandle<'_>,
+        entry: &mut PROCESSENTRY32W,
+    ) -> Result<()>;
+
+    /// Retrieves the first thread entry of a toolhelp snapshot. See `Thread32First`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails
+    /// or if there are no entries (`ERROR_NO_MORE_FILES`).
+    ///
+    fn thread32_first(&self, snapshot: BorrowedHandle<'_>, entry: &mut THREADENTRY32)
+        -> Result<()>;
+
+    /// Retrieves the next thread entry of a toolhelp snapshot. See `Thread32Next`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the call fails
+    /// or if there are no more entries (`ERROR_NO_MORE_FILES`).
+    ///
+    fn thread32_next(&self, snapshot: BorrowedHandle<'_>, entry: &mut THREADENTRY32) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// The [`Backend`] that calls the real Windows API functions.
+pub struct Native;
+
+/// Gets a pointer to the first element of `buffer` or a null pointer if `buffer` is empty.
+const fn as_mut_ptr_or_null<T>(buffer: &mut [T]) -> *mut T {
+    if buffer.is_empty() {
+        ptr::null_mut()
+    } else {
+        buffer.as_mut_ptr()
+    }
+}
+
+impl Backend for Native {
+    fn enum_processes(&self, buffer: &mut [u32]) -> Result<u32> {
+        call_BOOL! {
+            EnumProcesses(
+                buffer.as_mut_ptr(),
+                u32::try_from(size_of_val(buffer)).unwrap_or(u32::MAX),
+                addr_of_mut!(bytes_written)
+            ) -> mut bytes_written: u32
+        }
+    }
+
+    fn enum_process_modules(
+        &self,
+        handle: BorrowedHandle<'_>,
+        buffer: &mut [isize],
+        filter: ENUM_PROCESS_MODULES_EX_FLAGS,
+        bytes_needed: &mut u32,
+    ) -> Result<()> {
+        call_BOOL! {
+            EnumProcessModulesEx(
+                handle.as_raw(),
+                as_mut_ptr_or_null(buffer),
+                u32::try_from(size_of_val(buffer)).unwrap_or(u32::MAX),
+                bytes_needed,
+                filter
+            )
+        }
+    }
+
+    fn get_mapped_file_name(
+        &self,
+        handle: BorrowedHandle<'_>,
+        address: usize,
+        buffer: &mut [u16],
+    ) -> Result<usize> {
+        let len = u32::try_from(buffer.len()).unwrap_or(u32::MAX);
+        call_num! {
+            GetMappedFileNameW(
+                handle.as_raw(),
+                address as *const c_void,
+                buffer.as_mut_ptr(),
+                len
+            ) != 0
+        }
+        .map(|len| len as usize)
+    }
+
+    fn get_module_file_name(
+        &self,
+        handle: BorrowedHandle<'_>,
+        module: isize,
+        buffer: &mut [u16],
+    ) -> Result<usize> {
+        let len = u32::try_from(buffer.len()).unwrap_or(u32::MAX);
+        call_num! {
+            GetModuleFileNameExW(handle.as_raw(), module, buffer.as_mut_ptr(), len) != 0
+        }
+        .map(|len| len as usize)
+    }
+
+    fn get_module_base_name(
+        &self,
+        handle: BorrowedHandle<'_>,
+        module: isize,
+        buffer: &mut [u16],
+    ) -> Result<usize> {
+        let len = u32::try_from(buffer.len()).unwrap_or(u32::MAX);
+        call_num! {
+            GetModuleBaseNameW(handle.as_raw(), module, buffer.as_mut_ptr(), len) != 0
+        }
+        .map(|len| len as usize)
+    }
+
+    fn get_process_group_affinity(
+        &self,
+        handle: BorrowedHandle<'_>,
+        buffer: &mut [u16],
+        count: &mut u16,
+    ) -> Result<()> {
+        *count = u16::try_from(buffer.len()).unwrap_or(u16::MAX);
+        call_BOOL! { GetProcessGroupAffinity(handle.as_raw(), count, as_mut_ptr_or_null(buffer)) }
+    }
+
+    fn get_thread_selected_cpu_sets(
+        &self,
+        handle: BorrowedHandle<'_>,
+        buffer: &mut [u32],
+        count: &mut u32,
+    ) -> Result<()> {
+        call_BOOL! {
+            GetThreadSelectedCpuSets(
+                handle.as_raw(),
+                as_mut_ptr_or_null(buffer),
+                u32::try_from(buffer.len()).unwrap_or(u32::MAX),
+                count
+            )
+        }
+    }
+
+    fn process32_first(
+        &self,
+        snapshot: BorrowedHandle<'_>,
+        entry: &mut PROCESSENTRY32W,
+    ) -> Result<()> {
+        call_BOOL! { Process32FirstW(snapshot.as_raw(), entry) }
+    }
+
+    fn process32_next(
+        &self,
+        snapshot: BorrowedHandle<'_>,
+        entry: &mut PROCESSENTRY32W,
+    ) -> Result<()> {
+        call_BOOL! { Process32NextW(snapshot.as_raw(), entry) }
+    }
+
+    fn thread32_first(
+        &self,
+        snapshot: BorrowedHandle<'_>,
+        entry: &mut THREADENTRY32,
+    ) -> Result<()> {
+        call_BOOL! { Thread32First(snapshot.as_raw(), entry) }
+    }
+
+    fn thread32_next(&self, snapshot: BorrowedHandle<'_>, entry: &mut THREADENTRY32) -> Result<()> {
+        call_BOOL! { Thread32Next(snapshot.as_raw(), entry) }
+    }
+}
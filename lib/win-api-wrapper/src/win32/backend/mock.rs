@@ -0,0 +1,333 @@
+//! A scripted [`Backend`] that can be used to exercise the logic of the `*_in` functions without Windows.
+
+use super::Backend;
+use crate::win32::core::{Result, Win32Error};
+use crate::win32::foundation::{
+    BorrowedHandle, KernelObject, ObjectHandle, OwnedHandle, CURRENT_PROCESS_PSEUDO_HANDLE,
+};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::mem::size_of;
+use windows_sys::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, ERROR_NO_MORE_FILES};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::{PROCESSENTRY32W, THREADENTRY32};
+use windows_sys::Win32::System::ProcessStatus::ENUM_PROCESS_MODULES_EX_FLAGS;
+
+/// Creates a handle that can be passed to the `*_in` functions together with a [`ScriptedBackend`].
+///
+/// # Remarks
+///
+/// * The handle wraps a pseudo handle value, so it is never closed and no Windows API function is called on drop.
+///
+/// # Safety
+///
+/// If any of the following conditions are violated, the result is Undefined Behavior:
+///
+/// * The handle must only be passed to the `*_in` functions together with a [`ScriptedBackend`].
+///   It must not be used with the [`Native`][`super::Native`] backend or with any other function of this crate,
+///   because it refers to the current process and it has none of the access rights of `RIGHTS`.
+///
+pub const unsafe fn fake_handle<O: KernelObject, const RIGHTS: u32>() -> ObjectHandle<O, RIGHTS> {
+    // Safety: Pseudo handles are never closed and the caller only passes the handle to the scripted backend,
+    // which ignores the handle value.
+    unsafe { ObjectHandle::from_owned(OwnedHandle::from_raw(CURRENT_PROCESS_PSEUDO_HANDLE)) }
+}
+
+#[derive(Clone)]
+/// The scripted outcome of a single [`Backend`] call.
+///
+/// # Remarks
+///
+/// * Each step describes what the system "contains" at the time of the call.
+///   The buffer semantics (truncation, required length) are simulated like the real functions do.
+///
+pub enum Step {
+    /// `EnumProcesses` sees the specified process identifiers. The result is silently truncated if the buffer is too small.
+    EnumProcesses(Result<Vec<u32>>),
+    /// `EnumProcessModulesEx` sees the specified module handles. The result is silently truncated if the buffer is too small,
+    /// and the required size is reported in both cases.
+    EnumProcessModules(Result<Vec<isize>>),
+    /// `GetMappedFileNameW` sees the specified name. If the buffer is too small, the name is truncated
+    /// to `buffer.len() - 1` characters and null-terminated, and the truncated length is returned.
+    GetMappedFileName(Result<Vec<u16>>),
+    /// `GetModuleFileNameExW` sees the specified path. If the buffer is too small, the path is truncated
+    /// to `buffer.len() - 1` characters and null-terminated, and the truncated length is returned.
+    GetModuleFileName(Result<Vec<u16>>),
+    /// `GetModuleBaseNameW` sees the specified name. If the buffer is too small, the name is truncated
+    /// to `buffer.len() - 1` characters and null-terminated, and the truncated length is returned.
+    GetModuleBaseName(Result<Vec<u16>>),
+    /// `GetProcessGroupAffinity` sees the specified group numbers.
+    /// The call fails with `ERROR_INSUFFICIENT_BUFFER` if the buffer is too small.
+    GetProcessGroupAffinity(Result<Vec<u16>>),
+    /// `GetThreadSelectedCpuSets` sees the specified CPU Set identifiers.
+    /// The call fails with `ERROR_INSUFFICIENT_BUFFER` if the buffer is too small.
+    GetThreadSelectedCpuSets(Result<Vec<u32>>),
+    /// `Process32FirstW` yields the specified entry. [`None`] fails with `ERROR_NO_MORE_FILES`.
+    Process32First(Result<Option<PROCESSENTRY32W>>),
+    /// `Process32NextW` yields the specified entry. [`None`] fails with `ERROR_NO_MORE_FILES`.
+    Process32Next(Result<Option<PROCESSENTRY32W>>),
+    /// `Thread32First` yields the specified entry. [`None`] fails with `ERROR_NO_MORE_FILES`.
+    Thread32First(Result<Option<THREADENTRY32>>),
+    /// `Thread32Next` yields the specified entry. [`None`] fails with `ERROR_NO_MORE_FILES`.
+    Thread32Next(Result<Option<THREADENTRY32>>),
+}
+
+impl Step {
+    /// Gets the name of the Windows API function that is scripted by the step.
+    const fn function(&self) -> &'static str {
+        match self {
+            Self::EnumProcesses(_) => "EnumProcesses",
+            Self::EnumProcessModules(_) => "EnumProcessModulesEx",
+            Self::GetMappedFileName(_) => "GetMappedFileNameW",
+            Self::GetModuleFileName(_) => "GetModuleFileNameExW",
+            Self::GetModuleBaseName(_) => "GetModuleBaseNameW",
+            Self::GetProcessGroupAffinity(_) => "GetProcessGroupAffinity",
+            Self::GetThreadSelectedCpuSets(_) => "GetThreadSelectedCpuSets",
+            Self::Process32First(_) => "Process32FirstW",
+            Self::Process32Next(_) => "Process32NextW",
+            Self::Thread32First(_) => "Thread32First",
+            Self::Thread32Next(_) => "Thread32Next",
+        }
+    }
+}
+
+impl core::fmt::Debug for Step {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // The snapshot entries don't implement `Debug`, so only the scripted function is shown.
+        f.debug_tuple(self.function()).finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Default)]
+/// A [`Backend`] that replays a script of [`Step`]s in order.
+///
+/// # Panics
+///
+/// The backend methods panic if the script is exhausted or if the next step scripts a different function than the one being called.
+///
+pub struct ScriptedBackend {
+    /// The remaining steps.
+    steps: RefCell<VecDeque<Step>>,
+    /// The names of the called functions in call order.
+    calls: RefCell<Vec<&'static str>>,
+}
+
+impl ScriptedBackend {
+    #[inline]
+    /// Creates a new [`ScriptedBackend`] with an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `step` to the script.
+    #[must_use]
+    pub fn then(self, step: Step) -> Self {
+        self.steps.borrow_mut().push_back(step);
+        self
+    }
+
+    /// Determines whether every scripted step has been consumed.
+    pub fn is_finished(&self) -> bool {
+        self.steps.borrow().is_empty()
+    }
+
+    /// Gets the names of the called functions in call order.
+    pub fn calls(&self) -> Vec<&'static str> {
+        self.calls.borrow().clone()
+    }
+
+    /// Pops the next step and checks that it scripts `function`.
+    fn next_step(&self, function: &'static str) -> Step {
+        self.calls.borrow_mut().push(function);
+        let step =
+            self.steps.borrow_mut().pop_front().unwrap_or_else(|| {
+                panic!("unexpected call to {function}: the script is exhausted")
+            });
+        assert_eq!(
+            step.function(),
+            function,
+            "unexpected call to {function}: the next step scripts {}",
+            step.function()
+        );
+        step
+    }
+}
+
+/// Simulates a call that copies `values` to `buffer` and fails with `ERROR_INSUFFICIENT_BUFFER` if `buffer` is too small.
+fn copy_or_insufficient<T: Copy>(
+    function: &'static str,
+    values: &[T],
+    buffer: &mut [T],
+) -> Result<()> {
+    if buffer.len() < values.len() {
+        return Err(Win32Error::new(ERROR_INSUFFICIENT_BUFFER).with_function(function));
+    }
+
+    buffer[..values.len()].copy_from_slice(values);
+    Ok(())
+}
+
+/// Simulates a call that copies `string` to `buffer`, truncates it to `buffer.len() - 1` characters if `buffer` is too small,
+/// null-terminates it and returns the copied length.
+fn copy_truncated(string: &[u16], buffer: &mut [u16]) -> usize {
+    let len = string.len().min(buffer.len().saturating_sub(1));
+    buffer[..len].copy_from_slice(&string[..len]);
+    if let Some(terminator) = buffer.get_mut(len) {
+        *terminator = 0;
+    }
+    len
+}
+
+/// Simulates a call that yields a snapshot entry and fails with `ERROR_NO_MORE_FILES` if there are no more entries.
+fn yield_entry<T>(function: &'static str, result: Result<Option<T>>, entry: &mut T) -> Result<()> {
+    result?.map_or_else(
+        || Err(Win32Error::new(ERROR_NO_MORE_FILES).with_function(function)),
+        |value| {
+            *entry = value;
+            Ok(())
+        },
+    )
+}
+
+impl Backend for ScriptedBackend {
+    fn enum_processes(&self, buffer: &mut [u32]) -> Result<u32> {
+        let Step::EnumProcesses(result) = self.next_step("EnumProcesses") else {
+            unreachable!()
+        };
+        let pids = result?;
+        let len = pids.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&pids[..len]);
+        Ok((len * size_of::<u32>()) as u32)
+    }
+
+    fn enum_process_modules(
+        &self,
+        _handle: BorrowedHandle<'_>,
+        buffer: &mut [isize],
+        _filter: ENUM_PROCESS_MODULES_EX_FLAGS,
+        bytes_needed: &mut u32,
+    ) -> Result<()> {
+        let Step::EnumProcessModules(result) = self.next_step("EnumProcessModulesEx") else {
+            unreachable!()
+        };
+        let modules = result?;
+        let len = modules.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&modules[..len]);
+        *bytes_needed = (modules.len() * size_of::<isize>()) as u32;
+        Ok(())
+    }
+
+    fn get_mapped_file_name(
+        &self,
+        _handle: BorrowedHandle<'_>,
+        _address: usize,
+        buffer: &mut [u16],
+    ) -> Result<usize> {
+        let Step::GetMappedFileName(result) = self.next_step("GetMappedFileNameW") else {
+            unreachable!()
+        };
+        Ok(copy_truncated(&result?, buffer))
+    }
+
+    fn get_module_file_name(
+        &self,
+        _handle: BorrowedHandle<'_>,
+        _module: isize,
+        buffer: &mut [u16],
+    ) -> Result<usize> {
+        let Step::GetModuleFileName(result) = self.next_step("GetModuleFileNameExW") else {
+            unreachable!()
+        };
+        Ok(copy_truncated(&result?, buffer))
+    }
+
+    fn get_module_base_name(
+        &self,
+        _handle: BorrowedHandle<'_>,
+        _module: isize,
+        buffer: &mut [u16],
+    ) -> Result<usize> {
+        let Step::GetModuleBaseName(result) = self.next_step("GetModuleBaseNameW") else {
+            unreachable!()
+        };
+        Ok(copy_truncated(&result?, buffer))
+    }
+
+    fn get_process_group_affinity(
+        &self,
+        _handle: BorrowedHandle<'_>,
+        buffer: &mut [u16],
+        count: &mut u16,
+    ) -> Result<()> {
+        let function = "GetProcessGroupAffinity";
+        let Step::GetProcessGroupAffinity(result) = self.next_step(function) else {
+            unreachable!()
+        };
+        let groups = result?;
+        *count = groups.len() as u16;
+        copy_or_insufficient(function, &groups, buffer)
+    }
+
+    fn get_thread_selected_cpu_sets(
+        &self,
+        _handle: BorrowedHandle<'_>,
+        buffer: &mut [u32],
+        count: &mut u32,
+    ) -> Result<()> {
+        let function = "GetThreadSelectedCpuSets";
+        let Step::GetThreadSelectedCpuSets(result) = self.next_step(function) else {
+            unreachable!()
+        };
+        let cpu_set_ids = result?;
+        *count = cpu_set_ids.len() as u32;
+        copy_or_insufficient(function, &cpu_set_ids, buffer)
+    }
+
+    fn process32_first(
+        &self,
+        _snapshot: BorrowedHandle<'_>,
+        entry: &mut PROCESSENTRY32W,
+    ) -> Result<()> {
+        let function = "Process32FirstW";
+        let Step::Process32First(result) = self.next_step(function) else {
+            unreachable!()
+        };
+        yield_entry(function, result, entry)
+    }
+
+    fn process32_next(
+        &self,
+        _snapshot: BorrowedHandle<'_>,
+        entry: &mut PROCESSENTRY32W,
+    ) -> Result<()> {
+        let function = "Process32NextW";
+        let Step::Process32Next(result) = self.next_step(function) else {
+            unreachable!()
+        };
+        yield_entry(function, result, entry)
+    }
+
+    fn thread32_first(
+        &self,
+        _snapshot: BorrowedHandle<'_>,
+        entry: &mut THREADENTRY32,
+    ) -> Result<()> {
+        let function = "Thread32First";
+        let Step::Thread32First(result) = self.next_step(function) else {
+            unreachable!()
+        };
+        yield_entry(function, result, entry)
+    }
+
+    fn thread32_next(
+        &self,
+        _snapshot: BorrowedHandle<'_>,
+        entry: &mut THREADENTRY32,
+    ) -> Result<()> {
+        let function = "Thread32Next";
+        let Step::Thread32Next(result) = self.next_step(function) else {
+            unreachable!()
+        };
+        yield_entry(function, result, entry)
+    }
+}
@@ -1,32 +1,271 @@
-use super::{
-    foundation::get_last_error,
-    system::diagnostics::debug::{format_message, FormatMessagetOptions, System},
+#[cfg(windows)]
+use super::system::diagnostics::debug::{format_message, FormatMessagetOptions, System};
+use super::{foundation::get_last_error, system::system_services::LangId};
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+use core::sync::atomic::{AtomicU16, Ordering};
+use error_names::ERROR_NAMES;
+use windows_sys::core::HRESULT;
+#[cfg(windows)]
+use windows_sys::Win32::Foundation::RtlNtStatusToDosError;
+use windows_sys::Win32::Foundation::{
+    ERROR_ACCESS_DENIED, ERROR_ALREADY_EXISTS, ERROR_BAD_ARGUMENTS, ERROR_BAD_LENGTH,
+    ERROR_BROKEN_PIPE, ERROR_BUFFER_OVERFLOW, ERROR_BUSY, ERROR_CALL_NOT_IMPLEMENTED,
+    ERROR_COMMITMENT_LIMIT, ERROR_ELEVATION_REQUIRED, ERROR_FILE_EXISTS, ERROR_FILE_NOT_FOUND,
+    ERROR_HANDLE_EOF, ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_FLAGS, ERROR_INVALID_FUNCTION,
+    ERROR_INVALID_HANDLE, ERROR_INVALID_NAME, ERROR_INVALID_PARAMETER, ERROR_INVALID_THREAD_ID,
+    ERROR_INVALID_WINDOW_HANDLE, ERROR_LOCK_VIOLATION, ERROR_MOD_NOT_FOUND, ERROR_MORE_DATA,
+    ERROR_MUI_FILE_NOT_FOUND, ERROR_NOT_ENOUGH_MEMORY, ERROR_NOT_FOUND, ERROR_NOT_SUPPORTED,
+    ERROR_NO_DATA, ERROR_NO_MORE_FILES, ERROR_NO_MORE_ITEMS, ERROR_OPERATION_ABORTED,
+    ERROR_OUTOFMEMORY, ERROR_PARTIAL_COPY, ERROR_PATH_NOT_FOUND, ERROR_PIPE_BUSY,
+    ERROR_PRIVILEGE_NOT_HELD, ERROR_PROC_NOT_FOUND, ERROR_RESOURCE_LANG_NOT_FOUND,
+    ERROR_SEM_TIMEOUT, ERROR_SHARING_VIOLATION, ERROR_SUCCESS, ERROR_TIMEOUT, FACILITY_NTWIN32,
+    NTSTATUS, WAIT_TIMEOUT,
 };
-use widestring::U16String;
-use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+use windows_sys::Win32::System::Diagnostics::Debug::{FACILITY_NT_BIT, FACILITY_WIN32};
 
-/// The result of an error-prone Win32 API call.
-pub type Result<T> = core::result::Result<T, Win32Error>;
+mod error_names;
+
+/// The result of an error-prone Windows API call.
+///
+/// # Remarks
+///
+/// * The error type is [`Win32Error`] by default, but it can be any other error type like [`Error`], [`HResult`] or [`NtStatus`].
+///
+pub type Result<T, E = Win32Error> = core::result::Result<T, E>;
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A category of [`Win32Error`]s. See [`Win32Error::kind`].
+pub enum ErrorKind {
+    /// The specified object (e.g. a file or a module) doesn't exist.
+    NotFound,
+    /// The caller doesn't have the required access rights or privileges.
+    PermissionDenied,
+    /// The specified handle is invalid.
+    InvalidHandle,
+    /// One of the arguments is invalid, e.g. the specified process or thread identifier doesn't exist.
+    InvalidParameter,
+    /// The specified buffer is too small to hold the result.
+    InsufficientBuffer,
+    /// There are no more items to enumerate.
+    NoMoreItems,
+    /// The operation timed out.
+    Timeout,
+    /// The object already exists.
+    AlreadyExists,
+    /// There is not enough memory to complete the operation.
+    OutOfMemory,
+    /// The operation is not supported.
+    Unsupported,
+    /// Only part of a memory read or write request was completed.
+    PartialCopy,
+    /// The resource is in use.
+    Busy,
+    /// The operation was aborted.
+    Aborted,
+    /// The pipe has been ended or closed.
+    BrokenPipe,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Gets the [`ErrorKind`] of the specified Win32 error code.
+    pub const fn from_code(code: u32) -> Self {
+        match code {
+            ERROR_FILE_NOT_FOUND
+            | ERROR_PATH_NOT_FOUND
+            | ERROR_NOT_FOUND
+            | ERROR_MOD_NOT_FOUND
+            | ERROR_PROC_NOT_FOUND
+            | ERROR_RESOURCE_LANG_NOT_FOUND
+            | ERROR_MUI_FILE_NOT_FOUND => Self::NotFound,
+            ERROR_ACCESS_DENIED | ERROR_PRIVILEGE_NOT_HELD | ERROR_ELEVATION_REQUIRED => {
+                Self::PermissionDenied
+            }
+            ERROR_INVALID_HANDLE | ERROR_INVALID_WINDOW_HANDLE => Self::InvalidHandle,
+            ERROR_INVALID_PARAMETER
+            | ERROR_BAD_ARGUMENTS
+            | ERROR_INVALID_FLAGS
+            | ERROR_INVALID_NAME
+            | ERROR_INVALID_THREAD_ID => Self::InvalidParameter,
+            ERROR_INSUFFICIENT_BUFFER
+            | ERROR_MORE_DATA
+            | ERROR_BUFFER_OVERFLOW
+            | ERROR_BAD_LENGTH => Self::InsufficientBuffer,
+            ERROR_NO_MORE_ITEMS | ERROR_NO_MORE_FILES | ERROR_HANDLE_EOF => Self::NoMoreItems,
+            ERROR_TIMEOUT | WAIT_TIMEOUT | ERROR_SEM_TIMEOUT => Self::Timeout,
+            ERROR_ALREADY_EXISTS | ERROR_FILE_EXISTS => Self::AlreadyExists,
+            ERROR_NOT_ENOUGH_MEMORY | ERROR_OUTOFMEMORY | ERROR_COMMITMENT_LIMIT => {
+                Self::OutOfMemory
+            }
+            ERROR_NOT_SUPPORTED | ERROR_CALL_NOT_IMPLEMENTED | ERROR_INVALID_FUNCTION => {
+                Self::Unsupported
+            }
+            ERROR_PARTIAL_COPY => Self::PartialCopy,
+            ERROR_BUSY | ERROR_PIPE_BUSY | ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION => {
+                Self::Busy
+            }
+            ERROR_OPERATION_ABORTED => Self::Aborted,
+            ERROR_BROKEN_PIPE | ERROR_NO_DATA => Self::BrokenPipe,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ErrorKind> for std::io::ErrorKind {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::NotFound => Self::NotFound,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            ErrorKind::InvalidHandle | ErrorKind::InvalidParameter => Self::InvalidInput,
+            ErrorKind::NoMoreItems => Self::UnexpectedEof,
+            ErrorKind::Timeout => Self::TimedOut,
+            ErrorKind::AlreadyExists => Self::AlreadyExists,
+            ErrorKind::OutOfMemory => Self::OutOfMemory,
+            ErrorKind::Unsupported => Self::Unsupported,
+            ErrorKind::Busy => Self::ResourceBusy,
+            ErrorKind::Aborted => Self::Interrupted,
+            ErrorKind::BrokenPipe => Self::BrokenPipe,
+            ErrorKind::InsufficientBuffer | ErrorKind::PartialCopy | ErrorKind::Other => {
+                Self::Other
+            }
+        }
+    }
+}
+
+/// The process-wide language of the error messages. See [`Win32Error::set_message_language`].
+static MESSAGE_LANG_ID: AtomicU16 = AtomicU16::new(LangId::NEUTRAL.value());
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+/// Additional information about the failed Windows API call that is attached to a [`Win32Error`].
+///
+/// # Remarks
+///
+/// * The name of the failed function is not part of the context, it is stored inline in the [`Win32Error`]
+///   (see [`Win32Error::function`]), so attaching it never allocates.
+/// * Additional messages can be attached by using [`with_context`][`Win32Error::with_context`] or [`ResultExt`].
+///
+pub struct ErrorContext {
+    /// The handle that was passed to the failed function.
+    handle: Option<isize>,
+    /// The identifier of the process that was targeted by the failed function.
+    process_id: Option<u32>,
+    /// The identifier of the thread that was targeted by the failed function.
+    thread_id: Option<u32>,
+    /// The attached messages from the innermost to the outermost one.
+    messages: Vec<Cow<'static, str>>,
+}
+
+impl ErrorContext {
+    /// An empty context that is used to display errors that only have a function name.
+    const EMPTY: Self = Self {
+        handle: None,
+        process_id: None,
+        thread_id: None,
+        messages: Vec::new(),
+    };
+
+    #[inline]
+    /// Gets the raw value of the handle that was passed to the failed function.
+    pub const fn handle(&self) -> Option<isize> {
+        self.handle
+    }
+
+    #[inline]
+    /// Gets the identifier of the process that was targeted by the failed function.
+    pub const fn process_id(&self) -> Option<u32> {
+        self.process_id
+    }
+
+    #[inline]
+    /// Gets the identifier of the thread that was targeted by the failed function.
+    pub const fn thread_id(&self) -> Option<u32> {
+        self.thread_id
+    }
+
+    /// Gets an iterator over the attached messages from the innermost to the outermost one.
+    pub fn messages(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.messages.iter().map(AsRef::as_ref)
+    }
+
+    /// Writes the messages, the name of the failed function and the identifiers, in this order.
+    fn fmt_with_function(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+        function: Option<&'static str>,
+    ) -> core::fmt::Result {
+        let mut separator = "";
+        for message in self.messages.iter().rev() {
+            write!(f, "{separator}{message}")?;
+            separator = ": ";
+        }
+
+        if let Some(function) = function {
+            write!(f, "{separator}{function} failed")?;
+            separator = " ";
+        }
+
+        let mut open = "(";
+        if let Some(handle) = self.handle {
+            write!(f, "{separator}{open}handle: {handle:#X}")?;
+            (separator, open) = (", ", "");
+        }
+        if let Some(process_id) = self.process_id {
+            write!(f, "{separator}{open}process id: {process_id}")?;
+            (separator, open) = (", ", "");
+        }
+        if let Some(thread_id) = self.thread_id {
+            write!(f, "{separator}{open}thread id: {thread_id}")?;
+            open = "";
+        }
+
+        if open.is_empty() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Display for ErrorContext {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.fmt_with_function(f, None)
+    }
+}
 
 #[derive(Debug, Clone)]
 /// Repersents a Win32 error.
+///
+/// # Remarks
+///
+/// * Only the error code, the name of the failed function and the optional [`ErrorContext`] are stored.
+///   The context is boxed, so errors without handles, process or thread identifiers or messages never allocate. The error message is retrieved from the system message-table
+///   when it is requested by [`message`][`Win32Error::message`] or when the error is displayed.
+/// * Equality and hashing only take the error code into account, so errors with different contexts are considered equal.
+///
 pub struct Win32Error {
     /// The Win32 error code
     pub(crate) code: u32,
-    pub(crate) message: Box<str>,
+    /// The name of the Windows API function that failed.
+    function: Option<&'static str>,
+    /// The additional context of the failed call.
+    context: Option<Box<ErrorContext>>,
 }
 
 impl Win32Error {
-    /// Error message that is used when [`format_message`] fails to retreive the error message using the error code.
+    /// Error message that is used when [`format_message`][`crate::win32::system::diagnostics::debug::format_message`] fails to retreive the error message using the error code.
     pub const FORMAT_MESSAGE_ERROR_MESSAGE: &'static str = "Failed to retrieve the error message!";
 
+    #[inline]
     /// Creates a new [`Win32Error`] from the specified error code.
-    pub fn new(code: u32) -> Self {
-        let message = format_message(System, code, 0, None, FormatMessagetOptions::All)
-            .unwrap_or(U16String::from_str(Self::FORMAT_MESSAGE_ERROR_MESSAGE))
-            .to_string_lossy()
-            .into_boxed_str();
-        Self { code, message }
+    pub const fn new(code: u32) -> Self {
+        Self {
+            code,
+            function: None,
+            context: None,
+        }
     }
 
     #[inline]
@@ -36,9 +275,138 @@ impl Win32Error {
     }
 
     #[inline]
-    /// Gets the error message that is associated with from a system message-table.
-    pub const fn message(&self) -> &str {
-        &self.message
+    /// Gets the name of the Windows API function that failed (e.g. `OpenProcessToken`).
+    pub const fn function(&self) -> Option<&'static str> {
+        self.function
+    }
+
+    #[inline]
+    /// Gets the [`ErrorContext`] of the error.
+    /// If no context is attached to the error, the result is [`None`].
+    pub fn context(&self) -> Option<&ErrorContext> {
+        self.context.as_deref()
+    }
+
+    #[inline]
+    /// Attaches the name of the Windows API function that failed.
+    pub const fn with_function(mut self, function: &'static str) -> Self {
+        self.function = Some(function);
+        self
+    }
+
+    /// Attaches the raw value of the handle that was passed to the failed function.
+    pub fn with_handle(mut self, handle: isize) -> Self {
+        self.context_mut().handle = Some(handle);
+        self
+    }
+
+    /// Attaches the identifier of the process that was targeted by the failed function.
+    pub fn with_process_id(mut self, process_id: u32) -> Self {
+        self.context_mut().process_id = Some(process_id);
+        self
+    }
+
+    /// Attaches the identifier of the thread that was targeted by the failed function.
+    pub fn with_thread_id(mut self, thread_id: u32) -> Self {
+        self.context_mut().thread_id = Some(thread_id);
+        self
+    }
+
+    /// Attaches a message that describes what was being done when the error occurred.
+    ///
+    /// # Remarks
+    ///
+    /// * Messages form a chain: the last attached message is the outermost one and it is displayed first.
+    ///
+    pub fn with_context<M>(mut self, message: M) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        self.context_mut().messages.push(message.into());
+        self
+    }
+
+    /// Gets the [`ErrorContext`] of the error and creates an empty one if it doesn't exist yet.
+    fn context_mut(&mut self) -> &mut ErrorContext {
+        self.context.get_or_insert_with(Box::default)
+    }
+
+    /// Gets the error message that is associated with the error code from the system message-table
+    /// in the process-wide message language (see [`set_message_language`][`Win32Error::set_message_language`]).
+    /// If the message cannot be retrieved, the result is [`FORMAT_MESSAGE_ERROR_MESSAGE`][`Win32Error::FORMAT_MESSAGE_ERROR_MESSAGE`].
+    ///
+    /// # Remarks
+    ///
+    /// * The message is formatted on each call.
+    ///
+    #[inline]
+    pub fn message(&self) -> String {
+        self.message_in(Self::message_language())
+    }
+
+    /// Gets the error message that is associated with the error code from the system message-table in the specified language.
+    /// If the message cannot be retrieved, the result is [`FORMAT_MESSAGE_ERROR_MESSAGE`][`Win32Error::FORMAT_MESSAGE_ERROR_MESSAGE`].
+    ///
+    /// # Remarks
+    ///
+    /// * If the message table of the specified language is not available, the message is retrieved in English ([`LangId::ENGLISH_US`]).
+    ///   See [`format_message`][`crate::win32::system::diagnostics::debug::format_message`].
+    /// * The message is formatted on each call.
+    /// * The system message-table is only available on Windows, so the result is always
+    ///   [`FORMAT_MESSAGE_ERROR_MESSAGE`][`Win32Error::FORMAT_MESSAGE_ERROR_MESSAGE`] on other targets.
+    ///
+    pub fn message_in(&self, lang_id: LangId) -> String {
+        #[cfg(not(windows))]
+        let message = {
+            let _ = lang_id;
+            Err::<widestring::U16String, _>(Self::new(ERROR_NOT_SUPPORTED))
+        };
+        #[cfg(windows)]
+        let message = format_message(System, self.code, lang_id, None, FormatMessagetOptions::All);
+        message.map_or_else(
+            |_| String::from(Self::FORMAT_MESSAGE_ERROR_MESSAGE),
+            |message| message.to_string_lossy(),
+        )
+    }
+
+    /// Sets the process-wide language that is used by [`message`][`Win32Error::message`] and when the error is displayed.
+    ///
+    /// # Remarks
+    ///
+    /// * The default language is [`LangId::NEUTRAL`], which means that the message is searched for
+    ///   in the language neutral, thread, user default and system default languages (in this order).
+    /// * Use [`LangId::ENGLISH_US`] to get the same messages regardless of the system language.
+    ///
+    #[inline]
+    pub fn set_message_language(lang_id: LangId) {
+        MESSAGE_LANG_ID.store(lang_id.value(), Ordering::Relaxed);
+    }
+
+    #[inline]
+    /// Gets the process-wide language that is used by [`message`][`Win32Error::message`] and when the error is displayed.
+    pub fn message_language() -> LangId {
+        LangId::from_raw(MESSAGE_LANG_ID.load(Ordering::Relaxed))
+    }
+
+    #[inline]
+    /// Gets the [`ErrorKind`] of the error code.
+    pub const fn kind(&self) -> ErrorKind {
+        ErrorKind::from_code(self.code)
+    }
+
+    /// Gets the symbolic name of the error code (e.g. `ERROR_ACCESS_DENIED`).
+    /// If the error code is not a documented Win32 error code, the result is [`None`].
+    ///
+    /// # Remarks
+    ///
+    /// * Unlike [`message`][`Win32Error::message`], the name doesn't depend on the system language
+    ///   and it is available even if the error message cannot be retrieved.
+    ///
+    pub fn name(&self) -> Option<&'static str> {
+        ERROR_NAMES
+            .binary_search_by_key(&self.code, |&(code, _)| code)
+            .ok()
+            .map(|index| ERROR_NAMES[index].1)
     }
 
     #[inline]
@@ -54,14 +422,753 @@ impl Win32Error {
     }
 
     #[inline]
-    /// Gets the calling thread's last [`Win32Error`].
-    pub(crate) fn get_last() -> Self {
-        Self::new(get_last_error())
+    /// Gets the calling thread's last [`Win32Error`] and attaches the name of the function that failed.
+    pub(crate) fn get_last_from(function: &'static str) -> Self {
+        Self::new(get_last_error()).with_function(function)
+    }
+}
+
+impl PartialEq for Win32Error {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.code == other.code
+    }
+}
+
+impl Eq for Win32Error {}
+
+impl core::hash::Hash for Win32Error {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.code.hash(state);
+    }
+}
+
+impl From<u32> for Win32Error {
+    #[inline]
+    fn from(code: u32) -> Self {
+        Self::new(code)
     }
 }
 
 impl core::fmt::Display for Win32Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Win32Error: {} -> {}", self.code, self.message)
+        if self.function.is_some() || self.context.is_some() {
+            self.context
+                .as_deref()
+                .unwrap_or(&ErrorContext::EMPTY)
+                .fmt_with_function(f, self.function)?;
+            write!(f, ": ")?;
+        }
+
+        match self.name() {
+            Some(name) => write!(
+                f,
+                "Win32Error: {} ({}) -> {}",
+                self.code,
+                name,
+                self.message()
+            ),
+            None => write!(f, "Win32Error: {} -> {}", self.code, self.message()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Win32Error {}
+
+#[cfg(feature = "std")]
+/// Converts a [`Win32Error`] to a [`std::io::Error`].
+///
+/// # Remarks
+///
+/// * On Windows the result is created using [`from_raw_os_error`][`std::io::Error::from_raw_os_error`],
+///   so [`raw_os_error`][`std::io::Error::raw_os_error`] returns the error code.
+///   The attached function name and context are dropped. Wrap the error (e.g. using [`std::io::Error::new`]) to keep them.
+/// * On other targets the error is wrapped, because the raw OS error codes have a different meaning.
+///
+impl From<Win32Error> for std::io::Error {
+    fn from(error: Win32Error) -> Self {
+        if cfg!(windows) {
+            Self::from_raw_os_error(error.code as i32)
+        } else {
+            // The raw OS error codes of other platforms have a different meaning.
+            Self::new(error.kind().into(), error)
+        }
+    }
+}
+
+/// Extension methods for attaching context to the [`Win32Error`] of a [`Result`].
+pub trait ResultExt<T> {
+    /// Attaches a message to the error that describes what was being done when the error occurred.
+    /// See [`Win32Error::with_context`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error with the attached context if the result is an error.
+    fn context<M>(self, message: M) -> Result<T>
+    where
+        M: Into<Cow<'static, str>>;
+
+    /// Attaches a lazily created message to the error that describes what was being done when the error occurred.
+    /// The closure is only called if the result is an error. See [`Win32Error::with_context`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error with the attached context if the result is an error.
+    fn with_context<M, F>(self, message: F) -> Result<T>
+    where
+        M: Into<Cow<'static, str>>,
+        F: FnOnce() -> M;
+
+    /// Attaches the raw value of the handle that was passed to the failed function.
+    /// See [`Win32Error::with_handle`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error with the attached context if the result is an error.
+    fn with_handle(self, handle: isize) -> Result<T>;
+
+    /// Attaches the identifier of the process that was targeted by the failed function.
+    /// See [`Win32Error::with_process_id`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error with the attached context if the result is an error.
+    fn with_process_id(self, process_id: u32) -> Result<T>;
+
+    /// Attaches the identifier of the thread that was targeted by the failed function.
+    /// See [`Win32Error::with_thread_id`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error with the attached context if the result is an error.
+    fn with_thread_id(self, thread_id: u32) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    #[inline]
+    fn context<M>(self, message: M) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        self.map_err(|error| error.with_context(message))
+    }
+
+    #[inline]
+    fn with_context<M, F>(self, message: F) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+        F: FnOnce() -> M,
+    {
+        self.map_err(|error| error.with_context(message()))
+    }
+
+    #[inline]
+    fn with_handle(self, handle: isize) -> Self {
+        self.map_err(|error| error.with_handle(handle))
+    }
+
+    #[inline]
+    fn with_process_id(self, process_id: u32) -> Self {
+        self.map_err(|error| error.with_process_id(process_id))
+    }
+
+    #[inline]
+    fn with_thread_id(self, thread_id: u32) -> Self {
+        self.map_err(|error| error.with_thread_id(thread_id))
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Represents an `HRESULT` value that is used by COM and many other Windows APIs.
+///
+/// # Remarks
+///
+/// * The layout of the value is the following (from the most significant bit):
+///   severity (1 bit), reserved (1 bit), customer (1 bit), facility (13 bits) and code (16 bits).
+/// * The most significant bit of the facility is the NTSTATUS bit (see [`is_nt`][`HResult::is_nt`]).
+///   It is part of the facility like in the `HRESULT_FACILITY` macro.
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-erref/0642cb2f-2075-4469-918c-4441e69c548a
+///
+pub struct HResult(pub HRESULT);
+
+impl HResult {
+    /// The operation completed successfully.
+    pub const S_OK: Self = Self(0);
+    /// Bit that indicates that the value was mapped from an [`NtStatus`].
+    const NT_BIT: u32 = FACILITY_NT_BIT;
+    /// Bit that indicates that the value is customer-defined.
+    const CUSTOMER_BIT: u32 = 0x2000_0000;
+    /// Bit that indicates a failure.
+    const SEVERITY_BIT: u32 = 0x8000_0000;
+
+    #[inline]
+    /// Creates a new [`HResult`] from a raw `HRESULT` value.
+    pub const fn new(value: HRESULT) -> Self {
+        Self(value)
+    }
+
+    /// Creates a new [`HResult`] from a Win32 error code like the `HRESULT_FROM_WIN32` macro.
+    pub const fn from_win32(code: u32) -> Self {
+        if code as HRESULT <= 0 {
+            Self(code as HRESULT)
+        } else {
+            Self(((code & 0xFFFF) | (FACILITY_WIN32 << 16) | Self::SEVERITY_BIT) as HRESULT)
+        }
+    }
+
+    #[inline]
+    /// Creates a new [`HResult`] from an [`NtStatus`] like the `HRESULT_FROM_NT` macro.
+    pub const fn from_nt(status: NtStatus) -> Self {
+        Self((status.0 as u32 | Self::NT_BIT) as HRESULT)
+    }
+
+    #[inline]
+    /// Gets the raw `HRESULT` value.
+    pub const fn value(self) -> HRESULT {
+        self.0
+    }
+
+    #[inline]
+    /// Determines whether the value indicates success.
+    pub const fn is_success(self) -> bool {
+        self.0 >= 0
+    }
+
+    #[inline]
+    /// Determines whether the value indicates failure.
+    pub const fn is_failure(self) -> bool {
+        !self.is_success()
+    }
+
+    #[inline]
+    /// Determines whether the value is customer-defined.
+    pub const fn is_customer(self) -> bool {
+        self.0 as u32 & Self::CUSTOMER_BIT != 0
+    }
+
+    #[inline]
+    /// Determines whether the value was mapped from an [`NtStatus`]. See [`from_nt`][`HResult::from_nt`].
+    pub const fn is_nt(self) -> bool {
+        self.0 as u32 & Self::NT_BIT != 0
+    }
+
+    #[inline]
+    /// Gets the facility of the value (13 bits, including the NTSTATUS bit).
+    pub const fn facility(self) -> u32 {
+        (self.0 as u32 >> 16) & 0x1FFF
+    }
+
+    #[inline]
+    /// Gets the facility's status code of the value.
+    pub const fn code(self) -> u16 {
+        (self.0 as u32 & 0xFFFF) as u16
+    }
+
+    /// Gets the [`Win32Error`] the value was created from using [`from_win32`][`HResult::from_win32`].
+    /// If the value doesn't represent a Win32 error the result is [`None`].
+    pub const fn to_win32(self) -> Option<Win32Error> {
+        if self.0 == 0 {
+            Some(Win32Error::new(ERROR_SUCCESS))
+        } else if self.is_failure() && !self.is_nt() && self.facility() == FACILITY_WIN32 {
+            Some(Win32Error::new(self.code() as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the [`NtStatus`] the value was created from using [`from_nt`][`HResult::from_nt`].
+    /// If the value doesn't represent an [`NtStatus`] the result is [`None`].
+    pub const fn to_nt(self) -> Option<NtStatus> {
+        if self.is_nt() {
+            Some(NtStatus((self.0 as u32 & !Self::NT_BIT) as NTSTATUS))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Win32Error> for HResult {
+    #[inline]
+    fn from(error: Win32Error) -> Self {
+        Self::from_win32(error.code)
+    }
+}
+
+impl From<NtStatus> for HResult {
+    #[inline]
+    fn from(status: NtStatus) -> Self {
+        Self::from_nt(status)
+    }
+}
+
+impl TryFrom<HResult> for Win32Error {
+    type Error = HResult;
+
+    #[inline]
+    fn try_from(value: HResult) -> core::result::Result<Self, Self::Error> {
+        value.to_win32().ok_or(value)
+    }
+}
+
+impl core::fmt::Display for HResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "HRESULT: {:#010X}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HResult {}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The severity of an [`NtStatus`].
+pub enum NtStatusSeverity {
+    /// The status indicates success.
+    Success,
+    /// The status is informational.
+    Informational,
+    /// The status is a warning.
+    Warning,
+    /// The status indicates an error.
+    Error,
+}
+
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Represents an `NTSTATUS` value that is returned by the native system services.
+///
+/// # Remarks
+///
+/// * The layout of the value is the following (from the most significant bit):
+///   severity (2 bits), customer (1 bit), reserved (1 bit), facility (12 bits) and code (16 bits).
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-erref/87fba13e-bf06-450e-83b1-9241dc81e781
+///
+pub struct NtStatus(pub NTSTATUS);
+
+impl NtStatus {
+    /// The operation completed successfully.
+    pub const STATUS_SUCCESS: Self = Self(0);
+    /// Bit that indicates that the value is customer-defined.
+    const CUSTOMER_BIT: u32 = 0x2000_0000;
+    /// The severity bits of a warning, which `NTSTATUS_FROM_WIN32` uses for every mapped error.
+    const SEVERITY_WARNING: u32 = 0x8000_0000;
+
+    #[inline]
+    /// Creates a new [`NtStatus`] from a raw `NTSTATUS` value.
+    pub const fn new(value: NTSTATUS) -> Self {
+        Self(value)
+    }
+
+    /// Creates a new [`NtStatus`] from a Win32 error code like the `NTSTATUS_FROM_WIN32` macro.
+    pub const fn from_win32(code: u32) -> Self {
+        if code as NTSTATUS <= 0 {
+            Self(code as NTSTATUS)
+        } else {
+            Self(((code & 0xFFFF) | (FACILITY_NTWIN32 << 16) | Self::SEVERITY_WARNING) as NTSTATUS)
+        }
+    }
+
+    #[inline]
+    /// Gets the raw `NTSTATUS` value.
+    pub const fn value(self) -> NTSTATUS {
+        self.0
+    }
+
+    #[inline]
+    /// Determines whether the value indicates success or information like the `NT_SUCCESS` macro.
+    pub const fn is_success(self) -> bool {
+        self.0 >= 0
+    }
+
+    #[inline]
+    /// Determines whether the value indicates a warning or an error.
+    pub const fn is_failure(self) -> bool {
+        !self.is_success()
+    }
+
+    /// Gets the severity of the value.
+    pub const fn severity(self) -> NtStatusSeverity {
+        match self.0 as u32 >> 30 {
+            0 => NtStatusSeverity::Success,
+            1 => NtStatusSeverity::Informational,
+            2 => NtStatusSeverity::Warning,
+            _ => NtStatusSeverity::Error,
+        }
+    }
+
+    #[inline]
+    /// Determines whether the value is customer-defined.
+    pub const fn is_customer(self) -> bool {
+        self.0 as u32 & Self::CUSTOMER_BIT != 0
+    }
+
+    #[inline]
+    /// Gets the facility of the value.
+    pub const fn facility(self) -> u32 {
+        (self.0 as u32 >> 16) & 0x0FFF
+    }
+
+    #[inline]
+    /// Gets the facility's status code of the value.
+    pub const fn code(self) -> u16 {
+        (self.0 as u32 & 0xFFFF) as u16
+    }
+
+    /// Converts the value to the corresponding [`Win32Error`].
+    ///
+    /// # Remarks
+    ///
+    /// * Values created by [`from_win32`][`NtStatus::from_win32`] are converted back without calling any system function.
+    /// * Other values are converted using `RtlNtStatusToDosError`, which returns
+    ///   [`ERROR_MR_MID_NOT_FOUND`][`windows_sys::Win32::Foundation::ERROR_MR_MID_NOT_FOUND`] if there's no corresponding Win32 error code.
+    /// * `RtlNtStatusToDosError` is only available on Windows, so other values are always converted to
+    ///   [`ERROR_MR_MID_NOT_FOUND`][`windows_sys::Win32::Foundation::ERROR_MR_MID_NOT_FOUND`] on other targets.
+    ///
+    /// For more information see the official [documentation].
+    ///
+    /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winternl/nf-winternl-rtlntstatustodoserror
+    ///
+    #[cfg_attr(not(windows), allow(clippy::missing_const_for_fn))]
+    pub fn to_win32(self) -> Win32Error {
+        if self.0 == 0 {
+            return Win32Error::new(ERROR_SUCCESS);
+        }
+
+        if self.is_failure() && self.facility() == FACILITY_NTWIN32 {
+            return Win32Error::new(self.code() as u32);
+        }
+
+        #[cfg(not(windows))]
+        let code = windows_sys::Win32::Foundation::ERROR_MR_MID_NOT_FOUND;
+        #[cfg(windows)]
+        #[allow(clippy::undocumented_unsafe_blocks)]
+        let code = unsafe { RtlNtStatusToDosError(self.0) };
+        Win32Error::new(code)
+    }
+}
+
+impl From<Win32Error> for NtStatus {
+    #[inline]
+    fn from(error: Win32Error) -> Self {
+        Self::from_win32(error.code)
+    }
+}
+
+impl From<NtStatus> for Win32Error {
+    #[inline]
+    fn from(status: NtStatus) -> Self {
+        status.to_win32()
+    }
+}
+
+impl core::fmt::Display for NtStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "NTSTATUS: {:#010X}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NtStatus {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// An error that can be returned by any of the error spaces used by Windows API.
+pub enum Error {
+    /// An error returned by `GetLastError`.
+    Win32(Win32Error),
+    /// An error returned as an `HRESULT`.
+    HResult(HResult),
+    /// An error returned as an `NTSTATUS`.
+    NtStatus(NtStatus),
+}
+
+impl Error {
+    /// Converts the error to an [`HResult`].
+    ///
+    /// # Remarks
+    ///
+    /// * [`HResult`] and [`NtStatus`] values are converted losslessly.
+    /// * A Win32 error code is converted like the `HRESULT_FROM_WIN32` macro, which keeps only the low 16 bits of the code,
+    ///   so codes above `0xFFFF` cannot be recovered from the result.
+    ///
+    pub const fn to_hresult(&self) -> HResult {
+        match self {
+            Self::Win32(error) => HResult::from_win32(error.code),
+            Self::HResult(hresult) => *hresult,
+            Self::NtStatus(status) => HResult::from_nt(*status),
+        }
+    }
+
+    /// Converts the error to a [`Win32Error`].
+    /// If an [`HResult`] doesn't represent a Win32 error or an [`NtStatus`], the result is [`None`].
+    pub fn to_win32(&self) -> Option<Win32Error> {
+        match self {
+            Self::Win32(error) => Some(error.clone()),
+            Self::HResult(hresult) => hresult
+                .to_win32()
+                .or_else(|| hresult.to_nt().map(NtStatus::to_win32)),
+            Self::NtStatus(status) => Some(status.to_win32()),
+        }
+    }
+}
+
+impl From<Win32Error> for Error {
+    #[inline]
+    fn from(error: Win32Error) -> Self {
+        Self::Win32(error)
+    }
+}
+
+impl From<HResult> for Error {
+    #[inline]
+    fn from(hresult: HResult) -> Self {
+        Self::HResult(hresult)
+    }
+}
+
+impl From<NtStatus> for Error {
+    #[inline]
+    fn from(status: NtStatus) -> Self {
+        Self::NtStatus(status)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Win32(error) => error.fmt(f),
+            Self::HResult(hresult) => hresult.fmt(f),
+            Self::NtStatus(status) => status.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn function_is_stored_inline() {
+        let error = Win32Error::new(ERROR_NO_MORE_FILES).with_function("Process32NextW");
+        assert_eq!(error.function(), Some("Process32NextW"));
+        assert!(error.context().is_none());
+
+        let error = error.with_process_id(4);
+        assert_eq!(error.function(), Some("Process32NextW"));
+        assert_eq!(error.context().and_then(ErrorContext::process_id), Some(4));
+    }
+
+    #[test]
+    fn context_displays_the_identifiers() {
+        let cases = [
+            (
+                Win32Error::new(ERROR_ACCESS_DENIED).with_handle(0x1C),
+                "(handle: 0x1C)",
+            ),
+            (
+                Win32Error::new(ERROR_ACCESS_DENIED).with_thread_id(8),
+                "(thread id: 8)",
+            ),
+            (
+                Win32Error::new(ERROR_ACCESS_DENIED)
+                    .with_thread_id(8)
+                    .with_process_id(4)
+                    .with_handle(0x1C),
+                "(handle: 0x1C, process id: 4, thread id: 8)",
+            ),
+            (
+                Win32Error::new(ERROR_ACCESS_DENIED)
+                    .with_process_id(4)
+                    .with_context("opening the process"),
+                "opening the process: (process id: 4)",
+            ),
+            (
+                Win32Error::new(ERROR_ACCESS_DENIED).with_context("querying the process"),
+                "querying the process",
+            ),
+        ];
+
+        for (error, expected) in cases {
+            assert_eq!(error.context().unwrap().to_string(), expected);
+        }
+
+        let error = Win32Error::new(ERROR_INVALID_PARAMETER)
+            .with_function("OpenThread")
+            .with_thread_id(8);
+        assert!(error.to_string().starts_with(
+            "OpenThread failed (thread id: 8): Win32Error: 87 (ERROR_INVALID_PARAMETER)"
+        ));
+    }
+
+    #[test]
+    fn hresult_decodes_bits() {
+        let hresult = HResult(0x8007_0005_u32 as HRESULT);
+        assert!(hresult.is_failure());
+        assert!(!hresult.is_customer());
+        assert!(!hresult.is_nt());
+        assert_eq!(hresult.facility(), FACILITY_WIN32);
+        assert_eq!(hresult.code(), 5);
+
+        let customer = HResult(0xA123_4567_u32 as HRESULT);
+        assert!(customer.is_customer());
+        assert_eq!(customer.facility(), 0x0123);
+        assert_eq!(customer.code(), 0x4567);
+
+        let nt = HResult(0xD000_0022_u32 as HRESULT);
+        assert!(nt.is_nt());
+        assert_eq!(nt.facility(), 0x1000);
+
+        assert!(HResult::S_OK.is_success());
+        assert!(HResult(1).is_success());
+    }
+
+    #[test]
+    fn hresult_win32_round_trip() {
+        assert_eq!(
+            HResult::from_win32(ERROR_ACCESS_DENIED),
+            HResult(0x8007_0005_u32 as HRESULT)
+        );
+        assert_eq!(HResult::from_win32(ERROR_SUCCESS), HResult::S_OK);
+        for code in [
+            ERROR_SUCCESS,
+            ERROR_ACCESS_DENIED,
+            ERROR_INSUFFICIENT_BUFFER,
+        ] {
+            let hresult = HResult::from_win32(code);
+            assert_eq!(hresult.to_win32().map(|error| error.code()), Some(code));
+            assert_eq!(hresult.to_nt(), None);
+        }
+
+        // `HRESULT_FROM_WIN32` keeps negative values unchanged.
+        let hresult = HResult::from_win32(0x8007_0005);
+        assert_eq!(hresult, HResult(0x8007_0005_u32 as HRESULT));
+        assert_eq!(HResult(0x8004_0005_u32 as HRESULT).to_win32(), None);
+    }
+
+    #[test]
+    fn hresult_nt_round_trip() {
+        let status = NtStatus(0xC000_0005_u32 as NTSTATUS);
+        let hresult = HResult::from_nt(status);
+        assert_eq!(hresult, HResult(0xD000_0005_u32 as HRESULT));
+        assert!(hresult.is_nt());
+        assert!(hresult.is_failure());
+        assert_eq!(hresult.to_nt(), Some(status));
+        assert_eq!(hresult.to_win32(), None);
+    }
+
+    #[test]
+    fn nt_status_decodes_bits() {
+        let cases = [
+            (0x0000_0000_u32, NtStatusSeverity::Success),
+            (0x4000_0000, NtStatusSeverity::Informational),
+            (0x8000_0005, NtStatusSeverity::Warning),
+            (0xC000_0005, NtStatusSeverity::Error),
+        ];
+        for (value, severity) in cases {
+            let status = NtStatus(value as NTSTATUS);
+            assert_eq!(status.severity(), severity);
+            assert_eq!(status.is_success(), value < 0x8000_0000);
+        }
+
+        let status = NtStatus(0xE007_1234_u32 as NTSTATUS);
+        assert!(status.is_customer());
+        assert_eq!(status.facility(), FACILITY_NTWIN32);
+        assert_eq!(status.code(), 0x1234);
+    }
+
+    #[test]
+    fn nt_status_win32_round_trip() {
+        let status = NtStatus::from_win32(ERROR_ACCESS_DENIED);
+        assert_eq!(status, NtStatus(0x8007_0005_u32 as NTSTATUS));
+        assert_eq!(status.severity(), NtStatusSeverity::Warning);
+        assert_eq!(status.facility(), FACILITY_NTWIN32);
+        assert_eq!(
+            NtStatus::from_win32(ERROR_SUCCESS),
+            NtStatus::STATUS_SUCCESS
+        );
+
+        for code in [ERROR_SUCCESS, ERROR_ACCESS_DENIED, ERROR_PARTIAL_COPY] {
+            assert_eq!(NtStatus::from_win32(code).to_win32().code(), code);
+        }
+
+        // Values with the error severity are converted without calling `RtlNtStatusToDosError` too.
+        let error = NtStatus(0xC007_0005_u32 as NTSTATUS).to_win32();
+        assert_eq!(error.code(), ERROR_ACCESS_DENIED);
+    }
+
+    #[test]
+    fn error_converts_to_hresult() {
+        let win32 = Error::from(Win32Error::new(ERROR_ACCESS_DENIED));
+        assert_eq!(win32.to_hresult(), HResult(0x8007_0005_u32 as HRESULT));
+        assert_eq!(
+            win32.to_win32().map(|error| error.code()),
+            Some(ERROR_ACCESS_DENIED)
+        );
+
+        let status = NtStatus::from_win32(ERROR_NOT_FOUND);
+        let nt = Error::from(status);
+        assert_eq!(nt.to_hresult(), HResult::from_nt(status));
+        assert_eq!(
+            nt.to_win32().map(|error| error.code()),
+            Some(ERROR_NOT_FOUND)
+        );
+
+        let hresult = Error::from(HResult::from_nt(status));
+        assert_eq!(
+            hresult.to_win32().map(|error| error.code()),
+            Some(ERROR_NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn error_names_are_sorted_by_code() {
+        assert!(ERROR_NAMES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(ERROR_NAMES
+            .iter()
+            .all(|&(_, name)| name.starts_with("ERROR_")));
+    }
+
+    #[test]
+    fn name_finds_documented_codes() {
+        assert_eq!(Win32Error::new(ERROR_SUCCESS).name(), Some("ERROR_SUCCESS"));
+        assert_eq!(
+            Win32Error::new(ERROR_NO_MORE_FILES).name(),
+            Some("ERROR_NO_MORE_FILES")
+        );
+        assert_eq!(
+            Win32Error::new(ERROR_NOT_FOUND).name(),
+            Some("ERROR_NOT_FOUND")
+        );
+        assert_eq!(Win32Error::new(u32::MAX).name(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_error_keeps_the_code() {
+        let error = Win32Error::new(ERROR_ACCESS_DENIED)
+            .with_function("OpenProcess")
+            .with_process_id(4);
+        let io_error = std::io::Error::from(error);
+
+        assert_eq!(io_error.kind(), std::io::ErrorKind::PermissionDenied);
+        if cfg!(windows) {
+            assert_eq!(io_error.raw_os_error(), Some(ERROR_ACCESS_DENIED as i32));
+        } else {
+            let inner = io_error
+                .get_ref()
+                .and_then(|inner| inner.downcast_ref::<Win32Error>());
+            assert_eq!(inner.map(Win32Error::code), Some(ERROR_ACCESS_DENIED));
+        }
     }
 }
@@ -1,18 +1,436 @@
 use crate::call_BOOL;
 use crate::win32::core::Result;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
 use windows_sys::Win32::Foundation::{CloseHandle, GetLastError};
 
-pub use windows_sys::Win32::Foundation::{ERROR_INVALID_HANDLE, ERROR_INVALID_WINDOW_HANDLE};
+pub use windows_sys::Win32::Foundation::{
+    ERROR_INVALID_HANDLE, ERROR_INVALID_PARAMETER, ERROR_INVALID_WINDOW_HANDLE, ERROR_NOT_FOUND,
+};
+
+/// The pseudo handle value that is interpreted as the current process handle.
+pub const CURRENT_PROCESS_PSEUDO_HANDLE: isize = -1;
+/// The pseudo handle value that is interpreted as the current thread handle.
+pub const CURRENT_THREAD_PSEUDO_HANDLE: isize = -2;
+
+/// Determines whether `raw` is one of the pseudo handle values returned by
+/// [`process::get_current_handle`][`crate::win32::system::threading::process::get_current_handle`] or
+/// [`thread::get_current_handle`][`crate::win32::system::threading::thread::get_current_handle`].
+#[inline]
+const fn is_pseudo_handle(raw: isize) -> bool {
+    raw == CURRENT_PROCESS_PSEUDO_HANDLE || raw == CURRENT_THREAD_PSEUDO_HANDLE
+}
+
+#[repr(transparent)]
+#[derive(Debug)]
+/// An owned handle to a kernel object that is closed using [`close_handle`] when dropped.
+///
+/// # Remarks
+///
+/// * Pseudo handles ([`CURRENT_PROCESS_PSEUDO_HANDLE`] and [`CURRENT_THREAD_PSEUDO_HANDLE`]) are never closed.
+/// * Errors that occur while closing the handle on drop are ignored. Use [`OwnedHandle::close`] to handle them.
+///
+pub struct OwnedHandle {
+    /// The raw value of the owned handle.
+    raw: isize,
+}
+
+impl OwnedHandle {
+    /// Creates a new [`OwnedHandle`] that takes the ownership of `raw`.
+    ///
+    /// # Safety
+    ///
+    /// If any of the following conditions are violated, the result is Undefined Behavior:
+    ///
+    /// * `raw` must be an open handle or a pseudo handle.
+    /// * `raw` must not be owned by anything else, because it gets closed when the [`OwnedHandle`] is dropped.
+    ///
+    #[inline]
+    pub const unsafe fn from_raw(raw: isize) -> Self {
+        Self { raw }
+    }
+
+    #[inline]
+    /// Gets the raw value of the handle without giving up its ownership.
+    pub const fn as_raw(&self) -> isize {
+        self.raw
+    }
+
+    #[inline]
+    /// Consumes the [`OwnedHandle`] and returns its raw value without closing it.
+    /// The caller becomes responsible for closing the returned handle.
+    pub fn into_raw(self) -> isize {
+        ManuallyDrop::new(self).raw
+    }
+
+    #[inline]
+    /// Borrows the handle.
+    pub const fn as_handle(&self) -> BorrowedHandle<'_> {
+        BorrowedHandle {
+            raw: self.raw,
+            _owner: PhantomData,
+        }
+    }
+
+    #[inline]
+    /// Determines whether the handle is a pseudo handle that doesn't need to be closed.
+    pub const fn is_pseudo(&self) -> bool {
+        is_pseudo_handle(self.raw)
+    }
+
+    /// Closes the handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+    ///
+    /// ## Possible errors
+    ///
+    /// * The handle has been already invalidated.
+    ///
+    /// # Examples
+    /// TODO
+    ///
+    pub fn close(self) -> Result<()> {
+        if self.is_pseudo() {
+            return Ok(());
+        }
+
+        close_handle(self.into_raw())
+    }
+}
+
+impl Drop for OwnedHandle {
+    fn drop(&mut self) {
+        // Other targets don't have kernel objects, so they can only have fake handles (e.g. in tests with a scripted backend).
+        #[cfg(windows)]
+        if !self.is_pseudo() {
+            let _ = close_handle(self.raw);
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy)]
+/// A borrowed handle to a kernel object that is valid for the lifetime of `'handle`.
+pub struct BorrowedHandle<'handle> {
+    /// The raw value of the borrowed handle.
+    raw: isize,
+    /// Ties the borrowed handle to the lifetime of its owner.
+    _owner: PhantomData<&'handle OwnedHandle>,
+}
+
+impl BorrowedHandle<'_> {
+    /// Creates a new [`BorrowedHandle`] from `raw`.
+    ///
+    /// # Safety
+    ///
+    /// If any of the following conditions are violated, the result is Undefined Behavior:
+    ///
+    /// * `raw` must be an open handle or a pseudo handle.
+    /// * `raw` must remain open for the duration of the returned [`BorrowedHandle`].
+    ///
+    #[inline]
+    pub const unsafe fn borrow_raw(raw: isize) -> Self {
+        Self {
+            raw,
+            _owner: PhantomData,
+        }
+    }
+
+    #[inline]
+    /// Gets the raw value of the handle.
+    pub const fn as_raw(&self) -> isize {
+        self.raw
+    }
+
+    #[inline]
+    /// Determines whether the handle is a pseudo handle.
+    pub const fn is_pseudo(&self) -> bool {
+        is_pseudo_handle(self.raw)
+    }
+}
+
+impl BorrowedHandle<'static> {
+    #[inline]
+    /// Gets the pseudo handle of the current process. See [`CURRENT_PROCESS_PSEUDO_HANDLE`].
+    pub const fn current_process() -> Self {
+        // Safety: Pseudo handles are always valid.
+        unsafe { Self::borrow_raw(CURRENT_PROCESS_PSEUDO_HANDLE) }
+    }
+
+    #[inline]
+    /// Gets the pseudo handle of the current thread. See [`CURRENT_THREAD_PSEUDO_HANDLE`].
+    pub const fn current_thread() -> Self {
+        // Safety: Pseudo handles are always valid.
+        unsafe { Self::borrow_raw(CURRENT_THREAD_PSEUDO_HANDLE) }
+    }
+}
+
+/// A trait for borrowing handles.
+pub trait AsHandle {
+    /// Borrows the underlying handle.
+    fn as_handle(&self) -> BorrowedHandle<'_>;
+}
+
+impl AsHandle for OwnedHandle {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        Self::as_handle(self)
+    }
+}
+
+impl AsHandle for BorrowedHandle<'_> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        *self
+    }
+}
+
+impl<T: AsHandle> AsHandle for &T {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        T::as_handle(self)
+    }
+}
+
+/// A kind of kernel object that can be referred to by an [`ObjectHandle`].
+pub trait KernelObject {
+    /// Pairs of access rights, where the first access right implies the second one.
+    ///
+    /// For example, [`PROCESS_QUERY_INFORMATION`][`crate::win32::system::threading::process::PROCESS_QUERY_INFORMATION`]
+    /// implies [`PROCESS_QUERY_LIMITED_INFORMATION`][`crate::win32::system::threading::process::PROCESS_QUERY_LIMITED_INFORMATION`].
+    const IMPLIED_RIGHTS: &'static [(u32, u32)];
+}
+
+/// Determines whether the `granted` access rights, extended by the rights they imply, include all of the `required` access rights.
+pub const fn has_access_rights(granted: u32, implied_rights: &[(u32, u32)], required: u32) -> bool {
+    let mut effective = granted;
+    let mut i = 0;
+    while i < implied_rights.len() {
+        let (right, implied) = implied_rights[i];
+        if granted & right == right {
+            effective |= implied;
+        }
+
+        i += 1;
+    }
+
+    effective & required == required
+}
+
+/// Compile-time check of the access rights of an [`ObjectHandle`].
+struct RightsCheck<O, const GRANTED: u32, const REQUIRED: u32>(PhantomData<O>);
+
+impl<O: KernelObject, const GRANTED: u32, const REQUIRED: u32> RightsCheck<O, GRANTED, REQUIRED> {
+    /// Fails to evaluate if `GRANTED` doesn't include all of the `REQUIRED` access rights.
+    const OK: () = assert!(
+        has_access_rights(GRANTED, O::IMPLIED_RIGHTS, REQUIRED),
+        "the handle doesn't have the required access rights"
+    );
+}
+
+#[repr(transparent)]
+#[derive(Debug)]
+/// An owned handle to a kernel object of type `O` that was opened with the `RIGHTS` access rights.
+///
+/// # Remarks
+///
+/// * The access rights of the handle are part of its type, so wrappers that require access rights
+///   the handle doesn't have fail to compile instead of failing with [`ERROR_ACCESS_DENIED`][`windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED`].
+/// * The access rights are checked when the wrapper is instantiated, which means the error is reported by
+///   `cargo build` but not by `cargo check`.
+/// * The handle is closed when it is dropped.
+///
+/// # Examples
+///
+/// Access rights that are implied by the granted ones are accepted:
+///
+/// ```no_run
+/// use win_api_wrapper::win32::foundation::BorrowedHandle;
+/// use win_api_wrapper::win32::system::threading::process::{
+///     ProcessHandle, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION,
+/// };
+///
+/// // `PROCESS_QUERY_INFORMATION` implies `PROCESS_QUERY_LIMITED_INFORMATION`.
+/// fn query(handle: &ProcessHandle<PROCESS_QUERY_INFORMATION>) -> BorrowedHandle<'_> {
+///     handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>()
+/// }
+///
+/// let _: fn(&ProcessHandle<PROCESS_QUERY_INFORMATION>) -> BorrowedHandle<'_> = query;
+/// ```
+///
+/// Passing a handle to a wrapper that requires access rights the handle doesn't have fails to compile:
+///
+/// ```compile_fail,E0080
+/// use win_api_wrapper::win32::core::Result;
+/// use win_api_wrapper::win32::system::threading::process::{
+///     self, ProcessHandle, NORMAL_PRIORITY_CLASS, PROCESS_QUERY_LIMITED_INFORMATION,
+/// };
+///
+/// // `set_priority_class` requires `PROCESS_SET_INFORMATION`.
+/// fn set_normal_priority(handle: &ProcessHandle<PROCESS_QUERY_LIMITED_INFORMATION>) -> Result<()> {
+///     process::set_priority_class(handle, NORMAL_PRIORITY_CLASS)
+/// }
+///
+/// let _: fn(&ProcessHandle<PROCESS_QUERY_LIMITED_INFORMATION>) -> Result<()> = set_normal_priority;
+/// ```
+///
+/// A limited access right doesn't imply the full one:
+///
+/// ```compile_fail,E0080
+/// use win_api_wrapper::win32::foundation::BorrowedHandle;
+/// use win_api_wrapper::win32::system::threading::process::{
+///     ProcessHandle, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION,
+/// };
+///
+/// fn query(handle: &ProcessHandle<PROCESS_QUERY_LIMITED_INFORMATION>) -> BorrowedHandle<'_> {
+///     handle.require_rights::<PROCESS_QUERY_INFORMATION>()
+/// }
+///
+/// let _: fn(&ProcessHandle<PROCESS_QUERY_LIMITED_INFORMATION>) -> BorrowedHandle<'_> = query;
+/// ```
+///
+pub struct ObjectHandle<O, const RIGHTS: u32> {
+    /// The underlying owned handle.
+    handle: OwnedHandle,
+    /// The kind of the object.
+    _object: PhantomData<O>,
+}
+
+impl<O: KernelObject, const RIGHTS: u32> ObjectHandle<O, RIGHTS> {
+    /// The access rights that were granted when the handle was opened.
+    pub const GRANTED_RIGHTS: u32 = RIGHTS;
+
+    /// Creates a new [`ObjectHandle`] from `handle`.
+    ///
+    /// # Safety
+    ///
+    /// If any of the following conditions are violated, the result is Undefined Behavior:
+    ///
+    /// * `handle` must refer to a kernel object of type `O`.
+    /// * `handle` must have been opened with at least the `RIGHTS` access rights.
+    ///
+    #[inline]
+    pub const unsafe fn from_owned(handle: OwnedHandle) -> Self {
+        Self {
+            handle,
+            _object: PhantomData,
+        }
+    }
+
+    #[inline]
+    #[allow(clippy::missing_const_for_fn)]
+    /// Consumes the [`ObjectHandle`] and returns the underlying [`OwnedHandle`].
+    pub fn into_owned(self) -> OwnedHandle {
+        self.handle
+    }
+
+    #[inline]
+    /// Borrows the handle.
+    pub const fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.handle.as_handle()
+    }
+
+    #[inline]
+    /// Gets the raw value of the handle without giving up its ownership.
+    pub const fn as_raw(&self) -> isize {
+        self.handle.as_raw()
+    }
+
+    #[inline]
+    /// Borrows the handle after checking at compile time that it has the `REQUIRED` access rights.
+    pub const fn require_rights<const REQUIRED: u32>(&self) -> BorrowedHandle<'_> {
+        let () = RightsCheck::<O, RIGHTS, REQUIRED>::OK;
+        self.as_handle()
+    }
+
+    #[inline]
+    /// Converts the handle to a handle that has a subset of its access rights.
+    /// The conversion fails to compile if `RESTRICTED` is not a subset of the granted access rights.
+    pub fn restrict<const RESTRICTED: u32>(self) -> ObjectHandle<O, RESTRICTED> {
+        let () = RightsCheck::<O, RIGHTS, RESTRICTED>::OK;
+        ObjectHandle {
+            handle: self.handle,
+            _object: PhantomData,
+        }
+    }
+}
+
+impl<O: KernelObject, const RIGHTS: u32> AsHandle for ObjectHandle<O, RIGHTS> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        Self::as_handle(self)
+    }
+}
+
+impl<O: KernelObject, const RIGHTS: u32> From<ObjectHandle<O, RIGHTS>> for OwnedHandle {
+    #[inline]
+    fn from(handle: ObjectHandle<O, RIGHTS>) -> Self {
+        handle.into_owned()
+    }
+}
+
+#[cfg(all(windows, feature = "std"))]
+/// Conversions between the handle types and their [`std::os::windows::io`] counterparts.
+mod std_conversions {
+    use super::{BorrowedHandle, OwnedHandle};
+    use std::os::windows::io::{self, AsRawHandle, FromRawHandle, IntoRawHandle, RawHandle};
+
+    impl From<io::OwnedHandle> for OwnedHandle {
+        fn from(handle: io::OwnedHandle) -> Self {
+            // Safety: The ownership of the open handle is transferred.
+            unsafe { Self::from_raw(handle.into_raw_handle() as isize) }
+        }
+    }
+
+    impl From<OwnedHandle> for io::OwnedHandle {
+        fn from(handle: OwnedHandle) -> Self {
+            // Safety: The ownership of the open handle is transferred.
+            unsafe { Self::from_raw_handle(handle.into_raw() as RawHandle) }
+        }
+    }
+
+    impl<'handle> From<io::BorrowedHandle<'handle>> for BorrowedHandle<'handle> {
+        fn from(handle: io::BorrowedHandle<'handle>) -> Self {
+            // Safety: The handle remains open for `'handle`.
+            unsafe { Self::borrow_raw(handle.as_raw_handle() as isize) }
+        }
+    }
+
+    impl<'handle> From<BorrowedHandle<'handle>> for io::BorrowedHandle<'handle> {
+        fn from(handle: BorrowedHandle<'handle>) -> Self {
+            // Safety: The handle remains open for `'handle`.
+            unsafe { Self::borrow_raw(handle.as_raw() as RawHandle) }
+        }
+    }
+
+    impl io::AsHandle for OwnedHandle {
+        fn as_handle(&self) -> io::BorrowedHandle<'_> {
+            Self::as_handle(self).into()
+        }
+    }
+
+    impl io::AsHandle for BorrowedHandle<'_> {
+        fn as_handle(&self) -> io::BorrowedHandle<'_> {
+            (*self).into()
+        }
+    }
+}
 
 /// Closes the specified handle.
 ///
+/// # Remarks
+///
+/// * Handles owned by an [`OwnedHandle`] are closed automatically and must not be closed using this function.
+///
 /// # Errors
 ///
 /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
 ///
 /// ## Possible errors
 ///
-/// * `handle` has been already invalidated.
+/// * `handle` has been already invalidated. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
 ///
 /// # Examples
 /// TODO
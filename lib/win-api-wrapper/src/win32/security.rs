@@ -1,14 +1,29 @@
 use crate::call_BOOL;
 use crate::win32::core::Result;
+use crate::win32::foundation::{KernelObject, ObjectHandle};
 use core::ffi::c_void;
 use core::mem::size_of_val;
 use core::ptr::addr_of_mut;
 use windows_sys::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_INFORMATION_CLASS};
 
 pub use windows_sys::Win32::Security::{
-    TokenSource, TOKEN_ELEVATION, TOKEN_QUERY, TOKEN_QUERY_SOURCE,
+    TokenSource, TOKEN_ACCESS_MASK, TOKEN_ALL_ACCESS, TOKEN_ELEVATION, TOKEN_QUERY,
+    TOKEN_QUERY_SOURCE,
 };
 
+#[derive(Debug)]
+/// The access token kernel object. See [`TokenHandle`].
+pub struct TokenObject;
+
+impl KernelObject for TokenObject {
+    const IMPLIED_RIGHTS: &'static [(u32, u32)] = &[];
+}
+
+/// An owned access token handle that has the `RIGHTS` access rights.
+/// See [`open_token`][`crate::win32::system::threading::process::open_token`].
+pub type TokenHandle<const RIGHTS: TOKEN_ACCESS_MASK = TOKEN_ALL_ACCESS> =
+    ObjectHandle<TokenObject, RIGHTS>;
+
 /// Information that [`get_token_information`] can return about an access token.
 pub trait TokenInformation {
     /// Gets the associated [`TOKEN_INFORMATION_CLASS`] of the type.
@@ -35,9 +50,9 @@ impl TokenInformation for TOKEN_ELEVATION {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`TOKEN_QUERY`] access right.
-/// * `handle` doesn't have [`TOKEN_QUERY_SOURCE`] access right if the class returned by [`get_token_information`] specifies a [`TokenSource`].
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`TOKEN_QUERY`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `handle` doesn't have [`TOKEN_QUERY_SOURCE`] access right if the class returned by [`get_token_information`] specifies a [`TokenSource`]. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -46,13 +61,14 @@ impl TokenInformation for TOKEN_ELEVATION {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation
 ///
-pub fn get_token_information<T>(handle: isize) -> Result<T>
+pub fn get_token_information<T, const R: TOKEN_ACCESS_MASK>(handle: &TokenHandle<R>) -> Result<T>
 where
     T: Copy + TokenInformation,
 {
+    let handle = handle.require_rights::<TOKEN_QUERY>();
     call_BOOL! {
         GetTokenInformation(
-            handle,
+            handle.as_raw(),
             T::token_information_class(),
             addr_of_mut!(token_info).cast::<c_void>(),
             size_of_val(&token_info) as u32,
@@ -0,0 +1,2 @@
+/// `Win32::Storage::FileSystem`
+pub mod file_system;
@@ -0,0 +1,128 @@
+use crate::call_num;
+use crate::common::buffer::{negotiate, BufferPolicy, Fill};
+use crate::common::path::DeviceMap;
+use crate::common::wide::IntoWideCStr;
+use crate::win32::core::Result;
+use alloc::format;
+use alloc::vec::Vec;
+use core::ptr;
+use widestring::{U16Str, U16String};
+use windows_sys::core::PCWSTR;
+use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, MAX_PATH};
+use windows_sys::Win32::Storage::FileSystem::{GetLogicalDrives, QueryDosDeviceW};
+
+/// The maximum length of the buffer that is used by [`query_dos_device`] and [`get_dos_device_names`].
+pub const MAX_DOS_DEVICE_BUFFER_LEN: usize = u32::MAX as usize;
+
+/// Gets the targets of the specified MS-DOS device name (e.g. `C:`).
+/// The first target is the current mapping of the device (e.g. `\Device\HarddiskVolume3`).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `device_name` contains a null character. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * `device_name` is not an existing MS-DOS device name. ([`ErrorKind::NotFound`][`crate::win32::core::ErrorKind::NotFound`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-querydosdevicew
+///
+pub fn query_dos_device<D: IntoWideCStr + ?Sized>(device_name: &D) -> Result<Vec<U16String>> {
+    let device_name = device_name.to_wide_c_str()?;
+    query_dos_device_raw(device_name.as_ptr())
+}
+
+/// Gets every existing MS-DOS device name.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-querydosdevicew
+///
+pub fn get_dos_device_names() -> Result<Vec<U16String>> {
+    query_dos_device_raw(ptr::null())
+}
+
+/// Calls `QueryDosDeviceW` with a growing buffer and splits the returned list of null-terminated strings.
+fn query_dos_device_raw(device_name: PCWSTR) -> Result<Vec<U16String>> {
+    let policy = BufferPolicy::new(MAX_PATH as usize, MAX_DOS_DEVICE_BUFFER_LEN);
+    let buffer = negotiate(policy, |buffer: &mut [u16]| {
+        Fill::written(call_num! {
+            QueryDosDeviceW(device_name, buffer.as_mut_ptr(), buffer.len() as u32) != 0 as usize
+        })
+    })?;
+
+    Ok(buffer
+        .split(|&unit| unit == 0)
+        .filter(|target| !target.is_empty())
+        .map(U16String::from_vec)
+        .collect())
+}
+
+/// Gets a bitmask that represents the currently available disk drives.
+/// Bit position 0 is drive `A`, bit position 1 is drive `B`, and so on.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getlogicaldrives
+///
+pub fn get_logical_drives() -> Result<u32> {
+    call_num! { GetLogicalDrives() != 0 }
+}
+
+/// Gets the [`DeviceMap`] that maps the NT device path of each available drive to its drive letter.
+///
+/// # Remarks
+///
+/// * The map can be used to translate the native paths returned by
+///   [`process::get_full_image_name`][`crate::win32::system::threading::process::get_full_image_name`]
+///   (e.g. `\Device\HarddiskVolume3\Windows\notepad.exe`) to Win32 paths (e.g. `C:\Windows\notepad.exe`).
+/// * Drives that are removed while the map is built are skipped.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+pub fn get_device_map() -> Result<DeviceMap> {
+    let drives = get_logical_drives()?;
+    let mut device_map = DeviceMap::new();
+    for letter in (b'A'..=b'Z').filter(|letter| drives & (1 << (letter - b'A')) != 0) {
+        let drive = [u16::from(letter), u16::from(b':')];
+        let drive = U16Str::from_slice(&drive);
+        match query_dos_device(drive) {
+            Ok(targets) => {
+                if let Some(device) = targets.first() {
+                    device_map.insert(drive, device);
+                }
+            }
+            Err(error) if error.code() == ERROR_FILE_NOT_FOUND => {}
+            Err(error) => {
+                return Err(error.with_context(format!("querying drive {}:", char::from(letter))))
+            }
+        }
+    }
+
+    Ok(device_map)
+}
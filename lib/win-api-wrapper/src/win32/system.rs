@@ -1,11 +1,21 @@
+/// `Win32::System::Console`
+pub mod console;
 /// `Win32::System::Diagnostics`
 pub mod diagnostics;
+/// `Win32::System::Environment`
+pub mod environment;
+/// `Win32::System::IO`
+pub mod io;
+/// `Win32::System::JobObjects`
+pub mod job_objects;
 /// `Win32::System::Kernel`
 pub mod kernel;
 /// `Win32::System::Memory`
 pub mod memory;
 /// `Win32::System::ProcessStatus`
 pub mod process_status;
+/// `Win32::System::SystemServices`
+pub mod system_services;
 /// `Win32::System::Threading`
 pub mod threading;
 /// `Win32::System::Time`
@@ -0,0 +1,46 @@
+use crate::call_num;
+use crate::win32::core::Result;
+use crate::win32::foundation::BorrowedHandle;
+use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+use windows_sys::Win32::System::Console::GetStdHandle;
+
+pub use windows_sys::Win32::System::Console::{
+    STD_ERROR_HANDLE, STD_HANDLE, STD_INPUT_HANDLE, STD_OUTPUT_HANDLE,
+};
+
+/// Gets the specified standard handle of the current process.
+/// If the process doesn't have the specified standard handle, the result is [`None`].
+///
+/// # Arguments
+///
+/// * `kind`: [`STD_INPUT_HANDLE`], [`STD_OUTPUT_HANDLE`] or [`STD_ERROR_HANDLE`]
+///
+/// # Remarks
+///
+/// * The returned handle is owned by the process, so it must not be closed.
+/// * The returned handle stays valid until it is replaced or closed by the process (e.g. by `SetStdHandle`).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `kind` is not a standard device. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/console/getstdhandle
+///
+pub fn get_std_handle(kind: STD_HANDLE) -> Result<Option<BorrowedHandle<'static>>> {
+    let handle = call_num! { GetStdHandle(kind) != INVALID_HANDLE_VALUE }?;
+    Ok(if handle == 0 {
+        None
+    } else {
+        // Safety: The standard handles are owned by the process and they stay open until they are replaced.
+        Some(unsafe { BorrowedHandle::borrow_raw(handle) })
+    })
+}
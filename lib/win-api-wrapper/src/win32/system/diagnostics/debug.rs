@@ -1,25 +1,33 @@
-use crate::common::{pcwstr_to_u16_string, To};
+use crate::common::wide::{self, U16CStr, U16CString, U16String};
+use crate::common::To;
 use crate::win32::core::Result;
 use crate::win32::system::memory::{get_local_handle, local_free};
+use crate::win32::system::system_services::LangId;
 use core::ffi::c_void;
-use core::ptr::{self, addr_of, addr_of_mut};
-use widestring::U16String;
+use core::ptr::{self, addr_of_mut};
+use windows_sys::Win32::Foundation::{ERROR_MUI_FILE_NOT_FOUND, ERROR_RESOURCE_LANG_NOT_FOUND};
 use windows_sys::Win32::System::Diagnostics::Debug::{
     FormatMessageW, FORMAT_MESSAGE_ALLOCATE_BUFFER, FORMAT_MESSAGE_ARGUMENT_ARRAY,
     FORMAT_MESSAGE_FROM_HMODULE, FORMAT_MESSAGE_FROM_STRING, FORMAT_MESSAGE_FROM_SYSTEM,
     FORMAT_MESSAGE_IGNORE_INSERTS, FORMAT_MESSAGE_OPTIONS,
 };
 
+/// Process memory functions of `Win32::System::Diagnostics::Debug`
+pub mod process_memory;
+
 /// A [`FORMAT_MESSAGE_OPTIONS`] flag that is used to tell [`FormatMessageW`] to
 /// remove regular line breaks (`\r\n` or `\n`) from the formatted message.
 pub const FORMAT_MESSAGE_IGNORE_REGULAR_LINE_BREAKS: u32 = 0x000000FF;
 
 use crate::call_num;
 
-/// A marker trait for types that can be used as a source in [`format_message_with_source`].
+/// A trait for types that can be used as a `source` in [`format_message`] and [`format_message_with_buffer`].
 pub trait FormatSource {
     /// Gets the associated [`FORMAT_MESSAGE_OPTIONS`] flag for the type.
     fn format_message_options_flag() -> FORMAT_MESSAGE_OPTIONS;
+
+    /// Gets the pointer that is passed to [`FormatMessageW`] as the `lpSource` argument.
+    fn source_ptr(&self) -> *const c_void;
 }
 
 #[repr(u32)]
@@ -48,22 +56,74 @@ impl To<u32> for FormatMessagetOptions {
 /// The `System` source that can be used with [`format_message`] and [`format_message_with_buffer`] as a `source` argument.
 pub struct System;
 
-impl FormatSource for U16String {
+impl FormatSource for U16CStr {
     fn format_message_options_flag() -> FORMAT_MESSAGE_OPTIONS {
         FORMAT_MESSAGE_FROM_STRING
     }
+
+    fn source_ptr(&self) -> *const c_void {
+        self.as_ptr().cast()
+    }
+}
+
+impl FormatSource for U16CString {
+    fn format_message_options_flag() -> FORMAT_MESSAGE_OPTIONS {
+        FORMAT_MESSAGE_FROM_STRING
+    }
+
+    fn source_ptr(&self) -> *const c_void {
+        self.as_ptr().cast()
+    }
 }
 
 impl FormatSource for isize {
     fn format_message_options_flag() -> FORMAT_MESSAGE_OPTIONS {
         FORMAT_MESSAGE_FROM_HMODULE
     }
+
+    fn source_ptr(&self) -> *const c_void {
+        *self as *const c_void
+    }
 }
 
 impl FormatSource for System {
     fn format_message_options_flag() -> FORMAT_MESSAGE_OPTIONS {
         FORMAT_MESSAGE_FROM_SYSTEM
     }
+
+    fn source_ptr(&self) -> *const c_void {
+        ptr::null()
+    }
+}
+
+impl<T: FormatSource + ?Sized> FormatSource for &T {
+    fn format_message_options_flag() -> FORMAT_MESSAGE_OPTIONS {
+        T::format_message_options_flag()
+    }
+
+    fn source_ptr(&self) -> *const c_void {
+        (**self).source_ptr()
+    }
+}
+
+/// Calls `format` with `lang_id` and calls it again with [`LangId::ENGLISH_US`]
+/// if the message table of `lang_id` is not available.
+fn with_english_fallback<R, F>(lang_id: LangId, mut format: F) -> Result<R>
+where
+    F: FnMut(LangId) -> Result<R>,
+{
+    match format(lang_id) {
+        Err(error)
+            if lang_id != LangId::ENGLISH_US
+                && matches!(
+                    error.code(),
+                    ERROR_RESOURCE_LANG_NOT_FOUND | ERROR_MUI_FILE_NOT_FOUND
+                ) =>
+        {
+            format(LangId::ENGLISH_US)
+        }
+        result => result,
+    }
 }
 
 /// Gets the non-null-terminated formatted message string from the `source` location.
@@ -71,10 +131,15 @@ impl FormatSource for System {
 /// # Arguments
 /// * `source`: The location of the message definition.
 /// * `id`: The message identifier for the requested message.
-/// * `lang_id`: The language identifier for the requested message.
+/// * `lang_id`: The language identifier for the requested message. Use [`LangId::NEUTRAL`] to search for the message in the default languages.
 /// * `args`: An array of values that are used as insert values in the formatted message.
 /// * `options`: The formatting options.
 ///
+/// # Remarks
+///
+/// * If the message table of the language specified by `lang_id` is not available (`ERROR_RESOURCE_LANG_NOT_FOUND` or `ERROR_MUI_FILE_NOT_FOUND`),
+///   the message is retrieved in English ([`LangId::ENGLISH_US`]).
+///
 /// # Security
 ///
 /// See the [security-remarks] section in the official [documentation].
@@ -83,6 +148,10 @@ impl FormatSource for System {
 ///
 /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
 ///
+/// ## Possible errors
+///
+/// * The message is available neither in the language specified by `lang_id` nor in English. ([`ErrorKind::NotFound`][`crate::win32::core::ErrorKind::NotFound`])
+///
 /// # Examples
 /// TODO
 ///
@@ -94,20 +163,14 @@ impl FormatSource for System {
 pub fn format_message<T>(
     source: T,
     id: u32,
-    lang_id: u32,
+    lang_id: LangId,
     args: Option<&[*const i8]>,
     options: FormatMessagetOptions,
 ) -> Result<U16String>
 where
     T: FormatSource,
 {
-    let mut buffer = ptr::null_mut::<u16>();
-    let buffer_size = 0;
-    let source_ptr = if T::format_message_options_flag() == FORMAT_MESSAGE_FROM_SYSTEM {
-        ptr::null()
-    } else {
-        addr_of!(source).cast()
-    };
+    let source_ptr = source.source_ptr();
     let args_flag = if args.is_none() {
         0
     } else {
@@ -117,21 +180,29 @@ where
         | T::format_message_options_flag()
         | args_flag
         | options.to();
-    let buffer_len = call_num! {
-        (FormatMessageW(
-            option_flags,
-            source_ptr ,
-            id,
-            lang_id,
-            addr_of_mut!(buffer).cast(),
-            buffer_size,
-            if let Some(args) = args { args.as_ptr() } else { ptr::null() }
-        ) == 0) => return Error;
-    };
-    // Safety: `buffer` contains a valid system allocated `buffer_len` + 1 (including null termination).
+    let mut buffer = ptr::null_mut::<u16>();
+    let buffer_len = with_english_fallback(lang_id, |lang_id| {
+        call_num! {
+            FormatMessageW(
+                option_flags,
+                source_ptr,
+                id,
+                lang_id.into(),
+                addr_of_mut!(buffer).cast(),
+                0,
+                if let Some(args) = args { args.as_ptr() } else { ptr::null() }
+            ) != 0
+        }
+    })?;
     let can_trim_end = (options.to() & FormatMessagetOptions::RegularLineBreaks.to())
         == FormatMessagetOptions::RegularLineBreaks.to();
-    let message = unsafe { pcwstr_to_u16_string(buffer, buffer_len as usize, can_trim_end) };
+    // Safety: `buffer` contains a valid system allocated `buffer_len` + 1 (including null termination).
+    let mut message = unsafe { core::slice::from_raw_parts(buffer, buffer_len as usize) };
+    if can_trim_end {
+        message = wide::trim_end(message);
+    }
+
+    let message = U16String::from_vec(message);
     let handle = get_local_handle(buffer.cast::<c_void>())?;
     local_free(handle)?;
     Ok(message)
@@ -142,11 +213,16 @@ where
 /// # Arguments
 /// * `source`: The location of the message definition.
 /// * `id`: The message identifier for the requested message.
-/// * `lang_id`: The language identifier for the requested message.
+/// * `lang_id`: The language identifier for the requested message. Use [`LangId::NEUTRAL`] to search for the message in the default languages.
 /// * `args`: An array of values that are used as insert values in the formatted message.
 /// * `options`: The formatting options.
 /// * `buffer`: The buffer where the message gets copied to.
 ///
+/// # Remarks
+///
+/// * If the message table of the language specified by `lang_id` is not available, the message is retrieved in English
+///   like [`format_message`] does.
+///
 /// # Security
 ///
 /// See the [security-remarks] section in the official [documentation].
@@ -157,7 +233,8 @@ where
 ///
 /// #+ Possible errors
 ///  
-/// * `buffer` is not large enough to store the formatted message. ([`ERROR_INSUFFICIENT_BUFFER`][`windows_sys::Win32::Foundation::ERROR_INSUFFICIENT_BUFFER`])
+/// * `buffer` is not large enough to store the formatted message. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
+/// * The message is available neither in the language specified by `lang_id` nor in English. ([`ErrorKind::NotFound`][`crate::win32::core::ErrorKind::NotFound`])
 ///
 /// # Examples
 /// TODO
@@ -170,7 +247,7 @@ where
 pub fn format_message_with_buffer<T>(
     source: T,
     id: u32,
-    lang_id: u32,
+    lang_id: LangId,
     args: Option<&[*const i8]>,
     options: FormatMessagetOptions,
     buffer: &mut [u16],
@@ -178,26 +255,87 @@ pub fn format_message_with_buffer<T>(
 where
     T: FormatSource,
 {
-    let source_ptr = if T::format_message_options_flag() == FORMAT_MESSAGE_FROM_SYSTEM {
-        ptr::null()
-    } else {
-        addr_of!(source).cast()
-    };
+    let source_ptr = source.source_ptr();
     let args_flag = if args.is_none() {
         0
     } else {
         FORMAT_MESSAGE_ARGUMENT_ARRAY
     };
     let option_flags = T::format_message_options_flag() | args_flag | options.to();
-    call_num! {
-        FormatMessageW(
-            option_flags,
-            source_ptr,
-            id,
-            lang_id,
-            buffer.as_mut_ptr(),
-            buffer.len() as u32,
-            if let Some(args) = args { args.as_ptr() } else { ptr::null() }
-        ) != 0
+    with_english_fallback(lang_id, |lang_id| {
+        call_num! {
+            FormatMessageW(
+                option_flags,
+                source_ptr,
+                id,
+                lang_id.into(),
+                buffer.as_mut_ptr(),
+                buffer.len() as u32,
+                if let Some(args) = args { args.as_ptr() } else { ptr::null() }
+            ) != 0
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::win32::core::Win32Error;
+    use alloc::vec::Vec;
+    use windows_sys::Win32::Foundation::ERROR_MR_MID_NOT_FOUND;
+
+    /// Calls [`with_english_fallback`] with a format function that fails with `error` in every language but English
+    /// and returns the result and the requested languages.
+    fn fallback(lang_id: LangId, error: u32) -> (Result<LangId>, Vec<LangId>) {
+        let mut requested = Vec::new();
+        let result = with_english_fallback(lang_id, |lang_id| {
+            requested.push(lang_id);
+            if lang_id == LangId::ENGLISH_US {
+                Ok(lang_id)
+            } else {
+                Err(Win32Error::new(error))
+            }
+        });
+        (result, requested)
+    }
+
+    #[test]
+    fn missing_message_tables_fall_back_to_english() {
+        let german = LangId::new(0x07, 0x01);
+        for error in [ERROR_RESOURCE_LANG_NOT_FOUND, ERROR_MUI_FILE_NOT_FOUND] {
+            let (result, requested) = fallback(german, error);
+            assert_eq!(result, Ok(LangId::ENGLISH_US), "{error}");
+            assert_eq!(requested, [german, LangId::ENGLISH_US], "{error}");
+        }
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let german = LangId::new(0x07, 0x01);
+        let (result, requested) = fallback(german, ERROR_MR_MID_NOT_FOUND);
+        assert_eq!(result, Err(Win32Error::new(ERROR_MR_MID_NOT_FOUND)));
+        assert_eq!(requested, [german]);
+    }
+
+    #[test]
+    fn english_is_not_retried() {
+        let mut calls = 0;
+        let result: Result<()> = with_english_fallback(LangId::ENGLISH_US, |_| {
+            calls += 1;
+            Err(Win32Error::new(ERROR_RESOURCE_LANG_NOT_FOUND))
+        });
+        assert_eq!(result, Err(Win32Error::new(ERROR_RESOURCE_LANG_NOT_FOUND)));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn available_languages_are_not_retried() {
+        let mut requested = Vec::new();
+        let result = with_english_fallback(LangId::NEUTRAL, |lang_id| {
+            requested.push(lang_id);
+            Ok(lang_id)
+        });
+        assert_eq!(result, Ok(LangId::NEUTRAL));
+        assert_eq!(requested, [LangId::NEUTRAL]);
     }
 }
@@ -0,0 +1,579 @@
+use crate::call_BOOL;
+use crate::common::pod::{self, Pod};
+use crate::win32::core::{Result, Win32Error};
+use crate::win32::system::threading::process::{
+    ProcessAccessRights, ProcessHandle, PROCESS_VM_OPERATION, PROCESS_VM_READ, PROCESS_VM_WRITE,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ffi::c_void;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem::{size_of, size_of_val};
+use core::ptr::{self, addr_of_mut};
+use windows_sys::Win32::Foundation::ERROR_PARTIAL_COPY;
+use windows_sys::Win32::System::Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory};
+
+/// The address of a `T` in the virtual address space of another process.
+///
+/// # Remarks
+///
+/// * A [`RemotePtr`] is never dereferenced, so it cannot be confused with a pointer to local memory.
+///   The pointed value can be accessed using [`read_value`] and [`write_value`].
+/// * The arithmetic methods wrap around on overflow, like `pointer::wrapping_add`.
+///
+#[repr(transparent)]
+pub struct RemotePtr<T> {
+    /// The address in the other process.
+    address: usize,
+    /// The type of the pointed value. `fn() -> T` keeps the pointer `Send` and `Sync` for any `T`.
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> RemotePtr<T> {
+    #[inline]
+    /// Creates a new [`RemotePtr`] that points to `address` in another process.
+    pub const fn new(address: usize) -> Self {
+        Self {
+            address,
+            _type: PhantomData,
+        }
+    }
+
+    #[inline]
+    /// Creates a null [`RemotePtr`].
+    pub const fn null() -> Self {
+        Self::new(0)
+    }
+
+    #[inline]
+    /// Gets the address of the pointer.
+    pub const fn address(self) -> usize {
+        self.address
+    }
+
+    #[inline]
+    /// Determines whether the pointer is null.
+    pub const fn is_null(self) -> bool {
+        self.address == 0
+    }
+
+    #[inline]
+    /// Casts the pointer to a pointer of another type.
+    pub const fn cast<U>(self) -> RemotePtr<U> {
+        RemotePtr::new(self.address)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Calculates the address of the `count`-th `T` after the pointed one.
+    pub const fn wrapping_add(self, count: usize) -> Self {
+        self.wrapping_byte_add(count.wrapping_mul(size_of::<T>()))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Calculates the address that is `bytes` bytes after the pointed one.
+    pub const fn wrapping_byte_add(self, bytes: usize) -> Self {
+        Self::new(self.address.wrapping_add(bytes))
+    }
+
+    #[inline]
+    /// Gets the address as a pointer that can be passed to the Windows API functions. It must not be dereferenced.
+    const fn as_raw(self) -> *const c_void {
+        self.address as *const c_void
+    }
+}
+
+impl<T> Clone for RemotePtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RemotePtr<T> {}
+
+impl<T> Default for RemotePtr<T> {
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T> PartialEq for RemotePtr<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl<T> Eq for RemotePtr<T> {}
+
+impl<T> PartialOrd for RemotePtr<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for RemotePtr<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.address.cmp(&other.address)
+    }
+}
+
+impl<T> Hash for RemotePtr<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for RemotePtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RemotePtr({:#x})", self.address)
+    }
+}
+
+impl<T> fmt::Pointer for RemotePtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.as_raw(), f)
+    }
+}
+
+impl<T> From<RemotePtr<T>> for usize {
+    fn from(value: RemotePtr<T>) -> Self {
+        value.address
+    }
+}
+
+// Safety: A `RemotePtr` is a `usize` and every address is a valid value.
+unsafe impl<T: 'static> Pod for RemotePtr<T> {}
+
+/// Reads `buffer.len()` values starting at `address` in the specified process.
+///
+/// # Remarks
+///
+/// * The whole range must be readable. Use [`read_partial`] to get the readable part of the range.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_VM_READ`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * Only part of the range is readable. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-readprocessmemory
+///
+pub fn read<T: Pod, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<T>,
+    buffer: &mut [T],
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_VM_READ>();
+    call_BOOL! {
+        ReadProcessMemory(
+            handle.as_raw(),
+            address.as_raw(),
+            buffer.as_mut_ptr().cast(),
+            size_of_val(buffer),
+            ptr::null_mut()
+        )
+    }
+}
+
+/// Reads as many bytes as possible of the `buffer.len()` values starting at `address` in the specified process,
+/// and returns the number of bytes that were read.
+///
+/// # Remarks
+///
+/// * The copy stops at the first byte that is not readable, so the result is less than the size of `buffer`
+///   if only the beginning of the range is readable (`ERROR_PARTIAL_COPY`).
+/// * The last value in `buffer` that was copied partially keeps its remaining bytes.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails for any reason other than a partial copy.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_VM_READ`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-readprocessmemory
+///
+pub fn read_partial<T: Pod, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<T>,
+    buffer: &mut [T],
+) -> Result<usize> {
+    let handle = handle.require_rights::<PROCESS_VM_READ>();
+    let mut bytes_read = 0;
+    // Safety: `buffer` is valid for writes of its whole size and the system never writes more than that.
+    let succeeded = unsafe {
+        ReadProcessMemory(
+            handle.as_raw(),
+            address.as_raw(),
+            buffer.as_mut_ptr().cast(),
+            size_of_val(buffer),
+            addr_of_mut!(bytes_read),
+        )
+    };
+    if succeeded == 0 {
+        let error = Win32Error::get_last_from("ReadProcessMemory");
+        if error.code() != ERROR_PARTIAL_COPY {
+            return Err(error);
+        }
+    }
+
+    Ok(bytes_read)
+}
+
+/// Reads the value at `address` in the specified process.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_VM_READ`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * The value is not readable, or only part of it is. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-readprocessmemory
+///
+pub fn read_value<T: Pod, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<T>,
+) -> Result<T> {
+    let mut value = pod::zeroed::<T>();
+    read(handle, address, core::slice::from_mut(&mut value))?;
+    Ok(value)
+}
+
+/// Reads up to `len` values starting at `address` in the specified process.
+///
+/// # Remarks
+///
+/// * If only the beginning of the range is readable (`ERROR_PARTIAL_COPY`), the result contains
+///   the values that were copied completely, so its length is less than `len`.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_VM_READ`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * Not even the first value is readable completely. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-readprocessmemory
+///
+pub fn read_vec<T: Pod, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<T>,
+    len: usize,
+) -> Result<Vec<T>> {
+    let mut values = vec![pod::zeroed::<T>(); len];
+    if len == 0 || size_of::<T>() == 0 {
+        return Ok(values);
+    }
+
+    let values_read = whole_values::<T>(read_partial(handle, address, &mut values)?, len);
+    if values_read == 0 {
+        return Err(Win32Error::new(ERROR_PARTIAL_COPY).with_function("ReadProcessMemory"));
+    }
+
+    values.truncate(values_read);
+    Ok(values)
+}
+
+/// Gets the number of values out of `len` that were copied completely when `bytes_copied` bytes were copied.
+const fn whole_values<T>(bytes_copied: usize, len: usize) -> usize {
+    match size_of::<T>() {
+        0 => len,
+        size => {
+            let values = bytes_copied / size;
+            if values < len {
+                values
+            } else {
+                len
+            }
+        }
+    }
+}
+
+/// Writes `values` starting at `address` in the specified process.
+///
+/// # Remarks
+///
+/// * The whole range must be writable. Use [`write_partial`] to write the writable part of the range.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_VM_WRITE`] and [`PROCESS_VM_OPERATION`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * Only part of the range is writable. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-writeprocessmemory
+///
+pub fn write<T: Pod, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<T>,
+    values: &[T],
+) -> Result<()> {
+    let handle = handle.require_rights::<{ PROCESS_VM_WRITE | PROCESS_VM_OPERATION }>();
+    call_BOOL! {
+        WriteProcessMemory(
+            handle.as_raw(),
+            address.as_raw(),
+            values.as_ptr().cast(),
+            size_of_val(values),
+            ptr::null_mut()
+        )
+    }
+}
+
+/// Writes as many bytes as possible of `values` starting at `address` in the specified process,
+/// and returns the number of bytes that were written.
+///
+/// # Remarks
+///
+/// * The copy stops at the first byte that is not writable, so the result is less than the size of `values`
+///   if only the beginning of the range is writable (`ERROR_PARTIAL_COPY`).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails for any reason other than a partial copy.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_VM_WRITE`] and [`PROCESS_VM_OPERATION`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-writeprocessmemory
+///
+pub fn write_partial<T: Pod, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<T>,
+    values: &[T],
+) -> Result<usize> {
+    let handle = handle.require_rights::<{ PROCESS_VM_WRITE | PROCESS_VM_OPERATION }>();
+    let mut bytes_written = 0;
+    // Safety: `values` is valid for reads of its whole size and the system never reads more than that.
+    let succeeded = unsafe {
+        WriteProcessMemory(
+            handle.as_raw(),
+            address.as_raw(),
+            values.as_ptr().cast(),
+            size_of_val(values),
+            addr_of_mut!(bytes_written),
+        )
+    };
+    if succeeded == 0 {
+        let error = Win32Error::get_last_from("WriteProcessMemory");
+        if error.code() != ERROR_PARTIAL_COPY {
+            return Err(error);
+        }
+    }
+
+    Ok(bytes_written)
+}
+
+/// Writes `value` at `address` in the specified process.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_VM_WRITE`] and [`PROCESS_VM_OPERATION`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * The value is not writable, or only part of it is. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-writeprocessmemory
+///
+pub fn write_value<T: Pod, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<T>,
+    value: T,
+) -> Result<()> {
+    write(handle, address, core::slice::from_ref(&value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn wrapping_add_scales_by_the_size_of_the_value() {
+        assert_eq!(
+            RemotePtr::<u8>::new(0x1000).wrapping_add(3).address(),
+            0x1003
+        );
+        assert_eq!(
+            RemotePtr::<u32>::new(0x1000).wrapping_add(3).address(),
+            0x100C
+        );
+        assert_eq!(
+            RemotePtr::<[u64; 2]>::new(0x1000).wrapping_add(2).address(),
+            0x1020
+        );
+        assert_eq!(
+            RemotePtr::<()>::new(0x1000).wrapping_add(5).address(),
+            0x1000
+        );
+        assert_eq!(
+            RemotePtr::<u32>::new(0x1000).wrapping_byte_add(3).address(),
+            0x1003
+        );
+    }
+
+    #[test]
+    fn wrapping_add_wraps_around() {
+        assert_eq!(
+            RemotePtr::<u8>::new(usize::MAX).wrapping_add(1),
+            RemotePtr::null()
+        );
+        assert_eq!(
+            RemotePtr::<u32>::new(usize::MAX - 3)
+                .wrapping_add(2)
+                .address(),
+            4
+        );
+        assert_eq!(
+            RemotePtr::<u16>::new(0).wrapping_add(usize::MAX).address(),
+            usize::MAX - 1
+        );
+        assert!(RemotePtr::<u64>::new(usize::MAX - 7)
+            .wrapping_add(1)
+            .is_null());
+    }
+
+    #[test]
+    fn cast_keeps_the_address() {
+        let pointer = RemotePtr::<u32>::new(0x1234).cast::<[u8; 3]>();
+        assert_eq!(pointer.address(), 0x1234);
+        // The size of the new type is used from now on.
+        assert_eq!(pointer.wrapping_add(1).address(), 0x1237);
+        assert!(RemotePtr::<u8>::null().cast::<u64>().is_null());
+    }
+
+    #[test]
+    fn remote_ptrs_are_ordered_and_hashed_by_address() {
+        let low = RemotePtr::<u32>::new(0x10);
+        let high = RemotePtr::<u32>::new(0x20);
+        assert!(low < high);
+        assert_eq!(low.cmp(&high), Ordering::Less);
+        assert_eq!(high.max(low), high);
+        assert_eq!(
+            low.partial_cmp(&RemotePtr::new(0x10)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(RemotePtr::<u32>::default(), RemotePtr::null());
+        assert_eq!(usize::from(high), 0x20);
+
+        assert_eq!(hash_of(&low), hash_of(&0x10_usize));
+        assert_ne!(hash_of(&low), hash_of(&high));
+    }
+
+    #[test]
+    fn remote_ptrs_are_formatted_as_addresses() {
+        assert_eq!(
+            format!("{:?}", RemotePtr::<u32>::new(0x1F)),
+            "RemotePtr(0x1f)"
+        );
+        assert_eq!(format!("{:?}", RemotePtr::<u8>::null()), "RemotePtr(0x0)");
+        assert_eq!(format!("{:p}", RemotePtr::<u32>::new(0x1F)), "0x1f");
+    }
+
+    #[test]
+    fn whole_values_ignores_partially_copied_values() {
+        let cases = [
+            ("everything copied", 16, 4, 4),
+            ("nothing copied", 0, 4, 0),
+            ("mid-element", 10, 4, 2),
+            ("less than one value", 3, 4, 0),
+            ("more than requested", 20, 4, 4),
+        ];
+        for (case, bytes_copied, len, expected) in cases {
+            assert_eq!(whole_values::<u32>(bytes_copied, len), expected, "{case}");
+        }
+
+        assert_eq!(whole_values::<[u8; 3]>(8, 4), 2);
+        assert_eq!(whole_values::<u8>(3, 3), 3);
+    }
+
+    #[test]
+    fn whole_values_counts_every_zero_sized_value() {
+        assert_eq!(whole_values::<()>(0, 5), 5);
+        assert_eq!(whole_values::<()>(0, 0), 0);
+        assert_eq!(whole_values::<[u32; 0]>(0, 7), 7);
+    }
+
+    /// Hashes `value` with a deterministic hasher.
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = Fnv1a(0xCBF2_9CE4_8422_2325);
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// A minimal FNV-1a [`Hasher`] that works without `std`.
+    struct Fnv1a(u64);
+
+    impl Hasher for Fnv1a {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01B3);
+            }
+        }
+    }
+}
@@ -1,18 +1,29 @@
+use crate::call_num;
 use crate::default_sized;
+use crate::win32::backend::{Backend, Native};
 use crate::win32::core::Result;
-use crate::{call_BOOL, call_num};
+use crate::win32::foundation::{KernelObject, ObjectHandle, OwnedHandle};
 use core::mem::size_of;
 use windows_sys::Win32::Foundation::ERROR_NO_MORE_FILES;
 use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
-use windows_sys::Win32::System::Diagnostics::ToolHelp::{
-    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, Thread32First, Thread32Next,
-};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::CreateToolhelp32Snapshot;
 
 pub use windows_sys::Win32::System::Diagnostics::ToolHelp::{
     CREATE_TOOLHELP_SNAPSHOT_FLAGS, PROCESSENTRY32W, TH32CS_SNAPPROCESS, TH32CS_SNAPTHREAD,
     THREADENTRY32,
 };
 
+#[derive(Debug)]
+/// The toolhelp snapshot kernel object. See [`SnapshotHandle`].
+pub struct SnapshotObject;
+
+impl KernelObject for SnapshotObject {
+    const IMPLIED_RIGHTS: &'static [(u32, u32)] = &[];
+}
+
+/// An owned toolhelp snapshot handle. See [`create_snapshot`].
+pub type SnapshotHandle = ObjectHandle<SnapshotObject, 0>;
+
 /// Takes a snapshot of the specified processes, as well as the heaps, modules, and threads used by these processes.
 ///
 /// # Arguments
@@ -23,6 +34,7 @@ pub use windows_sys::Win32::System::Diagnostics::ToolHelp::{
 /// # Remarks
 ///
 /// * `pid` parameter can be zero to indicate the current process.
+/// * The returned handle is closed when it is dropped.
 /// * `pid` parameter is used when the [TH32CS_SNAPHEAPLIST], [TH32CS_SNAPMODULE], [TH32CS_SNAPMODULE32], or [TH32CS_SNAPALL] value is specified.
 ///   Otherwise, it is ignored and all processes are included in the snapshot.
 ///
@@ -32,8 +44,8 @@ pub use windows_sys::Win32::System::Diagnostics::ToolHelp::{
 ///
 /// ## Possible errors
 ///
-/// * `pid` is the idle process or one of the CSRSS processes. ([ERROR_ACCESS_DENIED])
-/// * `pid` is a 64-bit process and the caller is a 32-bit process. ([ERROR_PARTIAL_COPY])
+/// * `pid` is the idle process or one of the CSRSS processes. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `pid` is a 64-bit process and the caller is a 32-bit process. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
 ///
 /// # Examples
 /// TODO
@@ -41,15 +53,15 @@ pub use windows_sys::Win32::System::Diagnostics::ToolHelp::{
 /// For more information see the official [documentation].
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/tlhelp32/nf-tlhelp32-createtoolhelp32snapshot
-/// [ERROR_ACCESS_DENIED]: windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED
-/// [ERROR_PARTIAL_COPY]: windows_sys::Win32::Foundation::ERROR_PARTIAL_COPY
 /// [TH32CS_SNAPHEAPLIST]: windows_sys::Win32::System::Diagnostics::ToolHelp::TH32CS_SNAPHEAPLIST
 /// [TH32CS_SNAPMODULE]: windows_sys::Win32::System::Diagnostics::ToolHelp::TH32CS_SNAPMODULE
 /// [TH32CS_SNAPMODULE32]: windows_sys::Win32::System::Diagnostics::ToolHelp::TH32CS_SNAPMODULE32
 /// [TH32CS_SNAPALL]: windows_sys::Win32::System::Diagnostics::ToolHelp::TH32CS_SNAPALL
 ///
-pub fn create_snapshot(flags: CREATE_TOOLHELP_SNAPSHOT_FLAGS, pid: u32) -> Result<isize> {
-    call_num! { CreateToolhelp32Snapshot(flags, pid) != INVALID_HANDLE_VALUE }
+pub fn create_snapshot(flags: CREATE_TOOLHELP_SNAPSHOT_FLAGS, pid: u32) -> Result<SnapshotHandle> {
+    let handle = call_num! { CreateToolhelp32Snapshot(flags, pid) != INVALID_HANDLE_VALUE }?;
+    // Safety: `handle` is a newly created snapshot handle that is not owned by anything else.
+    Ok(unsafe { SnapshotHandle::from_owned(OwnedHandle::from_raw(handle)) })
 }
 
 /// Retrieves information about the first process recorded in the specified system snapshot.
@@ -67,7 +79,7 @@ pub fn create_snapshot(flags: CREATE_TOOLHELP_SNAPSHOT_FLAGS, pid: u32) -> Resul
 ///
 /// # Possible errors
 ///
-/// * `handle` is invalid.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
 ///
 /// # Examples
 /// TODO
@@ -76,13 +88,32 @@ pub fn create_snapshot(flags: CREATE_TOOLHELP_SNAPSHOT_FLAGS, pid: u32) -> Resul
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/tlhelp32/nf-tlhelp32-process32firstw
 ///
-pub fn first_process(handle: isize) -> Result<Option<PROCESSENTRY32W>> {
-    call_BOOL! {
-        Process32FirstW(handle, &mut entry) -> Result<Option> {
-            mut entry = default_sized!(mut PROCESSENTRY32W: SnapshotEntry);
-            ERROR_NO_MORE_FILES => None;
-        }
-    }
+#[inline]
+pub fn first_process(handle: &SnapshotHandle) -> Result<Option<PROCESSENTRY32W>> {
+    first_process_in(&Native, handle)
+}
+
+/// Retrieves information about the first process recorded in the specified system snapshot by using the specified [`Backend`].
+/// See [`first_process`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn first_process_in<B: Backend>(
+    backend: &B,
+    handle: &SnapshotHandle,
+) -> Result<Option<PROCESSENTRY32W>> {
+    let mut entry = default_sized!(mut PROCESSENTRY32W: SnapshotEntry);
+    let result = backend.process32_first(handle.as_handle(), &mut entry);
+    entry_or_none(result, entry)
 }
 
 /// Retrieves information about the next process recorded in the specified system snapshot.
@@ -100,7 +131,7 @@ pub fn first_process(handle: isize) -> Result<Option<PROCESSENTRY32W>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
 ///
 /// # Examples
 /// TODO
@@ -109,13 +140,32 @@ pub fn first_process(handle: isize) -> Result<Option<PROCESSENTRY32W>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/tlhelp32/nf-tlhelp32-process32nextw
 ///
-pub fn next_process(handle: isize) -> Result<Option<PROCESSENTRY32W>> {
-    call_BOOL! {
-        Process32NextW(handle, &mut entry) -> Result<Option> {
-            mut entry = default_sized!(mut PROCESSENTRY32W: SnapshotEntry);
-            ERROR_NO_MORE_FILES => None;
-        }
-    }
+#[inline]
+pub fn next_process(handle: &SnapshotHandle) -> Result<Option<PROCESSENTRY32W>> {
+    next_process_in(&Native, handle)
+}
+
+/// Retrieves information about the next process recorded in the specified system snapshot by using the specified [`Backend`].
+/// See [`next_process`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn next_process_in<B: Backend>(
+    backend: &B,
+    handle: &SnapshotHandle,
+) -> Result<Option<PROCESSENTRY32W>> {
+    let mut entry = default_sized!(mut PROCESSENTRY32W: SnapshotEntry);
+    let result = backend.process32_next(handle.as_handle(), &mut entry);
+    entry_or_none(result, entry)
 }
 
 /// Retrieves information about the first thread of any process encountered in the specified system snapshot.
@@ -133,7 +183,7 @@ pub fn next_process(handle: isize) -> Result<Option<PROCESSENTRY32W>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
 ///
 /// # Examples
 /// TODO
@@ -142,13 +192,32 @@ pub fn next_process(handle: isize) -> Result<Option<PROCESSENTRY32W>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/tlhelp32/nf-tlhelp32-thread32first
 ///
-pub fn first_thread(handle: isize) -> Result<Option<THREADENTRY32>> {
-    call_BOOL! {
-        Thread32First(handle, &mut entry) -> Result<Option> {
-            mut entry = default_sized!(mut THREADENTRY32: SnapshotEntry);
-            ERROR_NO_MORE_FILES => None;
-        }
-    }
+#[inline]
+pub fn first_thread(handle: &SnapshotHandle) -> Result<Option<THREADENTRY32>> {
+    first_thread_in(&Native, handle)
+}
+
+/// Retrieves information about the first thread of any process encountered in the specified system snapshot by using the specified [`Backend`].
+/// See [`first_thread`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn first_thread_in<B: Backend>(
+    backend: &B,
+    handle: &SnapshotHandle,
+) -> Result<Option<THREADENTRY32>> {
+    let mut entry = default_sized!(mut THREADENTRY32: SnapshotEntry);
+    let result = backend.thread32_first(handle.as_handle(), &mut entry);
+    entry_or_none(result, entry)
 }
 
 /// Retrieves information about the next thread of any process encountered in the specified system snapshot.
@@ -166,7 +235,7 @@ pub fn first_thread(handle: isize) -> Result<Option<THREADENTRY32>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
 ///
 /// # Examples
 /// TODO
@@ -175,11 +244,103 @@ pub fn first_thread(handle: isize) -> Result<Option<THREADENTRY32>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/tlhelp32/nf-tlhelp32-thread32next
 ///
-pub fn next_thread(handle: isize) -> Result<Option<THREADENTRY32>> {
-    call_BOOL! {
-        Thread32Next(handle, &mut entry) -> Result<Option> {
-            mut entry = default_sized!(mut THREADENTRY32: SnapshotEntry);
-            ERROR_NO_MORE_FILES => None;
+#[inline]
+pub fn next_thread(handle: &SnapshotHandle) -> Result<Option<THREADENTRY32>> {
+    next_thread_in(&Native, handle)
+}
+
+/// Retrieves information about the next thread of any process encountered in the specified system snapshot by using the specified [`Backend`].
+/// See [`next_thread`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn next_thread_in<B: Backend>(
+    backend: &B,
+    handle: &SnapshotHandle,
+) -> Result<Option<THREADENTRY32>> {
+    let mut entry = default_sized!(mut THREADENTRY32: SnapshotEntry);
+    let result = backend.thread32_next(handle.as_handle(), &mut entry);
+    entry_or_none(result, entry)
+}
+
+/// Converts the result of a snapshot entry retrieval to [`None`] if there are no more entries (`ERROR_NO_MORE_FILES`).
+fn entry_or_none<T>(result: Result<()>, entry: T) -> Result<Option<T>> {
+    match result {
+        Ok(()) => Ok(Some(entry)),
+        Err(error) if error.code() == ERROR_NO_MORE_FILES => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::win32::backend::mock::{fake_handle, ScriptedBackend, Step};
+    use crate::win32::core::Win32Error;
+    use alloc::vec::Vec;
+    use windows_sys::Win32::Foundation::ERROR_INVALID_HANDLE;
+
+    /// Creates a process snapshot entry with the specified process identifier.
+    const fn process_entry(pid: u32) -> PROCESSENTRY32W {
+        // Safety: Every field of the entry is an integer or an array of integers.
+        let mut entry: PROCESSENTRY32W = unsafe { core::mem::zeroed() };
+        entry.th32ProcessID = pid;
+        entry
+    }
+
+    #[test]
+    fn process_iteration_ends_with_none() {
+        // Safety: The handle is only passed to the scripted backend.
+        let snapshot = unsafe { fake_handle::<SnapshotObject, 0>() };
+        let backend = ScriptedBackend::new()
+            .then(Step::Process32First(Ok(Some(process_entry(4)))))
+            .then(Step::Process32Next(Ok(Some(process_entry(8)))))
+            .then(Step::Process32Next(Ok(None)));
+
+        let mut pids = Vec::new();
+        let mut entry = first_process_in(&backend, &snapshot).unwrap();
+        while let Some(process) = entry {
+            pids.push(process.th32ProcessID);
+            entry = next_process_in(&backend, &snapshot).unwrap();
         }
+
+        assert_eq!(pids, [4, 8]);
+        assert!(backend.is_finished());
+    }
+
+    #[test]
+    fn empty_process_snapshot_yields_none() {
+        // Safety: The handle is only passed to the scripted backend.
+        let snapshot = unsafe { fake_handle::<SnapshotObject, 0>() };
+        let backend = ScriptedBackend::new().then(Step::Process32First(Ok(None)));
+
+        assert!(first_process_in(&backend, &snapshot).unwrap().is_none());
+    }
+
+    #[test]
+    fn process_iteration_passes_errors_through() {
+        // Safety: The handle is only passed to the scripted backend.
+        let snapshot = unsafe { fake_handle::<SnapshotObject, 0>() };
+        let backend = ScriptedBackend::new()
+            .then(Step::Process32First(Ok(Some(process_entry(4)))))
+            .then(Step::Process32Next(Err(Win32Error::new(
+                ERROR_INVALID_HANDLE,
+            ))));
+
+        assert!(first_process_in(&backend, &snapshot).unwrap().is_some());
+        let Err(error) = next_process_in(&backend, &snapshot) else {
+            panic!("the error of Process32NextW was swallowed");
+        };
+        assert_eq!(error.code(), ERROR_INVALID_HANDLE);
+        assert!(backend.is_finished());
     }
 }
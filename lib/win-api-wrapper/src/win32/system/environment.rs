@@ -0,0 +1,461 @@
+use crate::common::buffer::{negotiate, BufferPolicy, Fill};
+use crate::common::command_line;
+use crate::common::path::to_upper;
+use crate::common::wide::{self, IntoWideCStr, U16CStr, U16Str, U16String};
+use crate::win32::core::{Result, Win32Error};
+use crate::{call_BOOL, call_num};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use windows_sys::core::PCWSTR;
+use windows_sys::Win32::Foundation::ERROR_INVALID_PARAMETER;
+use windows_sys::Win32::System::Environment::{
+    ExpandEnvironmentStringsW, FreeEnvironmentStringsW, GetCommandLineW, GetEnvironmentStringsW,
+};
+
+/// The equals sign that separates the name and the value of an environment variable.
+const EQUALS: u16 = b'=' as u16;
+
+/// The maximum length of the buffer that is used by [`expand_environment_strings`].
+pub const MAX_EXPANDED_BUFFER_LEN: usize = u32::MAX as usize;
+
+/// Gets the command line of the current process.
+///
+/// # Remarks
+///
+/// * The returned string is owned by the process and it lives until the process exits.
+/// * Use [`get_args`] to split the command line into arguments.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processenv/nf-processenv-getcommandlinew
+///
+pub fn get_command_line() -> &'static U16CStr {
+    // Safety: `GetCommandLineW` never fails and the returned string is owned by the process, which never frees it.
+    unsafe { wide::from_ptr(GetCommandLineW()) }
+}
+
+/// Gets the arguments of the current process, including the program name.
+/// The command line is split like `CommandLineToArgvW` does. See [`command_line::parse`].
+///
+/// # Examples
+/// TODO
+///
+pub fn get_args() -> Vec<U16String> {
+    command_line::parse(get_command_line().as_ustr())
+}
+
+/// Compares two environment variable names ignoring the case like the system sorts an environment block.
+fn cmp_names(a: &[u16], b: &[u16]) -> Ordering {
+    a.iter()
+        .map(|&unit| to_upper(unit))
+        .cmp(b.iter().map(|&unit| to_upper(unit)))
+}
+
+/// Checks that `name` can be the name of an environment variable.
+fn check_name(name: &[u16]) -> Result<()> {
+    match name.split_first() {
+        // The first character can be an equals sign, because of the hidden drive entries (e.g. `=C:`).
+        Some((_, rest)) if !rest.contains(&EQUALS) && !name.contains(&0) => Ok(()),
+        _ => Err(Win32Error::new(ERROR_INVALID_PARAMETER)
+            .with_context("invalid environment variable name")),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The environment variables of a process in the format of an environment block:
+/// a sequence of null-terminated `name=value` strings that ends with an empty string.
+///
+/// # Remarks
+///
+/// * The names are compared ignoring the case and the variables are kept sorted by name, like `CreateProcessW` expects.
+/// * The hidden entries that store the current directory of each drive (e.g. `=C:=C:\Windows`) are ordinary variables
+///   whose name starts with an equals sign. They are sorted by the same rule, so they follow the variables
+///   whose name starts with a character below the equals sign (e.g. a digit or `!`).
+/// * The block of the current process can be retrieved using [`get_environment_strings`].
+///
+pub struct EnvironmentBlock {
+    /// The `(name, value)` pairs sorted by name ignoring the case.
+    variables: Vec<(U16String, U16String)>,
+}
+
+impl EnvironmentBlock {
+    #[inline]
+    /// Creates a new empty [`EnvironmentBlock`].
+    pub const fn new() -> Self {
+        Self {
+            variables: Vec::new(),
+        }
+    }
+
+    /// Parses an environment block.
+    ///
+    /// # Remarks
+    ///
+    /// * Parsing stops at the first empty string or at the end of `block`, so the terminating null characters are optional.
+    /// * Strings that don't contain an equals sign after their first character are skipped.
+    /// * If a name occurs multiple times, the first occurrence is kept like `GetEnvironmentVariableW` does.
+    ///
+    pub fn parse(block: &[u16]) -> Self {
+        let mut environment = Self::new();
+        for entry in block
+            .split(|&unit| unit == 0)
+            .take_while(|entry| !entry.is_empty())
+        {
+            let Some(separator) = entry
+                .iter()
+                .skip(1)
+                .position(|&unit| unit == EQUALS)
+                .map(|position| position + 1)
+            else {
+                continue;
+            };
+
+            let (name, value) = (&entry[..separator], &entry[separator + 1..]);
+            if let Err(index) = environment.search(name) {
+                environment.variables.insert(
+                    index,
+                    (U16String::from_vec(name), U16String::from_vec(value)),
+                );
+            }
+        }
+
+        environment
+    }
+
+    /// Parses an environment block that is stored in memory (e.g. the result of `GetEnvironmentStringsW`). See [`EnvironmentBlock::parse`].
+    ///
+    /// # Safety
+    ///
+    /// If any of the following conditions are violated, the result is Undefined Behavior:
+    ///
+    /// * `ptr` must be non-null and it must point to the first element of an environment block.
+    /// * The memory of the block must be readable until the empty string that ends it.
+    ///
+    pub unsafe fn from_ptr(ptr: PCWSTR) -> Self {
+        let mut len = 0;
+        loop {
+            // Safety: The caller guarantees that the block is readable until the empty string.
+            let entry_len = unsafe { wide::len(ptr.add(len)) };
+            len += entry_len + 1;
+            if entry_len == 0 {
+                break;
+            }
+        }
+
+        // Safety: The `len` elements were read above.
+        Self::parse(unsafe { core::slice::from_raw_parts(ptr, len) })
+    }
+
+    /// Gets the value of the variable `name`. The name is compared ignoring the case.
+    pub fn get(&self, name: &U16Str) -> Option<&U16Str> {
+        self.search(name.as_slice())
+            .ok()
+            .map(|index| self.variables[index].1.as_ustr())
+    }
+
+    #[inline]
+    /// Determines whether the variable `name` exists. The name is compared ignoring the case.
+    pub fn contains(&self, name: &U16Str) -> bool {
+        self.search(name.as_slice()).is_ok()
+    }
+
+    /// Sets the variable `name` to `value` and returns the previous value.
+    ///
+    /// # Remarks
+    ///
+    /// * The name is compared ignoring the case. If the variable already exists, its name keeps the original case.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the variable cannot be set.
+    ///
+    /// ## Possible errors
+    ///
+    /// * `name` is empty or it contains an equals sign after its first character. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+    /// * `name` or `value` contains a null character. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+    ///
+    pub fn set(&mut self, name: &U16Str, value: &U16Str) -> Result<Option<U16String>> {
+        check_name(name.as_slice())?;
+        if value.as_slice().contains(&0) {
+            return Err(Win32Error::new(ERROR_INVALID_PARAMETER)
+                .with_context("the environment variable value contains a null character"));
+        }
+
+        match self.search(name.as_slice()) {
+            Ok(index) => Ok(Some(core::mem::replace(
+                &mut self.variables[index].1,
+                U16String::from(value),
+            ))),
+            Err(index) => {
+                self.variables
+                    .insert(index, (U16String::from(name), U16String::from(value)));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes the variable `name` and returns its value. The name is compared ignoring the case.
+    pub fn remove(&mut self, name: &U16Str) -> Option<U16String> {
+        self.search(name.as_slice())
+            .ok()
+            .map(|index| self.variables.remove(index).1)
+    }
+
+    #[inline]
+    /// Removes every variable.
+    pub fn clear(&mut self) {
+        self.variables.clear();
+    }
+
+    #[inline]
+    /// Returns an iterator over the `(name, value)` pairs sorted by name ignoring the case.
+    pub fn iter(&self) -> impl Iterator<Item = (&U16Str, &U16Str)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_ustr(), value.as_ustr()))
+    }
+
+    #[inline]
+    /// Gets the number of variables.
+    pub const fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    #[inline]
+    /// Determines whether there are no variables.
+    pub const fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    /// Serializes the variables to an environment block that can be passed to
+    /// [`process::create`][`crate::win32::system::threading::process::create`] with `CREATE_UNICODE_ENVIRONMENT`.
+    ///
+    /// # Remarks
+    ///
+    /// * The block is sorted by name ignoring the case and it ends with two null characters, even if it is empty.
+    ///
+    pub fn to_block(&self) -> Vec<u16> {
+        let mut block = Vec::new();
+        for (name, value) in &self.variables {
+            block.extend_from_slice(name.as_slice());
+            block.push(EQUALS);
+            block.extend_from_slice(value.as_slice());
+            block.push(0);
+        }
+        if block.is_empty() {
+            block.push(0);
+        }
+        block.push(0);
+        block
+    }
+
+    /// Searches the variable `name` ignoring the case.
+    /// The result is the index of the variable, or the index where it should be inserted.
+    fn search(&self, name: &[u16]) -> core::result::Result<usize, usize> {
+        self.variables
+            .binary_search_by(|(existing, _)| cmp_names(existing.as_slice(), name))
+    }
+}
+
+/// Gets the environment variables of the current process.
+///
+/// # Remarks
+///
+/// * Unlike [`std::env::vars_os`], the result includes the hidden entries that store the current directory of each drive (e.g. `=C:`).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processenv/nf-processenv-getenvironmentstringsw
+///
+pub fn get_environment_strings() -> Result<EnvironmentBlock> {
+    // Safety: `GetEnvironmentStringsW` has no preconditions.
+    let block = unsafe { GetEnvironmentStringsW() };
+    if block.is_null() {
+        return Err(Win32Error::get_last_from("GetEnvironmentStringsW"));
+    }
+
+    // Safety: `block` is a valid environment block that is owned by this function until it is freed below.
+    let environment = unsafe { EnvironmentBlock::from_ptr(block) };
+    call_BOOL! { FreeEnvironmentStringsW(block) }?;
+    Ok(environment)
+}
+
+/// Expands the environment variable references (e.g. `%SystemRoot%`) in `source`
+/// using the environment variables of the current process.
+///
+/// # Remarks
+///
+/// * References to variables that don't exist are left unchanged.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `source` contains a null character. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processenv/nf-processenv-expandenvironmentstringsw
+///
+pub fn expand_environment_strings<S: IntoWideCStr + ?Sized>(source: &S) -> Result<U16String> {
+    let source = source.to_wide_c_str()?;
+    let policy = BufferPolicy::new(source.len() + 1, MAX_EXPANDED_BUFFER_LEN);
+    let buffer = negotiate(policy, |buffer: &mut [u16]| {
+        // The result is the required length including the null character, even if it fits into the buffer.
+        let required_len = call_num! {
+            ExpandEnvironmentStringsW(source.as_ptr(), buffer.as_mut_ptr(), buffer.len() as u32) != 0 as usize
+        }?;
+        Ok(if required_len > buffer.len() {
+            Fill::TooSmall(Some(required_len))
+        } else {
+            Fill::Written(required_len - 1)
+        })
+    })?;
+
+    Ok(U16String::from_vec(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use widestring::u16str;
+
+    /// Encodes `block` as UTF-16.
+    fn wide(block: &str) -> Vec<u16> {
+        block.encode_utf16().collect()
+    }
+
+    /// Collects the variables as `name=value` strings.
+    fn entries(environment: &EnvironmentBlock) -> Vec<String> {
+        environment
+            .iter()
+            .map(|(name, value)| alloc::format!("{}={}", name.display(), value.display()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_keeps_the_drive_entries() {
+        let environment = EnvironmentBlock::parse(&wide("=C:=C:\\x\0PATH=C:\\bin\0\0"));
+
+        assert_eq!(entries(&environment), ["=C:=C:\\x", "PATH=C:\\bin"]);
+        assert_eq!(environment.get(u16str!("=c:")), Some(u16str!("C:\\x")));
+    }
+
+    #[test]
+    fn parse_keeps_the_first_duplicate() {
+        let environment = EnvironmentBlock::parse(&wide("Path=a\0PATH=b\0path=c\0\0"));
+
+        assert_eq!(entries(&environment), ["Path=a"]);
+    }
+
+    #[test]
+    fn parse_skips_entries_without_an_equals_sign() {
+        let environment = EnvironmentBlock::parse(&wide("A\0=B\0C=\0D==\0\0"));
+
+        assert_eq!(entries(&environment), ["C=", "D=="]);
+        assert_eq!(environment.get(u16str!("D")), Some(u16str!("=")));
+    }
+
+    #[test]
+    fn parse_doesnt_require_the_terminating_nulls() {
+        let expected = ["A=1", "B=2"];
+        for block in ["A=1\0B=2\0\0", "A=1\0B=2\0", "A=1\0B=2"] {
+            assert_eq!(
+                entries(&EnvironmentBlock::parse(&wide(block))),
+                expected,
+                "{block:?}"
+            );
+        }
+        // Parsing stops at the first empty string.
+        assert_eq!(
+            entries(&EnvironmentBlock::parse(&wide("A=1\0\0B=2\0\0"))),
+            ["A=1"]
+        );
+        assert!(EnvironmentBlock::parse(&[]).is_empty());
+    }
+
+    #[test]
+    fn from_ptr_reads_until_the_empty_string() {
+        let block = wide("B=2\0A=1\0\0C=3\0\0");
+
+        // Safety: `block` ends with an empty string.
+        let environment = unsafe { EnvironmentBlock::from_ptr(block.as_ptr()) };
+
+        assert_eq!(entries(&environment), ["A=1", "B=2"]);
+    }
+
+    #[test]
+    fn names_are_compared_ignoring_the_case() {
+        let mut environment = EnvironmentBlock::new();
+
+        assert_eq!(environment.set(u16str!("Path"), u16str!("a")), Ok(None));
+        assert_eq!(
+            environment.set(u16str!("PATH"), u16str!("b")),
+            Ok(Some(U16String::from_str("a")))
+        );
+        assert_eq!(entries(&environment), ["Path=b"]);
+        assert_eq!(environment.get(u16str!("path")), Some(u16str!("b")));
+        assert!(environment.contains(u16str!("pAtH")));
+        assert_eq!(
+            environment.remove(u16str!("PATH")),
+            Some(U16String::from_str("b"))
+        );
+        assert_eq!(environment.remove(u16str!("Path")), None);
+        assert!(environment.is_empty());
+    }
+
+    #[test]
+    fn set_rejects_invalid_names_and_values() {
+        let mut environment = EnvironmentBlock::new();
+
+        for name in ["", "A=B", "=C:=", "A\0"] {
+            let error = environment
+                .set(&U16String::from_str(name), u16str!("x"))
+                .unwrap_err();
+            assert_eq!(error.code(), ERROR_INVALID_PARAMETER, "{name:?}");
+        }
+        let error = environment.set(u16str!("A"), u16str!("x\0y")).unwrap_err();
+        assert_eq!(error.code(), ERROR_INVALID_PARAMETER);
+        assert!(environment.is_empty());
+
+        // A leading equals sign is allowed because of the drive entries.
+        assert_eq!(environment.set(u16str!("=D:"), u16str!("D:\\")), Ok(None));
+        assert_eq!(environment.set(u16str!("A"), u16str!("")), Ok(None));
+    }
+
+    #[test]
+    fn to_block_is_sorted_and_double_null_terminated() {
+        let mut environment = EnvironmentBlock::new();
+        for name in ["b", "=C:", "A", "1", "_"] {
+            environment
+                .set(&U16String::from_str(name), u16str!("x"))
+                .unwrap();
+        }
+
+        assert_eq!(
+            environment.to_block(),
+            wide("1=x\0=C:=x\0A=x\0b=x\0_=x\0\0")
+        );
+        assert_eq!(
+            EnvironmentBlock::parse(&environment.to_block()),
+            environment
+        );
+        assert_eq!(EnvironmentBlock::new().to_block(), [0, 0]);
+    }
+}
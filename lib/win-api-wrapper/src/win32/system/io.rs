@@ -0,0 +1,108 @@
+use crate::call_num;
+use crate::win32::core::{Result, Win32Error};
+use crate::win32::foundation::{BorrowedHandle, OwnedHandle};
+use crate::win32::system::threading::wait;
+use core::ptr::{self, addr_of_mut};
+use core::time::Duration;
+use windows_sys::Win32::Foundation::{INVALID_HANDLE_VALUE, WAIT_TIMEOUT};
+use windows_sys::Win32::System::IO::{CreateIoCompletionPort, GetQueuedCompletionStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A completion packet that was dequeued from an I/O completion port. See [`get_queued_completion_status`].
+pub struct CompletionPacket {
+    /// The number of bytes transferred by the completed I/O operation, or the message identifier of a job notification.
+    pub bytes_transferred: u32,
+    /// The completion key that is associated with the source of the packet.
+    pub key: usize,
+    /// The address of the `OVERLAPPED` structure of the completed I/O operation, or the value that was posted with the packet
+    /// (e.g. the process identifier of a job notification).
+    pub overlapped: usize,
+}
+
+/// Creates an I/O completion port that is not associated with any file handle.
+///
+/// # Arguments
+///
+/// * `concurrent_threads`: The maximum number of threads that can process completion packets concurrently.
+///   `0` allows as many threads as there are processors.
+///
+/// # Remarks
+///
+/// * The port can be associated with a job object to receive its notifications.
+///   See [`associate_completion_port`][`crate::win32::system::job_objects::associate_completion_port`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/fileio/createiocompletionport
+///
+pub fn create_io_completion_port(concurrent_threads: u32) -> Result<OwnedHandle> {
+    let handle = call_num! {
+        CreateIoCompletionPort(INVALID_HANDLE_VALUE, 0, 0, concurrent_threads) != 0
+    }?;
+    // Safety: `handle` is a new completion port handle that is not owned by anything else.
+    Ok(unsafe { OwnedHandle::from_raw(handle) })
+}
+
+/// Dequeues a completion packet from the I/O completion port `port`, waiting until a packet is queued or `timeout` elapses.
+///
+/// # Remarks
+///
+/// * `timeout` is converted using [`wait::timeout_to_ms`]. [`None`] waits indefinitely.
+/// * The result is [`None`] if `timeout` elapsed before a packet was queued.
+/// * Packets of failed I/O operations are reported as errors and the packet is discarded.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails or the dequeued packet is of a failed I/O operation.
+///
+/// ## Possible errors
+///
+/// * `port` is invalid or it is not a completion port. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `port` was closed while waiting. ([`ErrorKind::Other`][`crate::win32::core::ErrorKind::Other`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/ioapiset/nf-ioapiset-getqueuedcompletionstatus
+///
+pub fn get_queued_completion_status(
+    port: BorrowedHandle<'_>,
+    timeout: Option<Duration>,
+) -> Result<Option<CompletionPacket>> {
+    let mut bytes_transferred = 0;
+    let mut key = 0;
+    let mut overlapped = ptr::null_mut();
+    // Safety: Every output pointer points to a local variable.
+    let dequeued = unsafe {
+        GetQueuedCompletionStatus(
+            port.as_raw(),
+            addr_of_mut!(bytes_transferred),
+            addr_of_mut!(key),
+            addr_of_mut!(overlapped),
+            wait::timeout_to_ms(timeout),
+        )
+    };
+    if dequeued == 0 {
+        let error = Win32Error::get_last_from("GetQueuedCompletionStatus");
+        return if overlapped.is_null() && error.code() == WAIT_TIMEOUT {
+            Ok(None)
+        } else {
+            Err(error)
+        };
+    }
+
+    Ok(Some(CompletionPacket {
+        bytes_transferred,
+        key,
+        overlapped: overlapped as usize,
+    }))
+}
@@ -0,0 +1,924 @@
+use crate::common::buffer::{negotiate, BufferPolicy, Fill};
+use crate::common::wide::IntoWideCStr;
+use crate::win32::core::Result;
+use crate::win32::foundation::{BorrowedHandle, KernelObject, ObjectHandle, OwnedHandle};
+use crate::win32::system::io::{self, CompletionPacket};
+use crate::win32::system::threading::process::{
+    ProcessAccessRights, ProcessHandle, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_QUOTA,
+    PROCESS_TERMINATE,
+};
+use crate::{call_BOOL, call_num, to_BOOL};
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::mem::{offset_of, size_of, size_of_val, zeroed};
+use core::ptr::{self, addr_of, addr_of_mut};
+use core::time::Duration;
+use windows_sys::Win32::Foundation::ERROR_MORE_DATA;
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, IsProcessInJob,
+    JobObjectAssociateCompletionPortInformation, JobObjectBasicAccountingInformation,
+    JobObjectBasicAndIoAccountingInformation, JobObjectBasicLimitInformation,
+    JobObjectBasicProcessIdList, JobObjectBasicUIRestrictions, JobObjectCpuRateControlInformation,
+    JobObjectEndOfJobTimeInformation, JobObjectExtendedLimitInformation, OpenJobObjectW,
+    QueryInformationJobObject, SetInformationJobObject, TerminateJobObject,
+    JOBOBJECT_BASIC_PROCESS_ID_LIST,
+};
+use windows_sys::Win32::System::SystemServices::{
+    JOB_OBJECT_MSG_ABNORMAL_EXIT_PROCESS, JOB_OBJECT_MSG_ACTIVE_PROCESS_LIMIT,
+    JOB_OBJECT_MSG_ACTIVE_PROCESS_ZERO, JOB_OBJECT_MSG_END_OF_JOB_TIME,
+    JOB_OBJECT_MSG_END_OF_PROCESS_TIME, JOB_OBJECT_MSG_EXIT_PROCESS,
+    JOB_OBJECT_MSG_JOB_CYCLE_TIME_LIMIT, JOB_OBJECT_MSG_JOB_MEMORY_LIMIT,
+    JOB_OBJECT_MSG_NEW_PROCESS, JOB_OBJECT_MSG_NOTIFICATION_LIMIT,
+    JOB_OBJECT_MSG_PROCESS_MEMORY_LIMIT, JOB_OBJECT_MSG_SILO_TERMINATED,
+};
+
+pub use windows_sys::Win32::System::JobObjects::{
+    JOBOBJECTINFOCLASS, JOBOBJECT_ASSOCIATE_COMPLETION_PORT,
+    JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION,
+    JOBOBJECT_BASIC_LIMIT_INFORMATION, JOBOBJECT_BASIC_UI_RESTRICTIONS,
+    JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0,
+    JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0_0, JOBOBJECT_END_OF_JOB_TIME_INFORMATION,
+    JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_CPU_RATE_CONTROL,
+    JOB_OBJECT_CPU_RATE_CONTROL_ENABLE, JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+    JOB_OBJECT_CPU_RATE_CONTROL_MIN_MAX_RATE, JOB_OBJECT_CPU_RATE_CONTROL_NOTIFY,
+    JOB_OBJECT_CPU_RATE_CONTROL_WEIGHT_BASED, JOB_OBJECT_LIMIT, JOB_OBJECT_LIMIT_ACTIVE_PROCESS,
+    JOB_OBJECT_LIMIT_AFFINITY, JOB_OBJECT_LIMIT_BREAKAWAY_OK,
+    JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION, JOB_OBJECT_LIMIT_JOB_MEMORY,
+    JOB_OBJECT_LIMIT_JOB_TIME, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PRIORITY_CLASS,
+    JOB_OBJECT_LIMIT_PROCESS_MEMORY, JOB_OBJECT_LIMIT_PROCESS_TIME,
+    JOB_OBJECT_LIMIT_SILENT_BREAKAWAY_OK, JOB_OBJECT_LIMIT_WORKINGSET,
+    JOB_OBJECT_TERMINATE_AT_END_ACTION, JOB_OBJECT_TERMINATE_AT_END_OF_JOB, JOB_OBJECT_UILIMIT,
+};
+pub use windows_sys::Win32::System::SystemServices::{
+    JOB_OBJECT_ASSIGN_PROCESS, JOB_OBJECT_QUERY, JOB_OBJECT_SET_ATTRIBUTES,
+    JOB_OBJECT_SET_SECURITY_ATTRIBUTES, JOB_OBJECT_TERMINATE,
+};
+
+/// All possible access rights for a job object.
+pub const JOB_OBJECT_ALL_ACCESS: u32 = 0x001F_003F;
+
+/// The maximum number of process identifiers that [`get_process_ids`] can return.
+/// The size of the whole list, including its header, must fit in a `u32`.
+pub const MAX_JOB_PROCESS_IDS: usize =
+    u32::MAX as usize / size_of::<usize>() - PROCESS_ID_LIST_HEADER_LEN;
+
+/// The number of `usize` elements that the header of [`JOBOBJECT_BASIC_PROCESS_ID_LIST`] occupies.
+const PROCESS_ID_LIST_HEADER_LEN: usize =
+    offset_of!(JOBOBJECT_BASIC_PROCESS_ID_LIST, ProcessIdList) / size_of::<usize>();
+
+#[derive(Debug)]
+/// The job kernel object. See [`JobHandle`].
+pub struct JobObject;
+
+impl KernelObject for JobObject {
+    const IMPLIED_RIGHTS: &'static [(u32, u32)] = &[];
+}
+
+/// An owned job object handle that has the `RIGHTS` access rights. See [`create`] and [`open`].
+pub type JobHandle<const RIGHTS: u32 = JOB_OBJECT_ALL_ACCESS> = ObjectHandle<JobObject, RIGHTS>;
+
+/// Creates an unnamed job object.
+///
+/// # Remarks
+///
+/// * The job object is destroyed when its last handle is closed and it has no processes.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-createjobobjectw
+///
+pub fn create() -> Result<JobHandle> {
+    let handle = call_num! { CreateJobObjectW(ptr::null(), ptr::null()) != 0 }?;
+    // Safety: `handle` is a new job object handle that has all access rights and is not owned by anything else.
+    Ok(unsafe { JobHandle::from_owned(OwnedHandle::from_raw(handle)) })
+}
+
+/// Creates a named job object, or opens it with all access rights if it already exists.
+///
+/// # Remarks
+///
+/// * The names are case-sensitive and they can have a `Global\` or `Local\` prefix.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `name` contains a null or a backslash character (other than the one after the prefix). ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * `name` is the name of another kind of object. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-createjobobjectw
+///
+pub fn create_named<N: IntoWideCStr + ?Sized>(name: &N) -> Result<JobHandle> {
+    let name = name.to_wide_c_str()?;
+    let handle = call_num! { CreateJobObjectW(ptr::null(), name.as_ptr()) != 0 }?;
+    // Safety: `handle` is a new job object handle that has all access rights and is not owned by anything else.
+    Ok(unsafe { JobHandle::from_owned(OwnedHandle::from_raw(handle)) })
+}
+
+/// Opens an existing named job object.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `name` contains a null character. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * The job object doesn't exist. ([`ErrorKind::NotFound`][`crate::win32::core::ErrorKind::NotFound`])
+/// * The caller is not allowed to open the job object with the `RIGHTS` access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-openjobobjectw
+///
+pub fn open<const RIGHTS: u32, N: IntoWideCStr + ?Sized>(
+    name: &N,
+    inherit_handle: bool,
+) -> Result<JobHandle<RIGHTS>> {
+    let name = name.to_wide_c_str()?;
+    let handle =
+        call_num! { OpenJobObjectW(RIGHTS, to_BOOL!(inherit_handle), name.as_ptr()) != 0 }?;
+    // Safety: `handle` is a newly opened job object handle that has the `RIGHTS` access rights and is not owned by anything else.
+    Ok(unsafe { JobHandle::from_owned(OwnedHandle::from_raw(handle)) })
+}
+
+/// Assigns a process to a job object.
+///
+/// # Remarks
+///
+/// * The limits of the job apply to the process and every process it creates afterwards, unless breakaway is allowed.
+/// * A process can be assigned to multiple jobs, which form a hierarchy (nested jobs).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * The process is already assigned to a job that cannot be nested with `job`. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * The process has exited. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * Assigning the process would exceed the active process limit of the job. ([`ErrorKind::Other`][`crate::win32::core::ErrorKind::Other`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-assignprocesstojobobject
+///
+pub fn assign_process<const R: u32, const P: ProcessAccessRights>(
+    job: &JobHandle<R>,
+    process: &ProcessHandle<P>,
+) -> Result<()> {
+    let job = job.require_rights::<JOB_OBJECT_ASSIGN_PROCESS>();
+    let process = process.require_rights::<{ PROCESS_SET_QUOTA | PROCESS_TERMINATE }>();
+    call_BOOL! { AssignProcessToJobObject(job.as_raw(), process.as_raw()) }
+}
+
+/// Determines whether a process is assigned to a job object.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi/nf-jobapi-isprocessinjob
+///
+pub fn is_process_in_job<const R: u32, const P: ProcessAccessRights>(
+    process: &ProcessHandle<P>,
+    job: &JobHandle<R>,
+) -> Result<bool> {
+    let process = process.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    let job = job.require_rights::<JOB_OBJECT_QUERY>();
+    call_BOOL! { IsProcessInJob(process.as_raw(), job.as_raw(), addr_of_mut!(result)) -> mut result }
+}
+
+/// Determines whether a process is assigned to any job object.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi/nf-jobapi-isprocessinjob
+///
+pub fn is_process_in_any_job<const P: ProcessAccessRights>(
+    process: &ProcessHandle<P>,
+) -> Result<bool> {
+    let process = process.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    call_BOOL! { IsProcessInJob(process.as_raw(), 0, addr_of_mut!(result)) -> mut result }
+}
+
+/// Terminates every process that is currently assigned to a job object with the given exit code.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `job` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-terminatejobobject
+///
+pub fn terminate<const R: u32>(job: &JobHandle<R>, exit_code: u32) -> Result<()> {
+    let job = job.require_rights::<JOB_OBJECT_TERMINATE>();
+    call_BOOL! { TerminateJobObject(job.as_raw(), exit_code) }
+}
+
+/// A member of the [`JOBOBJECTINFOCLASS`] enumeration that has a fixed size.
+///
+/// # Safety
+///
+/// [`get_information`] and [`set_information`] pass a pointer to the type and its size to the system,
+/// so implementing this trait for a type that violates any of the following conditions is Undefined Behavior:
+///
+/// * The type must have the exact layout of the structure that is associated with [`JobInformation::information_class`].
+/// * Every bit pattern that the system can write into the structure, including all zeros, must be a valid value of the type
+///   (e.g. references are not allowed).
+///
+pub unsafe trait JobInformation: Sized {
+    /// Gets the [`JOBOBJECTINFOCLASS`], that is associated with the type.
+    fn information_class() -> JOBOBJECTINFOCLASS;
+
+    /// Creates the value that is passed to [`get_information`] to be overwritten. It's zeroed by default.
+    fn default_information() -> Self {
+        // Safety: The implementor guarantees that all zeros is a valid value of the type.
+        unsafe { zeroed() }
+    }
+}
+
+// Safety: `JOBOBJECT_BASIC_ACCOUNTING_INFORMATION` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_BASIC_ACCOUNTING_INFORMATION {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectBasicAccountingInformation
+    }
+}
+
+// Safety: `JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectBasicAndIoAccountingInformation
+    }
+}
+
+// Safety: `JOBOBJECT_BASIC_LIMIT_INFORMATION` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_BASIC_LIMIT_INFORMATION {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectBasicLimitInformation
+    }
+}
+
+// Safety: `JOBOBJECT_EXTENDED_LIMIT_INFORMATION` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_EXTENDED_LIMIT_INFORMATION {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectExtendedLimitInformation
+    }
+}
+
+// Safety: `JOBOBJECT_BASIC_UI_RESTRICTIONS` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_BASIC_UI_RESTRICTIONS {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectBasicUIRestrictions
+    }
+}
+
+// Safety: `JOBOBJECT_CPU_RATE_CONTROL_INFORMATION` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectCpuRateControlInformation
+    }
+}
+
+// Safety: `JOBOBJECT_END_OF_JOB_TIME_INFORMATION` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_END_OF_JOB_TIME_INFORMATION {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectEndOfJobTimeInformation
+    }
+}
+
+// Safety: `JOBOBJECT_ASSOCIATE_COMPLETION_PORT` is the structure of its information class, and it only contains integers and raw pointers.
+unsafe impl JobInformation for JOBOBJECT_ASSOCIATE_COMPLETION_PORT {
+    fn information_class() -> JOBOBJECTINFOCLASS {
+        JobObjectAssociateCompletionPortInformation
+    }
+}
+
+/// Gets information about a job object (e.g. [`JOBOBJECT_EXTENDED_LIMIT_INFORMATION`]).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `job` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * The information class cannot be queried (e.g. [`JOBOBJECT_ASSOCIATE_COMPLETION_PORT`]). ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-queryinformationjobobject
+///
+pub fn get_information<T: Copy + JobInformation, const R: u32>(job: &JobHandle<R>) -> Result<T> {
+    let job = job.require_rights::<JOB_OBJECT_QUERY>();
+    call_BOOL! {
+        QueryInformationJobObject(
+            job.as_raw(),
+            T::information_class(),
+            addr_of_mut!(information).cast::<c_void>(),
+            size_of::<T>() as u32,
+            ptr::null_mut()
+        ) -> mut information = T::default_information()
+    }
+}
+
+/// Sets information of a job object (e.g. [`JOBOBJECT_EXTENDED_LIMIT_INFORMATION`]).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `job` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `information` contains invalid flags or values. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-setinformationjobobject
+///
+pub fn set_information<T: Copy + JobInformation, const R: u32>(
+    job: &JobHandle<R>,
+    information: T,
+) -> Result<()> {
+    let job = job.require_rights::<JOB_OBJECT_SET_ATTRIBUTES>();
+    call_BOOL! {
+        SetInformationJobObject(
+            job.as_raw(),
+            T::information_class(),
+            addr_of!(information).cast::<c_void>(),
+            size_of::<T>() as u32)
+    }
+}
+
+/// Gets the identifiers of the processes that are currently assigned to a job object.
+///
+/// # Remarks
+///
+/// * Processes that have exited but are still referenced by open handles are not included.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `job` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/jobapi2/nf-jobapi2-queryinformationjobobject
+///
+pub fn get_process_ids<const R: u32>(job: &JobHandle<R>) -> Result<Vec<u32>> {
+    let job = job.require_rights::<JOB_OBJECT_QUERY>();
+    let policy = BufferPolicy::new(
+        PROCESS_ID_LIST_HEADER_LEN + 16,
+        PROCESS_ID_LIST_HEADER_LEN + MAX_JOB_PROCESS_IDS,
+    );
+    let buffer = negotiate(policy, |buffer: &mut [usize]| {
+        let result = call_BOOL! {
+            QueryInformationJobObject(
+                job.as_raw(),
+                JobObjectBasicProcessIdList,
+                buffer.as_mut_ptr().cast::<c_void>(),
+                size_of_val(buffer) as u32,
+                ptr::null_mut())
+        };
+        // Safety: The buffer is large enough for the header and it is aligned for `usize`.
+        let header = unsafe { &*buffer.as_ptr().cast::<JOBOBJECT_BASIC_PROCESS_ID_LIST>() };
+        match result {
+            Ok(()) => Ok(Fill::Written(
+                PROCESS_ID_LIST_HEADER_LEN + header.NumberOfProcessIdsInList as usize,
+            )),
+            Err(error) if error.code() == ERROR_MORE_DATA => Ok(Fill::TooSmall(Some(
+                PROCESS_ID_LIST_HEADER_LEN + header.NumberOfAssignedProcesses as usize,
+            ))),
+            Err(error) => Err(error),
+        }
+    })?;
+
+    Ok(buffer[PROCESS_ID_LIST_HEADER_LEN..]
+        .iter()
+        .map(|&pid| pid as u32)
+        .collect())
+}
+
+/// Associates an I/O completion port with a job object, so the port receives the notifications of the job.
+///
+/// # Remarks
+///
+/// * `key` is the completion key of the packets, so a port can tell apart the notifications of multiple jobs.
+/// * A job object can be associated with a single completion port, which cannot be changed afterwards.
+/// * Use [`get_notification`] to dequeue the notifications. See [`io::create_io_completion_port`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * The job object is already associated with a completion port. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-jobobject_associate_completion_port
+///
+pub fn associate_completion_port<const R: u32>(
+    job: &JobHandle<R>,
+    port: BorrowedHandle<'_>,
+    key: usize,
+) -> Result<()> {
+    set_information(
+        job,
+        JOBOBJECT_ASSOCIATE_COMPLETION_PORT {
+            CompletionKey: key as *mut c_void,
+            CompletionPort: port.as_raw(),
+        },
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A notification of a job object that was delivered to its completion port. See [`associate_completion_port`].
+pub enum JobNotification {
+    /// The end-of-job time limit elapsed. The processes of the job may have been terminated.
+    EndOfJobTime,
+    /// The end-of-process time limit of the specified process elapsed and the process was terminated.
+    EndOfProcessTime {
+        /// The identifier of the process.
+        pid: u32,
+    },
+    /// Assigning a process would have exceeded the active process limit.
+    ActiveProcessLimit,
+    /// The job has no active processes anymore.
+    ActiveProcessZero,
+    /// The specified process was added to the job.
+    NewProcess {
+        /// The identifier of the process.
+        pid: u32,
+    },
+    /// The specified process exited.
+    ExitProcess {
+        /// The identifier of the process.
+        pid: u32,
+    },
+    /// The specified process exited because of an unhandled exception (e.g. an access violation).
+    AbnormalExitProcess {
+        /// The identifier of the process.
+        pid: u32,
+    },
+    /// The specified process exceeded the memory limit of the processes.
+    ProcessMemoryLimit {
+        /// The identifier of the process.
+        pid: u32,
+    },
+    /// The specified process caused the job to exceed its memory limit.
+    JobMemoryLimit {
+        /// The identifier of the process.
+        pid: u32,
+    },
+    /// A notification limit of the job was exceeded.
+    NotificationLimit,
+    /// The CPU cycle limit of the job was exceeded.
+    JobCycleTimeLimit,
+    /// The silo of the job was terminated.
+    SiloTerminated,
+    /// A notification that is not known by this crate.
+    Other {
+        /// The message identifier of the notification.
+        message: u32,
+        /// The value that was delivered with the notification.
+        value: usize,
+    },
+}
+
+impl JobNotification {
+    /// Interprets a completion packet that was delivered by a job object.
+    ///
+    /// # Remarks
+    ///
+    /// * The message identifier of a job notification is in [`CompletionPacket::bytes_transferred`]
+    ///   and the process identifier, if any, is in [`CompletionPacket::overlapped`].
+    ///
+    pub const fn from_packet(packet: &CompletionPacket) -> Self {
+        let pid = packet.overlapped as u32;
+        match packet.bytes_transferred {
+            JOB_OBJECT_MSG_END_OF_JOB_TIME => Self::EndOfJobTime,
+            JOB_OBJECT_MSG_END_OF_PROCESS_TIME => Self::EndOfProcessTime { pid },
+            JOB_OBJECT_MSG_ACTIVE_PROCESS_LIMIT => Self::ActiveProcessLimit,
+            JOB_OBJECT_MSG_ACTIVE_PROCESS_ZERO => Self::ActiveProcessZero,
+            JOB_OBJECT_MSG_NEW_PROCESS => Self::NewProcess { pid },
+            JOB_OBJECT_MSG_EXIT_PROCESS => Self::ExitProcess { pid },
+            JOB_OBJECT_MSG_ABNORMAL_EXIT_PROCESS => Self::AbnormalExitProcess { pid },
+            JOB_OBJECT_MSG_PROCESS_MEMORY_LIMIT => Self::ProcessMemoryLimit { pid },
+            JOB_OBJECT_MSG_JOB_MEMORY_LIMIT => Self::JobMemoryLimit { pid },
+            JOB_OBJECT_MSG_NOTIFICATION_LIMIT => Self::NotificationLimit,
+            JOB_OBJECT_MSG_JOB_CYCLE_TIME_LIMIT => Self::JobCycleTimeLimit,
+            JOB_OBJECT_MSG_SILO_TERMINATED => Self::SiloTerminated,
+            message => Self::Other {
+                message,
+                value: packet.overlapped,
+            },
+        }
+    }
+
+    #[inline]
+    /// Gets the identifier of the process that the notification is about, if any.
+    pub const fn pid(&self) -> Option<u32> {
+        match self {
+            Self::EndOfProcessTime { pid }
+            | Self::NewProcess { pid }
+            | Self::ExitProcess { pid }
+            | Self::AbnormalExitProcess { pid }
+            | Self::ProcessMemoryLimit { pid }
+            | Self::JobMemoryLimit { pid } => Some(*pid),
+            _ => None,
+        }
+    }
+}
+
+/// Dequeues the next job notification from a completion port that is associated with one or more job objects.
+/// The result contains the completion key of the job and the notification, or [`None`] if `timeout` elapsed.
+/// See [`io::get_queued_completion_status`].
+///
+/// # Remarks
+///
+/// * The notifications are delivered asynchronously, so they may arrive after the state of the job has changed again
+///   (e.g. [`JobNotification::NewProcess`] may arrive after the process has exited).
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `port` is invalid or it is not a completion port. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn get_notification(
+    port: BorrowedHandle<'_>,
+    timeout: Option<Duration>,
+) -> Result<Option<(usize, JobNotification)>> {
+    Ok(io::get_queued_completion_status(port, timeout)?
+        .map(|packet| (packet.key, JobNotification::from_packet(&packet))))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The CPU rate control of a job object. See [`set_cpu_rate_control`].
+///
+/// # Remarks
+///
+/// * The rates are in hundredths of a percent of the total CPU time of all processors (from `1` to `10000`).
+///
+pub enum CpuRateControl {
+    /// The processes of the job cannot use more than `rate` CPU time in each scheduling interval.
+    HardCap {
+        /// The maximum CPU rate.
+        rate: u32,
+    },
+    /// The processes of the job can use more than `rate` CPU time if no other jobs need it,
+    /// but they are scheduled after the other jobs once they have used `rate` CPU time.
+    SoftCap {
+        /// The targeted CPU rate.
+        rate: u32,
+    },
+    /// The CPU time is shared between the jobs proportionally to their weights (from `1` to `9`, `5` by default).
+    Weight(u32),
+    /// The processes of the job are guaranteed `min` CPU time and they cannot use more than `max` CPU time.
+    MinMax {
+        /// The reserved CPU rate.
+        min: u16,
+        /// The maximum CPU rate.
+        max: u16,
+    },
+}
+
+impl CpuRateControl {
+    /// Converts the control to a [`JOBOBJECT_CPU_RATE_CONTROL_INFORMATION`] that enables it.
+    pub const fn to_information(&self) -> JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+        let (flags, rate) = match *self {
+            Self::HardCap { rate } => (
+                JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+                JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 { CpuRate: rate },
+            ),
+            Self::SoftCap { rate } => (
+                0,
+                JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 { CpuRate: rate },
+            ),
+            Self::Weight(weight) => (
+                JOB_OBJECT_CPU_RATE_CONTROL_WEIGHT_BASED,
+                JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 { Weight: weight },
+            ),
+            Self::MinMax { min, max } => (
+                JOB_OBJECT_CPU_RATE_CONTROL_MIN_MAX_RATE,
+                JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 {
+                    Anonymous: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0_0 {
+                        MinRate: min,
+                        MaxRate: max,
+                    },
+                },
+            ),
+        };
+        JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+            ControlFlags: JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | flags,
+            Anonymous: rate,
+        }
+    }
+
+    /// Interprets a [`JOBOBJECT_CPU_RATE_CONTROL_INFORMATION`]. The result is [`None`] if the CPU rate control is disabled.
+    pub const fn from_information(
+        information: &JOBOBJECT_CPU_RATE_CONTROL_INFORMATION,
+    ) -> Option<Self> {
+        let flags = information.ControlFlags;
+        if flags & JOB_OBJECT_CPU_RATE_CONTROL_ENABLE == 0 {
+            return None;
+        }
+
+        // Safety: The flags determine which member of the union is valid and every member is plain data.
+        unsafe {
+            if flags & JOB_OBJECT_CPU_RATE_CONTROL_WEIGHT_BASED != 0 {
+                Some(Self::Weight(information.Anonymous.Weight))
+            } else if flags & JOB_OBJECT_CPU_RATE_CONTROL_MIN_MAX_RATE != 0 {
+                let rates = information.Anonymous.Anonymous;
+                Some(Self::MinMax {
+                    min: rates.MinRate,
+                    max: rates.MaxRate,
+                })
+            } else if flags & JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP != 0 {
+                Some(Self::HardCap {
+                    rate: information.Anonymous.CpuRate,
+                })
+            } else {
+                Some(Self::SoftCap {
+                    rate: information.Anonymous.CpuRate,
+                })
+            }
+        }
+    }
+}
+
+/// Gets the CPU rate control of a job object, or [`None`] if it is disabled.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-jobobject_cpu_rate_control_information
+///
+pub fn get_cpu_rate_control<const R: u32>(job: &JobHandle<R>) -> Result<Option<CpuRateControl>> {
+    let information = get_information::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, R>(job)?;
+    Ok(CpuRateControl::from_information(&information))
+}
+
+/// Enables the CPU rate control of a job object, or disables it if `control` is [`None`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * A rate or a weight is out of range. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-jobobject_cpu_rate_control_information
+///
+pub fn set_cpu_rate_control<const R: u32>(
+    job: &JobHandle<R>,
+    control: Option<CpuRateControl>,
+) -> Result<()> {
+    let information = control.map_or(
+        JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+            ControlFlags: 0,
+            Anonymous: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 { CpuRate: 0 },
+        },
+        |control| control.to_information(),
+    );
+    set_information(job, information)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a completion packet that carries the specified job notification.
+    const fn packet(message: u32, value: usize) -> CompletionPacket {
+        CompletionPacket {
+            bytes_transferred: message,
+            key: 7,
+            overlapped: value,
+        }
+    }
+
+    #[test]
+    fn from_packet_interprets_every_message() {
+        let pid = 1234;
+        let cases = [
+            (
+                JOB_OBJECT_MSG_END_OF_JOB_TIME,
+                JobNotification::EndOfJobTime,
+            ),
+            (
+                JOB_OBJECT_MSG_END_OF_PROCESS_TIME,
+                JobNotification::EndOfProcessTime { pid },
+            ),
+            (
+                JOB_OBJECT_MSG_ACTIVE_PROCESS_LIMIT,
+                JobNotification::ActiveProcessLimit,
+            ),
+            (
+                JOB_OBJECT_MSG_ACTIVE_PROCESS_ZERO,
+                JobNotification::ActiveProcessZero,
+            ),
+            (
+                JOB_OBJECT_MSG_NEW_PROCESS,
+                JobNotification::NewProcess { pid },
+            ),
+            (
+                JOB_OBJECT_MSG_EXIT_PROCESS,
+                JobNotification::ExitProcess { pid },
+            ),
+            (
+                JOB_OBJECT_MSG_ABNORMAL_EXIT_PROCESS,
+                JobNotification::AbnormalExitProcess { pid },
+            ),
+            (
+                JOB_OBJECT_MSG_PROCESS_MEMORY_LIMIT,
+                JobNotification::ProcessMemoryLimit { pid },
+            ),
+            (
+                JOB_OBJECT_MSG_JOB_MEMORY_LIMIT,
+                JobNotification::JobMemoryLimit { pid },
+            ),
+            (
+                JOB_OBJECT_MSG_NOTIFICATION_LIMIT,
+                JobNotification::NotificationLimit,
+            ),
+            (
+                JOB_OBJECT_MSG_JOB_CYCLE_TIME_LIMIT,
+                JobNotification::JobCycleTimeLimit,
+            ),
+            (
+                JOB_OBJECT_MSG_SILO_TERMINATED,
+                JobNotification::SiloTerminated,
+            ),
+        ];
+
+        for (message, expected) in cases {
+            let notification = JobNotification::from_packet(&packet(message, pid as usize));
+            assert_eq!(notification, expected, "{message}");
+        }
+    }
+
+    #[test]
+    fn from_packet_extracts_the_pid() {
+        let new_process = JobNotification::from_packet(&packet(JOB_OBJECT_MSG_NEW_PROCESS, 42));
+        assert_eq!(new_process.pid(), Some(42));
+
+        let active_process_zero =
+            JobNotification::from_packet(&packet(JOB_OBJECT_MSG_ACTIVE_PROCESS_ZERO, 42));
+        assert_eq!(active_process_zero.pid(), None);
+    }
+
+    #[test]
+    fn from_packet_keeps_unknown_messages() {
+        let notification = JobNotification::from_packet(&packet(100, usize::MAX));
+
+        assert_eq!(
+            notification,
+            JobNotification::Other {
+                message: 100,
+                value: usize::MAX,
+            }
+        );
+        assert_eq!(notification.pid(), None);
+    }
+
+    #[test]
+    fn cpu_rate_control_round_trips() {
+        let controls = [
+            CpuRateControl::HardCap { rate: 2500 },
+            CpuRateControl::SoftCap { rate: 10000 },
+            CpuRateControl::Weight(9),
+            CpuRateControl::MinMax {
+                min: 100,
+                max: 5000,
+            },
+        ];
+
+        for control in controls {
+            let information = control.to_information();
+            assert_ne!(
+                information.ControlFlags & JOB_OBJECT_CPU_RATE_CONTROL_ENABLE,
+                0
+            );
+            assert_eq!(
+                CpuRateControl::from_information(&information),
+                Some(control)
+            );
+        }
+    }
+
+    #[test]
+    fn cpu_rate_control_from_information_reads_the_flags() {
+        let information = |flags, rate| JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+            ControlFlags: flags,
+            Anonymous: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 { CpuRate: rate },
+        };
+
+        assert_eq!(
+            CpuRateControl::from_information(&information(
+                JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+                50
+            )),
+            None
+        );
+        assert_eq!(
+            CpuRateControl::from_information(&information(
+                JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_NOTIFY,
+                50
+            )),
+            Some(CpuRateControl::SoftCap { rate: 50 })
+        );
+        assert_eq!(
+            CpuRateControl::from_information(&information(
+                JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+                50
+            )),
+            Some(CpuRateControl::HardCap { rate: 50 })
+        );
+    }
+
+    #[test]
+    fn max_process_id_list_size_fits_in_a_u32() {
+        let max_size = (PROCESS_ID_LIST_HEADER_LEN + MAX_JOB_PROCESS_IDS) * size_of::<usize>();
+
+        assert!(u32::try_from(max_size).is_ok());
+        assert_eq!(
+            PROCESS_ID_LIST_HEADER_LEN * size_of::<usize>(),
+            offset_of!(JOBOBJECT_BASIC_PROCESS_ID_LIST, ProcessIdList)
+        );
+    }
+}
@@ -1,7 +1,28 @@
 use crate::call_num;
-use crate::win32::core::Result;
+use crate::common::pod;
+use crate::win32::core::{Result, Win32Error};
+use crate::win32::system::diagnostics::debug::process_memory::RemotePtr;
+use crate::win32::system::threading::process::{
+    ProcessAccessRights, ProcessHandle, PROCESS_QUERY_INFORMATION,
+};
 use core::ffi::c_void;
-use windows_sys::Win32::System::Memory::{LocalFree, LocalHandle};
+use core::mem::size_of;
+use core::ptr::addr_of_mut;
+use windows_sys::Win32::Foundation::ERROR_INVALID_PARAMETER;
+use windows_sys::Win32::System::Memory::{LocalFree, LocalHandle, VirtualQueryEx};
+
+/// Virtual memory map of a process, built on `VirtualQueryEx`
+pub mod map;
+
+pub use windows_sys::Win32::System::Memory::{
+    MEMORY_BASIC_INFORMATION, MEM_COMMIT, MEM_FREE, MEM_IMAGE, MEM_MAPPED, MEM_PRIVATE,
+    MEM_RESERVE, PAGE_EXECUTE, PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY,
+    PAGE_GUARD, PAGE_NOACCESS, PAGE_NOCACHE, PAGE_PROTECTION_FLAGS, PAGE_READONLY, PAGE_READWRITE,
+    PAGE_TYPE, PAGE_WRITECOMBINE, PAGE_WRITECOPY, VIRTUAL_ALLOCATION_TYPE,
+};
+
+// Safety: Every field is an integer or a pointer that is never dereferenced.
+unsafe impl pod::Pod for MEMORY_BASIC_INFORMATION {}
 
 /// Gets the handle associated with the specified pointer to a local memory object.
 ///
@@ -30,3 +51,54 @@ pub fn get_local_handle(mem_ptr: *const c_void) -> Result<isize> {
 pub fn local_free(local_handle: isize) -> Result<()> {
     call_num! { LocalFree(local_handle) == 0 }
 }
+
+/// Gets information about the range of pages that contains `address` in the virtual address space of the specified process.
+///
+/// # Remarks
+///
+/// * The result is [`None`] if `address` is above the highest address that is accessible to applications.
+/// * The range starts at the page that contains `address` and covers the following pages that have the same attributes.
+/// * The memory map of a process can be walked using [`map::regions`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-virtualqueryex
+///
+pub fn virtual_query<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<u8>,
+) -> Result<Option<MEMORY_BASIC_INFORMATION>> {
+    let handle = handle.require_rights::<PROCESS_QUERY_INFORMATION>();
+    let mut information = pod::zeroed::<MEMORY_BASIC_INFORMATION>();
+    // Safety: `information` is a local variable of the specified size.
+    let written = unsafe {
+        VirtualQueryEx(
+            handle.as_raw(),
+            address.address() as *const c_void,
+            addr_of_mut!(information),
+            size_of::<MEMORY_BASIC_INFORMATION>(),
+        )
+    };
+    if written == 0 {
+        let error = Win32Error::get_last_from("VirtualQueryEx");
+        return if error.code() == ERROR_INVALID_PARAMETER {
+            Ok(None)
+        } else {
+            Err(error)
+        };
+    }
+
+    Ok(Some(information))
+}
@@ -0,0 +1,871 @@
+use super::{
+    virtual_query, MEMORY_BASIC_INFORMATION, MEM_COMMIT, MEM_FREE, MEM_IMAGE, MEM_MAPPED,
+    MEM_PRIVATE, MEM_RESERVE, PAGE_EXECUTE, PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE,
+    PAGE_EXECUTE_WRITECOPY, PAGE_GUARD, PAGE_NOACCESS, PAGE_NOCACHE, PAGE_PROTECTION_FLAGS,
+    PAGE_READONLY, PAGE_READWRITE, PAGE_TYPE, PAGE_WRITECOMBINE, PAGE_WRITECOPY,
+    VIRTUAL_ALLOCATION_TYPE,
+};
+use crate::common::wide::U16String;
+use crate::win32::core::Result;
+use crate::win32::system::diagnostics::debug::process_memory::RemotePtr;
+use crate::win32::system::process_status;
+use crate::win32::system::threading::process::{ProcessAccessRights, ProcessHandle};
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem::size_of;
+
+/// The number of hexadecimal digits that are used to print an address.
+const ADDRESS_WIDTH: usize = size_of::<usize>() * 2;
+
+/// The modifier flags of a [`PAGE_PROTECTION_FLAGS`] value.
+const PROTECTION_MODIFIERS: PAGE_PROTECTION_FLAGS = PAGE_GUARD | PAGE_NOCACHE | PAGE_WRITECOMBINE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The state of the pages of a [`MemoryRegion`].
+pub enum MemoryState {
+    /// The pages are committed (`MEM_COMMIT`).
+    Commit,
+    /// The pages are reserved without being committed (`MEM_RESERVE`).
+    Reserve,
+    /// The pages are free (`MEM_FREE`).
+    Free,
+    /// A state that is not known by this crate.
+    Unknown(VIRTUAL_ALLOCATION_TYPE),
+}
+
+impl MemoryState {
+    /// Creates a [`MemoryState`] from the `State` member of [`MEMORY_BASIC_INFORMATION`].
+    pub const fn from_raw(state: VIRTUAL_ALLOCATION_TYPE) -> Self {
+        match state {
+            MEM_COMMIT => Self::Commit,
+            MEM_RESERVE => Self::Reserve,
+            MEM_FREE => Self::Free,
+            _ => Self::Unknown(state),
+        }
+    }
+
+    /// Gets the raw value of the state.
+    pub const fn to_raw(self) -> VIRTUAL_ALLOCATION_TYPE {
+        match self {
+            Self::Commit => MEM_COMMIT,
+            Self::Reserve => MEM_RESERVE,
+            Self::Free => MEM_FREE,
+            Self::Unknown(state) => state,
+        }
+    }
+
+    /// Gets the name that is used by the [`Display`][`fmt::Display`] implementation.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Commit => "commit",
+            Self::Reserve => "reserve",
+            Self::Free => "free",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for MemoryState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The type of the pages of a [`MemoryRegion`].
+pub enum MemoryType {
+    /// The pages are private to the process (`MEM_PRIVATE`).
+    Private,
+    /// The pages are mapped into the view of a section (`MEM_MAPPED`).
+    Mapped,
+    /// The pages are mapped into the view of an image section (`MEM_IMAGE`).
+    Image,
+    /// A type that is not known by this crate.
+    Unknown(PAGE_TYPE),
+}
+
+impl MemoryType {
+    /// Creates a [`MemoryType`] from the `Type` member of [`MEMORY_BASIC_INFORMATION`].
+    /// The result is [`None`] if `kind` is `0`, which is the type of free pages.
+    pub const fn from_raw(kind: PAGE_TYPE) -> Option<Self> {
+        match kind {
+            0 => None,
+            MEM_PRIVATE => Some(Self::Private),
+            MEM_MAPPED => Some(Self::Mapped),
+            MEM_IMAGE => Some(Self::Image),
+            _ => Some(Self::Unknown(kind)),
+        }
+    }
+
+    /// Gets the raw value of the type.
+    pub const fn to_raw(self) -> PAGE_TYPE {
+        match self {
+            Self::Private => MEM_PRIVATE,
+            Self::Mapped => MEM_MAPPED,
+            Self::Image => MEM_IMAGE,
+            Self::Unknown(kind) => kind,
+        }
+    }
+
+    /// Determines whether the pages are backed by a file, so they have a mapped file name.
+    pub const fn is_file_backed(self) -> bool {
+        matches!(self, Self::Mapped | Self::Image)
+    }
+
+    /// Gets the name that is used by the [`Display`][`fmt::Display`] implementation.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Mapped => "mapped",
+            Self::Image => "image",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for MemoryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The access that is allowed to the pages, without the modifiers of [`Protection`].
+pub enum PageAccess {
+    /// `PAGE_NOACCESS`
+    NoAccess,
+    /// `PAGE_READONLY`
+    ReadOnly,
+    /// `PAGE_READWRITE`
+    ReadWrite,
+    /// `PAGE_WRITECOPY`
+    WriteCopy,
+    /// `PAGE_EXECUTE`
+    Execute,
+    /// `PAGE_EXECUTE_READ`
+    ExecuteRead,
+    /// `PAGE_EXECUTE_READWRITE`
+    ExecuteReadWrite,
+    /// `PAGE_EXECUTE_WRITECOPY`
+    ExecuteWriteCopy,
+    /// An access that is not known by this crate.
+    Unknown(PAGE_PROTECTION_FLAGS),
+}
+
+impl PageAccess {
+    /// Creates a [`PageAccess`] from a [`PAGE_PROTECTION_FLAGS`] value without its modifiers.
+    pub const fn from_raw(access: PAGE_PROTECTION_FLAGS) -> Self {
+        match access {
+            PAGE_NOACCESS => Self::NoAccess,
+            PAGE_READONLY => Self::ReadOnly,
+            PAGE_READWRITE => Self::ReadWrite,
+            PAGE_WRITECOPY => Self::WriteCopy,
+            PAGE_EXECUTE => Self::Execute,
+            PAGE_EXECUTE_READ => Self::ExecuteRead,
+            PAGE_EXECUTE_READWRITE => Self::ExecuteReadWrite,
+            PAGE_EXECUTE_WRITECOPY => Self::ExecuteWriteCopy,
+            _ => Self::Unknown(access),
+        }
+    }
+
+    /// Gets the raw value of the access.
+    pub const fn to_raw(self) -> PAGE_PROTECTION_FLAGS {
+        match self {
+            Self::NoAccess => PAGE_NOACCESS,
+            Self::ReadOnly => PAGE_READONLY,
+            Self::ReadWrite => PAGE_READWRITE,
+            Self::WriteCopy => PAGE_WRITECOPY,
+            Self::Execute => PAGE_EXECUTE,
+            Self::ExecuteRead => PAGE_EXECUTE_READ,
+            Self::ExecuteReadWrite => PAGE_EXECUTE_READWRITE,
+            Self::ExecuteWriteCopy => PAGE_EXECUTE_WRITECOPY,
+            Self::Unknown(access) => access,
+        }
+    }
+
+    /// Determines whether the pages can be read.
+    pub const fn is_readable(self) -> bool {
+        matches!(
+            self,
+            Self::ReadOnly
+                | Self::ReadWrite
+                | Self::WriteCopy
+                | Self::ExecuteRead
+                | Self::ExecuteReadWrite
+                | Self::ExecuteWriteCopy
+        )
+    }
+
+    /// Determines whether the pages can be written. Copy-on-write pages are writable.
+    pub const fn is_writable(self) -> bool {
+        matches!(
+            self,
+            Self::ReadWrite | Self::WriteCopy | Self::ExecuteReadWrite | Self::ExecuteWriteCopy
+        )
+    }
+
+    /// Determines whether the pages can be executed.
+    pub const fn is_executable(self) -> bool {
+        matches!(
+            self,
+            Self::Execute | Self::ExecuteRead | Self::ExecuteReadWrite | Self::ExecuteWriteCopy
+        )
+    }
+
+    /// Determines whether a write creates a private copy of the pages.
+    pub const fn is_copy_on_write(self) -> bool {
+        matches!(self, Self::WriteCopy | Self::ExecuteWriteCopy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The memory protection of a [`MemoryRegion`].
+///
+/// # Remarks
+///
+/// * The [`Display`][`fmt::Display`] implementation prints 6 characters: `r`, `w`, `x`, `c` (copy-on-write),
+///   `g` (guard) and `n` (no-cache) or `m` (write-combine), with `-` in place of the ones that don't apply.
+///
+pub struct Protection {
+    /// The allowed access.
+    pub access: PageAccess,
+    /// The pages are guard pages (`PAGE_GUARD`).
+    pub guard: bool,
+    /// The pages are not cached (`PAGE_NOCACHE`).
+    pub no_cache: bool,
+    /// The pages are write-combined (`PAGE_WRITECOMBINE`).
+    pub write_combine: bool,
+}
+
+impl Protection {
+    /// Creates a [`Protection`] from a [`PAGE_PROTECTION_FLAGS`] value.
+    /// The result is [`None`] if `flags` is `0`, which means that the caller has no access to the pages
+    /// (e.g. they are free or reserved).
+    pub const fn from_raw(flags: PAGE_PROTECTION_FLAGS) -> Option<Self> {
+        if flags == 0 {
+            return None;
+        }
+
+        Some(Self {
+            access: PageAccess::from_raw(flags & !PROTECTION_MODIFIERS),
+            guard: flags & PAGE_GUARD != 0,
+            no_cache: flags & PAGE_NOCACHE != 0,
+            write_combine: flags & PAGE_WRITECOMBINE != 0,
+        })
+    }
+
+    /// Gets the raw value of the protection.
+    pub const fn to_raw(self) -> PAGE_PROTECTION_FLAGS {
+        let mut flags = self.access.to_raw();
+        if self.guard {
+            flags |= PAGE_GUARD;
+        }
+        if self.no_cache {
+            flags |= PAGE_NOCACHE;
+        }
+        if self.write_combine {
+            flags |= PAGE_WRITECOMBINE;
+        }
+        flags
+    }
+}
+
+impl fmt::Display for Protection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |set: bool, c: char| if set { c } else { '-' };
+        let cache = if self.no_cache {
+            'n'
+        } else if self.write_combine {
+            'm'
+        } else {
+            '-'
+        };
+        write!(
+            f,
+            "{}{}{}{}{}{}",
+            flag(self.access.is_readable(), 'r'),
+            flag(self.access.is_writable(), 'w'),
+            flag(self.access.is_executable(), 'x'),
+            flag(self.access.is_copy_on_write(), 'c'),
+            flag(self.guard, 'g'),
+            cache
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A range of pages in the virtual address space of a process that have the same attributes.
+/// See [`regions`].
+pub struct MemoryRegion {
+    /// The address of the first page of the region.
+    pub base: RemotePtr<u8>,
+    /// The size of the region in bytes.
+    pub size: usize,
+    /// The state of the pages.
+    pub state: MemoryState,
+    /// The protection of the pages, or [`None`] if the caller has no access to them.
+    pub protection: Option<Protection>,
+    /// The type of the pages, or [`None`] if they are free.
+    pub kind: Option<MemoryType>,
+    /// The base address of the allocation that contains the region. It is null for free regions.
+    pub allocation_base: RemotePtr<u8>,
+    /// The protection of the allocation when it was reserved.
+    pub allocation_protection: Option<Protection>,
+}
+
+impl MemoryRegion {
+    /// Gets the address after the last byte of the region.
+    pub const fn end(&self) -> RemotePtr<u8> {
+        self.base.wrapping_byte_add(self.size)
+    }
+
+    /// Determines whether `address` is in the region.
+    pub const fn contains(&self, address: RemotePtr<u8>) -> bool {
+        address.address() >= self.base.address()
+            && address.address() - self.base.address() < self.size
+    }
+}
+
+impl From<MEMORY_BASIC_INFORMATION> for MemoryRegion {
+    fn from(information: MEMORY_BASIC_INFORMATION) -> Self {
+        Self {
+            base: RemotePtr::new(information.BaseAddress as usize),
+            size: information.RegionSize,
+            state: MemoryState::from_raw(information.State),
+            protection: Protection::from_raw(information.Protect),
+            kind: MemoryType::from_raw(information.Type),
+            allocation_base: RemotePtr::new(information.AllocationBase as usize),
+            allocation_protection: Protection::from_raw(information.AllocationProtect),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// An iterator over the [`MemoryRegion`]s of a process, in ascending address order. See [`regions`].
+pub struct Regions<'handle, const R: ProcessAccessRights> {
+    /// The process handle.
+    handle: &'handle ProcessHandle<R>,
+    /// The address of the next region, or [`None`] if the iteration is over.
+    next: Option<usize>,
+}
+
+impl<const R: ProcessAccessRights> Iterator for Regions<'_, R> {
+    type Item = Result<MemoryRegion>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let address = self.next?;
+        match virtual_query(self.handle, RemotePtr::new(address)) {
+            Ok(Some(information)) => {
+                let region = MemoryRegion::from(information);
+                self.next = region
+                    .base
+                    .address()
+                    .checked_add(region.size)
+                    .filter(|_| region.size != 0);
+                Some(Ok(region))
+            }
+            Ok(None) => {
+                self.next = None;
+                None
+            }
+            Err(error) => {
+                self.next = None;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl<const R: ProcessAccessRights> FusedIterator for Regions<'_, R> {}
+
+/// Gets an iterator over the [`MemoryRegion`]s of the specified process, including the free ones.
+///
+/// # Remarks
+///
+/// * The regions are queried lazily using [`virtual_query`], so they can change during the iteration
+///   if the process is running.
+/// * The iteration stops after the first error.
+/// * Use [`MemoryMap::query`] to group the regions by allocation and to get the names of the mapped files.
+///
+/// # Errors
+///
+/// The iterator yields a [`Win32Error`][`crate::win32::core::Win32Error`] if a query fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`][`crate::win32::system::threading::process::PROCESS_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+pub const fn regions<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Regions<'_, R> {
+    Regions {
+        handle,
+        next: Some(0),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The regions of a single allocation in the virtual address space of a process. See [`MemoryMap`].
+pub struct Allocation {
+    /// The base address of the allocation.
+    pub base: RemotePtr<u8>,
+    /// The protection of the allocation when it was reserved.
+    pub protection: Option<Protection>,
+    /// The type of the pages of the allocation.
+    pub kind: Option<MemoryType>,
+    /// The regions of the allocation in ascending address order.
+    pub regions: Vec<MemoryRegion>,
+    /// The native path of the mapped file, if the allocation is file-backed and the name could be queried.
+    /// See [`get_mapped_file_name`][`process_status::get_mapped_file_name`].
+    pub mapped_file_name: Option<U16String>,
+}
+
+impl Allocation {
+    /// Gets the address after the last byte of the last region of the allocation.
+    pub fn end(&self) -> RemotePtr<u8> {
+        self.regions.last().map_or(self.base, MemoryRegion::end)
+    }
+
+    /// Gets the total size of the regions of the allocation in bytes.
+    pub fn size(&self) -> usize {
+        self.regions.iter().map(|region| region.size).sum()
+    }
+
+    /// Gets the total size of the committed regions of the allocation in bytes.
+    pub fn committed_size(&self) -> usize {
+        self.regions
+            .iter()
+            .filter(|region| region.state == MemoryState::Commit)
+            .map(|region| region.size)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The memory map of a process: its allocations in ascending address order, without the free regions.
+///
+/// # Remarks
+///
+/// * The [`Display`][`fmt::Display`] implementation prints one line per region, similar to `/proc/<pid>/maps`:
+///
+///   `<base>-<end> <protection> <state> <type> <allocation base> [<mapped file name>]`
+///
+///   The addresses are printed as zero-padded hexadecimal numbers of `2 * size_of::<usize>()` digits,
+///   the protection as described in [`Protection`], and the state and the type are padded to 7 characters.
+///   Unavailable fields are printed as `-`.
+///
+pub struct MemoryMap {
+    /// The allocations of the process.
+    allocations: Vec<Allocation>,
+}
+
+impl MemoryMap {
+    /// Queries the memory map of the specified process.
+    ///
+    /// # Remarks
+    ///
+    /// * The regions are grouped by their allocation base. The names of the mapped files are queried once per allocation,
+    ///   and an allocation whose name cannot be queried has no name.
+    /// * The map is a snapshot that can be outdated if the process is running.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if a region cannot be queried.
+    ///
+    /// ## Possible errors
+    ///
+    /// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+    /// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`][`crate::win32::system::threading::process::PROCESS_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+    ///
+    /// # Examples
+    /// TODO
+    ///
+    pub fn query<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<Self> {
+        Self::from_regions(regions(handle), |region| {
+            process_status::get_mapped_file_name(handle, region.base).ok()
+        })
+    }
+
+    /// Groups `regions` by their allocation base and skips the free ones. See [`MemoryMap::query`].
+    /// `mapped_file_name` is called with the first region of each file-backed allocation.
+    fn from_regions<I, F>(regions: I, mut mapped_file_name: F) -> Result<Self>
+    where
+        I: IntoIterator<Item = Result<MemoryRegion>>,
+        F: FnMut(&MemoryRegion) -> Option<U16String>,
+    {
+        let mut allocations: Vec<Allocation> = Vec::new();
+        for region in regions {
+            let region = region?;
+            if region.state == MemoryState::Free {
+                continue;
+            }
+
+            match allocations.last_mut() {
+                Some(allocation) if allocation.base == region.allocation_base => {
+                    allocation.regions.push(region);
+                }
+                _ => {
+                    let mapped_file_name = region
+                        .kind
+                        .filter(|kind| kind.is_file_backed())
+                        .and_then(|_| mapped_file_name(&region));
+                    allocations.push(Allocation {
+                        base: region.allocation_base,
+                        protection: region.allocation_protection,
+                        kind: region.kind,
+                        regions: vec![region],
+                        mapped_file_name,
+                    });
+                }
+            }
+        }
+
+        Ok(Self { allocations })
+    }
+
+    /// Gets the allocations in ascending address order.
+    pub fn allocations(&self) -> &[Allocation] {
+        &self.allocations
+    }
+
+    /// Gets an iterator over the regions of every allocation in ascending address order.
+    pub fn regions(&self) -> impl Iterator<Item = &MemoryRegion> + '_ {
+        self.allocations
+            .iter()
+            .flat_map(|allocation| allocation.regions.iter())
+    }
+
+    /// Finds the allocation and the region that contain `address`.
+    pub fn find(&self, address: RemotePtr<u8>) -> Option<(&Allocation, &MemoryRegion)> {
+        self.allocations.iter().find_map(|allocation| {
+            allocation
+                .regions
+                .iter()
+                .find(|region| region.contains(address))
+                .map(|region| (allocation, region))
+        })
+    }
+}
+
+impl fmt::Display for MemoryMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for allocation in &self.allocations {
+            for region in &allocation.regions {
+                write!(
+                    f,
+                    "{:0width$x}-{:0width$x} ",
+                    region.base.address(),
+                    region.end().address(),
+                    width = ADDRESS_WIDTH
+                )?;
+                match region.protection {
+                    Some(protection) => write!(f, "{protection} ")?,
+                    None => f.write_str("------ ")?,
+                }
+                write!(f, "{:<7} ", region.state)?;
+                match region.kind {
+                    Some(kind) => write!(f, "{kind:<7} ")?,
+                    None => f.write_str("-       ")?,
+                }
+                write!(
+                    f,
+                    "{:0width$x}",
+                    region.allocation_base.address(),
+                    width = ADDRESS_WIDTH
+                )?;
+                if let Some(name) = &allocation.mapped_file_name {
+                    write!(f, " {}", name.display())?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::win32::core::Win32Error;
+    use alloc::string::ToString;
+    use core::ffi::c_void;
+    use windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED;
+
+    /// The native path of the image that is mapped by the synthetic regions.
+    const IMAGE_NAME: &str = r"\Device\HarddiskVolume3\Windows\System32\ntdll.dll";
+
+    /// The protection of the image allocation when it was reserved.
+    const IMAGE_ALLOCATION_PROTECT: PAGE_PROTECTION_FLAGS = PAGE_EXECUTE_WRITECOPY;
+
+    /// Creates a region from a synthetic [`MEMORY_BASIC_INFORMATION`].
+    fn region(
+        base: usize,
+        size: usize,
+        state: VIRTUAL_ALLOCATION_TYPE,
+        protect: PAGE_PROTECTION_FLAGS,
+        kind: PAGE_TYPE,
+        allocation_base: usize,
+        allocation_protect: PAGE_PROTECTION_FLAGS,
+    ) -> MemoryRegion {
+        // Safety: `MEMORY_BASIC_INFORMATION` is a plain C structure, so the all-zero value is valid.
+        let mut information: MEMORY_BASIC_INFORMATION = unsafe { core::mem::zeroed() };
+        information.BaseAddress = base as *mut c_void;
+        information.RegionSize = size;
+        information.State = state;
+        information.Protect = protect;
+        information.Type = kind;
+        information.AllocationBase = allocation_base as *mut c_void;
+        information.AllocationProtect = allocation_protect;
+        MemoryRegion::from(information)
+    }
+
+    /// Gets the regions of a small address space: a private allocation with a committed and a reserved region,
+    /// a free region, an image with a name, and a reserved allocation without a type.
+    fn synthetic_regions() -> Vec<MemoryRegion> {
+        vec![
+            region(
+                0x1_0000,
+                0x1000,
+                MEM_COMMIT,
+                PAGE_READWRITE,
+                MEM_PRIVATE,
+                0x1_0000,
+                PAGE_READWRITE,
+            ),
+            region(
+                0x1_1000,
+                0x2000,
+                MEM_RESERVE,
+                0,
+                MEM_PRIVATE,
+                0x1_0000,
+                PAGE_READWRITE,
+            ),
+            region(0x1_3000, 0xD000, MEM_FREE, PAGE_NOACCESS, 0, 0, 0),
+            region(
+                0x2_0000,
+                0x1000,
+                MEM_COMMIT,
+                PAGE_READONLY,
+                MEM_IMAGE,
+                0x2_0000,
+                IMAGE_ALLOCATION_PROTECT,
+            ),
+            region(
+                0x2_1000,
+                0x3000,
+                MEM_COMMIT,
+                PAGE_EXECUTE_READ,
+                MEM_IMAGE,
+                0x2_0000,
+                IMAGE_ALLOCATION_PROTECT,
+            ),
+            region(0x2_4000, 0x1000, MEM_RESERVE, 0, 0, 0x2_4000, 0),
+        ]
+    }
+
+    /// Builds a memory map from [`synthetic_regions`] and checks that a name is queried once per image.
+    fn synthetic_map() -> MemoryMap {
+        let mut queried = Vec::new();
+        let map = MemoryMap::from_regions(synthetic_regions().into_iter().map(Ok), |region| {
+            queried.push(region.base.address());
+            Some(U16String::from_str(IMAGE_NAME))
+        })
+        .unwrap();
+
+        assert_eq!(queried, [0x2_0000]);
+        map
+    }
+
+    #[test]
+    fn regions_are_grouped_by_allocation() {
+        let map = synthetic_map();
+
+        let bases: Vec<usize> = map
+            .allocations()
+            .iter()
+            .map(|allocation| allocation.base.address())
+            .collect();
+        assert_eq!(bases, [0x1_0000, 0x2_0000, 0x2_4000]);
+        assert!(map
+            .regions()
+            .all(|region| region.state != MemoryState::Free));
+
+        let private = &map.allocations()[0];
+        assert_eq!(private.size(), 0x3000);
+        assert_eq!(private.committed_size(), 0x1000);
+        assert_eq!(private.end().address(), 0x1_3000);
+        assert_eq!(private.mapped_file_name, None);
+
+        let image = &map.allocations()[1];
+        assert_eq!(image.kind, Some(MemoryType::Image));
+        assert_eq!(
+            image.protection,
+            Protection::from_raw(IMAGE_ALLOCATION_PROTECT)
+        );
+        assert_eq!(
+            image.mapped_file_name.as_ref().unwrap().to_string_lossy(),
+            IMAGE_NAME
+        );
+
+        let (allocation, region) = map.find(RemotePtr::new(0x2_3FFF)).unwrap();
+        assert_eq!(allocation.base.address(), 0x2_0000);
+        assert_eq!(region.base.address(), 0x2_1000);
+        assert!(map.find(RemotePtr::new(0x1_3000)).is_none());
+    }
+
+    #[test]
+    fn errors_are_propagated() {
+        let regions = [Err(Win32Error::new(ERROR_ACCESS_DENIED))];
+        let error = MemoryMap::from_regions(regions, |_| unreachable!()).unwrap_err();
+        assert_eq!(error.code(), ERROR_ACCESS_DENIED);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn display_prints_one_line_per_region() {
+        let expected = concat!(
+            "0000000000010000-0000000000011000 rw---- commit  private 0000000000010000\n",
+            "0000000000011000-0000000000013000 ------ reserve private 0000000000010000\n",
+            "0000000000020000-0000000000021000 r----- commit  image   0000000000020000 \\Device\\HarddiskVolume3\\Windows\\System32\\ntdll.dll\n",
+            "0000000000021000-0000000000024000 r-x--- commit  image   0000000000020000 \\Device\\HarddiskVolume3\\Windows\\System32\\ntdll.dll\n",
+            "0000000000024000-0000000000025000 ------ reserve -       0000000000024000\n",
+        );
+
+        assert_eq!(synthetic_map().to_string(), expected);
+    }
+
+    #[test]
+    fn display_of_an_empty_map_is_empty() {
+        assert_eq!(MemoryMap::default().to_string(), "");
+    }
+
+    #[test]
+    fn display_pads_the_addresses() {
+        let map = MemoryMap::from_regions(
+            [Ok(region(
+                0x1000,
+                0x1000,
+                MEM_COMMIT,
+                PAGE_READONLY,
+                MEM_PRIVATE,
+                0x1000,
+                PAGE_READONLY,
+            ))],
+            |_| unreachable!(),
+        )
+        .unwrap();
+
+        let zeros = "0".repeat(ADDRESS_WIDTH - 4);
+        assert_eq!(
+            map.to_string(),
+            alloc::format!("{zeros}1000-{zeros}2000 r----- commit  private {zeros}1000\n")
+        );
+    }
+
+    #[test]
+    fn protection_display() {
+        let cases = [
+            (PAGE_NOACCESS, "------"),
+            (PAGE_READONLY, "r-----"),
+            (PAGE_READWRITE, "rw----"),
+            (PAGE_WRITECOPY, "rw-c--"),
+            (PAGE_EXECUTE, "--x---"),
+            (PAGE_EXECUTE_READ, "r-x---"),
+            (PAGE_EXECUTE_READWRITE, "rwx---"),
+            (PAGE_EXECUTE_WRITECOPY, "rwxc--"),
+            (PAGE_READWRITE | PAGE_GUARD, "rw--g-"),
+            (PAGE_READWRITE | PAGE_NOCACHE, "rw---n"),
+            (PAGE_READWRITE | PAGE_WRITECOMBINE, "rw---m"),
+            (PAGE_READONLY | PAGE_GUARD | PAGE_NOCACHE, "r---gn"),
+            // An unknown access grants nothing that this crate knows about.
+            (0x8000_0000, "------"),
+        ];
+
+        for (flags, expected) in cases {
+            let protection = Protection::from_raw(flags).unwrap();
+            assert_eq!(protection.to_string(), expected, "{flags:#x}");
+        }
+    }
+
+    #[test]
+    fn protection_round_trip() {
+        let accesses = [
+            PAGE_NOACCESS,
+            PAGE_READONLY,
+            PAGE_READWRITE,
+            PAGE_WRITECOPY,
+            PAGE_EXECUTE,
+            PAGE_EXECUTE_READ,
+            PAGE_EXECUTE_READWRITE,
+            PAGE_EXECUTE_WRITECOPY,
+            0x8000_0000,
+        ];
+        let modifiers = [
+            0,
+            PAGE_GUARD,
+            PAGE_NOCACHE,
+            PAGE_WRITECOMBINE,
+            PAGE_GUARD | PAGE_NOCACHE,
+        ];
+
+        for access in accesses {
+            assert_eq!(PageAccess::from_raw(access).to_raw(), access, "{access:#x}");
+            for modifier in modifiers {
+                let flags = access | modifier;
+                let protection = Protection::from_raw(flags).unwrap();
+                assert_eq!(
+                    protection.access,
+                    PageAccess::from_raw(access),
+                    "{flags:#x}"
+                );
+                assert_eq!(protection.to_raw(), flags, "{flags:#x}");
+            }
+        }
+
+        assert_eq!(
+            PageAccess::from_raw(0x8000_0000),
+            PageAccess::Unknown(0x8000_0000)
+        );
+        assert_eq!(Protection::from_raw(0), None);
+    }
+
+    #[test]
+    fn state_round_trip() {
+        let cases = [
+            (MEM_COMMIT, MemoryState::Commit, "commit"),
+            (MEM_RESERVE, MemoryState::Reserve, "reserve"),
+            (MEM_FREE, MemoryState::Free, "free"),
+            (0x4000, MemoryState::Unknown(0x4000), "unknown"),
+        ];
+
+        for (raw, state, name) in cases {
+            assert_eq!(MemoryState::from_raw(raw), state, "{raw:#x}");
+            assert_eq!(state.to_raw(), raw, "{raw:#x}");
+            assert_eq!(state.to_string(), name, "{raw:#x}");
+        }
+    }
+
+    #[test]
+    fn type_round_trip() {
+        let cases = [
+            (MEM_PRIVATE, MemoryType::Private, "private", false),
+            (MEM_MAPPED, MemoryType::Mapped, "mapped", true),
+            (MEM_IMAGE, MemoryType::Image, "image", true),
+            (0x10, MemoryType::Unknown(0x10), "unknown", false),
+        ];
+
+        for (raw, kind, name, is_file_backed) in cases {
+            assert_eq!(MemoryType::from_raw(raw), Some(kind), "{raw:#x}");
+            assert_eq!(kind.to_raw(), raw, "{raw:#x}");
+            assert_eq!(kind.to_string(), name, "{raw:#x}");
+            assert_eq!(kind.is_file_backed(), is_file_backed, "{raw:#x}");
+        }
+
+        assert_eq!(MemoryType::from_raw(0), None);
+    }
+}
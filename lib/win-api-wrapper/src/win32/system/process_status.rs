@@ -1,51 +1,97 @@
 use crate::call_BOOL;
-use crate::win32::core::Result;
+use crate::common::buffer::{negotiate, BufferPolicy, Fill};
+use crate::common::wide::U16String;
+use crate::common::To;
+use crate::win32::backend::{Backend, Native};
+use crate::win32::core::{ErrorKind, Result};
+use crate::win32::system::diagnostics::debug::process_memory::RemotePtr;
+use crate::win32::system::threading::process::{
+    ProcessAccessRights, ProcessHandle, PROCESS_QUERY_INFORMATION,
+    PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ,
+};
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::mem::size_of;
-use windows_sys::Win32::System::ProcessStatus::EnumProcesses;
+use core::ptr::addr_of_mut;
+use windows_sys::Win32::Foundation::MAX_PATH;
+use windows_sys::Win32::System::ProcessStatus::{
+    GetModuleInformation, GetPerformanceInfo, GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+};
+
+pub use windows_sys::Win32::System::ProcessStatus::{
+    ENUM_PROCESS_MODULES_EX_FLAGS, LIST_MODULES_32BIT, LIST_MODULES_64BIT, LIST_MODULES_ALL,
+    LIST_MODULES_DEFAULT, MODULEINFO, PERFORMANCE_INFORMATION, PROCESS_MEMORY_COUNTERS_EX,
+};
 
 /// The recommended value for `initial_buffer_size` in [`get_pids`].
 pub const RECOMMENDED_INITIAL_PID_BUFFER_LENGTH: usize = 1024;
-/// Maximum number of processes that [`EnumProcesses`] could enumerate.
-const MAX_PID_BUFFER_LEN: usize = (u32::MAX >> 1) as usize + 1;
+/// Maximum number of processes that `EnumProcesses` could enumerate.
+pub const MAX_PID_BUFFER_LEN: usize = u32::MAX as usize / size_of::<u32>();
+/// The maximum length of a mapped file name in characters, which is the maximum length of a native path.
+pub const MAX_MAPPED_FILE_NAME_LEN: usize = u16::MAX as usize / size_of::<u16>();
+/// The recommended value for `initial_buffer_len` in [`get_modules`].
+pub const RECOMMENDED_INITIAL_MODULE_BUFFER_LENGTH: usize = 256;
+/// Maximum number of modules that `EnumProcessModulesEx` could enumerate.
+pub const MAX_MODULE_BUFFER_LEN: usize = u32::MAX as usize / size_of::<isize>();
+/// The maximum length of a module file name in characters, which is the maximum length of a long path.
+pub const MAX_MODULE_FILE_NAME_LEN: usize = u16::MAX as usize / size_of::<u16>();
 
 /// Gets the process identifier for each process in the system.
 ///
 /// # Remarks
 ///
 /// * If `initial_buffer_len` is too small, the buffer is resized by `buffer.len() * 2` until it is large enough to hold the process identifiers.
-/// * The maximum number of process identifiers that could be returned is [`MAX_PID_BUFFER_LEN`].
 /// * [`MAX_PID_BUFFER_LEN`] is used instead of `initial_buffer_len` to allocate the initial buffer if the specidied length is larger than [`MAX_PID_BUFFER_LEN`].
 ///
 /// # Errors
 ///
 /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
 ///
+/// ## Possible errors
+///
+/// * There are more than [`MAX_PID_BUFFER_LEN`] processes. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
+///
 /// # Examples
 /// TODO
 ///
+#[inline]
 pub fn get_pids(initial_buffer_len: u32) -> Result<Box<[u32]>> {
-    let mut buffer = vec![0_u32; initial_buffer_len as usize];
-    let mut bytes_written = 0;
-    while buffer.len() < MAX_PID_BUFFER_LEN {
-        bytes_written = 0;
-        call_BOOL! {
-            EnumProcesses(
-                buffer.as_mut_ptr(),
-                (buffer.len() * size_of::<u32>()) as u32,
-                &mut bytes_written)
-            return Error;
-        };
-        if bytes_written as usize / size_of::<u32>() < buffer.len() {
-            break;
-        }
+    get_pids_in(&Native, initial_buffer_len)
+}
 
-        buffer.resize(buffer.len() * 2, 0);
-    }
+/// Gets the process identifier for each process in the system by using the specified [`Backend`].
+/// See [`get_pids`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * There are more than [`MAX_PID_BUFFER_LEN`] processes. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
+///
+/// # Examples
+/// TODO
+///
+pub fn get_pids_in<B: Backend>(backend: &B, initial_buffer_len: u32) -> Result<Box<[u32]>> {
+    let policy = BufferPolicy::new(initial_buffer_len as usize, MAX_PID_BUFFER_LEN);
+    get_pids_with_policy_in(backend, policy)
+}
 
-    let len = bytes_written as usize / size_of::<u32>();
-    buffer.truncate(len);
-    Ok(buffer.into_boxed_slice())
+/// Gets the process identifier for each process in the system by using the specified [`Backend`] and buffer policy.
+/// See [`get_pids_in`].
+pub(crate) fn get_pids_with_policy_in<B: Backend>(
+    backend: &B,
+    policy: BufferPolicy,
+) -> Result<Box<[u32]>> {
+    negotiate(policy, |buffer| {
+        let bytes_written = backend.enum_processes(buffer)?;
+        Ok(Fill::until_full(
+            bytes_written as usize / size_of::<u32>(),
+            buffer.len(),
+        ))
+    })
+    .map(Vec::into_boxed_slice)
 }
 
 /// Copies the process identifier for each process in the system to `buffer`
@@ -67,14 +113,975 @@ pub fn get_pids(initial_buffer_len: u32) -> Result<Box<[u32]>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-enumprocesses
 ///
+#[inline]
 pub fn get_pids_with_buffer(buffer: &mut [u32]) -> Result<usize> {
-    let mut bytes_written = 0;
-    call_BOOL! {
-        EnumProcesses(
-            buffer.as_mut_ptr(),
-            buffer.len() as u32,
-            &mut bytes_written)
-        return Error;
+    Native
+        .enum_processes(buffer)
+        .map(|bytes_written| bytes_written as usize / size_of::<u32>())
+}
+
+/// Gets the name of the memory-mapped file that contains `address` in the virtual address space of the specified process.
+///
+/// # Remarks
+///
+/// * The name is a native device path (e.g. `\Device\HarddiskVolume1\Windows\System32\ntdll.dll`).
+/// * The buffer is grown until the name fits, up to [`MAX_MAPPED_FILE_NAME_LEN`] characters.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `address` is not in a memory-mapped file or an image. ([`ErrorKind::Other`][`crate::win32::core::ErrorKind::Other`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getmappedfilenamew
+///
+#[inline]
+pub fn get_mapped_file_name<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<u8>,
+) -> Result<U16String> {
+    get_mapped_file_name_in(&Native, handle, address)
+}
+
+/// Gets the name of the memory-mapped file that contains `address` in the virtual address space of the specified process
+/// by using the specified [`Backend`]. See [`get_mapped_file_name`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `address` is not in a memory-mapped file or an image. ([`ErrorKind::Other`][`crate::win32::core::ErrorKind::Other`])
+///
+/// # Examples
+/// TODO
+///
+pub fn get_mapped_file_name_in<B: Backend, const R: ProcessAccessRights>(
+    backend: &B,
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<u8>,
+) -> Result<U16String> {
+    let handle = handle.require_rights::<PROCESS_QUERY_INFORMATION>();
+    let policy = BufferPolicy::new(MAX_PATH as usize, MAX_MAPPED_FILE_NAME_LEN);
+    negotiate(policy, |buffer| {
+        let len = backend.get_mapped_file_name(handle, address.address(), buffer)?;
+        Ok(Fill::truncated(len, buffer.len()))
+    })
+    .map(U16String::from_vec)
+}
+
+/// Copies the name of the memory-mapped file that contains `address` in the virtual address space of the specified process
+/// to `buffer` and returns the length of the name.
+///
+/// # Remarks
+///
+/// * The name is truncated and null-terminated if `buffer` is too small. If the returned length is at least
+///   `buffer.len() - 1`, the name was probably truncated. See the implementation of [`get_mapped_file_name_in`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `address` is not in a memory-mapped file or an image. ([`ErrorKind::Other`][`crate::win32::core::ErrorKind::Other`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getmappedfilenamew
+///
+pub fn get_mapped_file_name_with_buffer<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    address: RemotePtr<u8>,
+    buffer: &mut [u16],
+) -> Result<usize> {
+    let handle = handle.require_rights::<PROCESS_QUERY_INFORMATION>();
+    Native.get_mapped_file_name(handle, address.address(), buffer)
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+/// The layout of `PROCESS_MEMORY_COUNTERS_EX2`, which is not defined by `windows-sys`.
+struct ProcessMemoryCountersEx2 {
+    /// `cb`
+    cb: u32,
+    /// `PageFaultCount`
+    page_fault_count: u32,
+    /// `PeakWorkingSetSize`
+    peak_working_set_size: usize,
+    /// `WorkingSetSize`
+    working_set_size: usize,
+    /// `QuotaPeakPagedPoolUsage`
+    quota_peak_paged_pool_usage: usize,
+    /// `QuotaPagedPoolUsage`
+    quota_paged_pool_usage: usize,
+    /// `QuotaPeakNonPagedPoolUsage`
+    quota_peak_non_paged_pool_usage: usize,
+    /// `QuotaNonPagedPoolUsage`
+    quota_non_paged_pool_usage: usize,
+    /// `PagefileUsage`
+    pagefile_usage: usize,
+    /// `PeakPagefileUsage`
+    peak_pagefile_usage: usize,
+    /// `PrivateUsage`
+    private_usage: usize,
+    /// `PrivateWorkingSetSize`
+    private_working_set_size: usize,
+    /// `SharedCommitUsage`
+    shared_commit_usage: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// The memory usage of a process. See [`get_memory_counters`].
+///
+/// # Remarks
+///
+/// * Every size is in bytes.
+///
+pub struct MemoryCounters {
+    /// The number of page faults.
+    pub page_fault_count: u32,
+    /// The peak working set size.
+    pub peak_working_set: usize,
+    /// The current working set size.
+    pub working_set: usize,
+    /// The peak paged pool usage.
+    pub quota_peak_paged_pool: usize,
+    /// The current paged pool usage.
+    pub quota_paged_pool: usize,
+    /// The peak nonpaged pool usage.
+    pub quota_peak_non_paged_pool: usize,
+    /// The current nonpaged pool usage.
+    pub quota_non_paged_pool: usize,
+    /// The commit charge of the process, which is the same as [`private_usage`][`Self::private_usage`]
+    /// on current versions of Windows.
+    pub pagefile_usage: usize,
+    /// The peak commit charge of the process.
+    pub peak_pagefile_usage: usize,
+    /// The private bytes of the process: memory that cannot be shared with other processes.
+    pub private_usage: usize,
+    /// The private part of the working set, or [`None`] if the system doesn't report it
+    /// (`PROCESS_MEMORY_COUNTERS_EX2` is supported since Windows 10 version 21H2).
+    pub private_working_set: Option<usize>,
+    /// The commit usage of the shared memory of the process, or [`None`] if the system doesn't report it.
+    pub shared_commit: Option<u64>,
+}
+
+impl From<PROCESS_MEMORY_COUNTERS_EX> for MemoryCounters {
+    fn from(counters: PROCESS_MEMORY_COUNTERS_EX) -> Self {
+        Self {
+            page_fault_count: counters.PageFaultCount,
+            peak_working_set: counters.PeakWorkingSetSize,
+            working_set: counters.WorkingSetSize,
+            quota_peak_paged_pool: counters.QuotaPeakPagedPoolUsage,
+            quota_paged_pool: counters.QuotaPagedPoolUsage,
+            quota_peak_non_paged_pool: counters.QuotaPeakNonPagedPoolUsage,
+            quota_non_paged_pool: counters.QuotaNonPagedPoolUsage,
+            pagefile_usage: counters.PagefileUsage,
+            peak_pagefile_usage: counters.PeakPagefileUsage,
+            private_usage: counters.PrivateUsage,
+            private_working_set: None,
+            shared_commit: None,
+        }
+    }
+}
+
+impl From<ProcessMemoryCountersEx2> for MemoryCounters {
+    fn from(counters: ProcessMemoryCountersEx2) -> Self {
+        Self {
+            page_fault_count: counters.page_fault_count,
+            peak_working_set: counters.peak_working_set_size,
+            working_set: counters.working_set_size,
+            quota_peak_paged_pool: counters.quota_peak_paged_pool_usage,
+            quota_paged_pool: counters.quota_paged_pool_usage,
+            quota_peak_non_paged_pool: counters.quota_peak_non_paged_pool_usage,
+            quota_non_paged_pool: counters.quota_non_paged_pool_usage,
+            pagefile_usage: counters.pagefile_usage,
+            peak_pagefile_usage: counters.peak_pagefile_usage,
+            private_usage: counters.private_usage,
+            private_working_set: Some(counters.private_working_set_size),
+            shared_commit: Some(counters.shared_commit_usage),
+        }
+    }
+}
+
+/// Gets the memory usage of the specified process.
+///
+/// # Remarks
+///
+/// * `PROCESS_MEMORY_COUNTERS_EX2` is requested first. If the system doesn't support it,
+///   `PROCESS_MEMORY_COUNTERS_EX` is requested instead and the additional counters are [`None`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_LIMITED_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getprocessmemoryinfo
+///
+pub fn get_memory_counters<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<MemoryCounters> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ }>();
+    let mut counters = ProcessMemoryCountersEx2 {
+        cb: size_of::<ProcessMemoryCountersEx2>() as u32,
+        ..Default::default()
+    };
+    let result = call_BOOL! {
+        GetProcessMemoryInfo(
+            handle.as_raw(),
+            addr_of_mut!(counters).cast::<PROCESS_MEMORY_COUNTERS>(),
+            counters.cb
+        )
     };
-    Ok(bytes_written as usize / size_of::<u32>())
+    match result {
+        Ok(()) => Ok(MemoryCounters::from(counters)),
+        Err(error)
+            if matches!(
+                error.kind(),
+                ErrorKind::InsufficientBuffer | ErrorKind::InvalidParameter
+            ) =>
+        {
+            call_BOOL! {
+                GetProcessMemoryInfo(
+                    handle.as_raw(),
+                    addr_of_mut!(counters_ex).cast::<PROCESS_MEMORY_COUNTERS>(),
+                    size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32
+                ) -> mut counters_ex: PROCESS_MEMORY_COUNTERS_EX
+            }
+            .map(MemoryCounters::from)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// The system-wide performance information. See [`get_performance_info`].
+///
+/// # Remarks
+///
+/// * Every size is in bytes. The system reports them in pages, so they are multiplied by [`page_size`][`Self::page_size`].
+///
+pub struct PerformanceInfo {
+    /// The number of bytes that are currently committed by the system.
+    pub commit_total: usize,
+    /// The maximum number of bytes that can be committed without extending the paging files.
+    pub commit_limit: usize,
+    /// The peak number of committed bytes since the last restart.
+    pub commit_peak: usize,
+    /// The amount of physical memory.
+    pub physical_total: usize,
+    /// The amount of available physical memory.
+    pub physical_available: usize,
+    /// The amount of the system cache memory.
+    pub system_cache: usize,
+    /// The sum of the paged and nonpaged kernel pools.
+    pub kernel_total: usize,
+    /// The size of the paged kernel pool.
+    pub kernel_paged: usize,
+    /// The size of the nonpaged kernel pool.
+    pub kernel_nonpaged: usize,
+    /// The size of a page.
+    pub page_size: usize,
+    /// The number of open handles.
+    pub handle_count: u32,
+    /// The number of processes.
+    pub process_count: u32,
+    /// The number of threads.
+    pub thread_count: u32,
+}
+
+impl From<PERFORMANCE_INFORMATION> for PerformanceInfo {
+    fn from(information: PERFORMANCE_INFORMATION) -> Self {
+        let bytes = |pages: usize| pages.saturating_mul(information.PageSize);
+        Self {
+            commit_total: bytes(information.CommitTotal),
+            commit_limit: bytes(information.CommitLimit),
+            commit_peak: bytes(information.CommitPeak),
+            physical_total: bytes(information.PhysicalTotal),
+            physical_available: bytes(information.PhysicalAvailable),
+            system_cache: bytes(information.SystemCache),
+            kernel_total: bytes(information.KernelTotal),
+            kernel_paged: bytes(information.KernelPaged),
+            kernel_nonpaged: bytes(information.KernelNonpaged),
+            page_size: information.PageSize,
+            handle_count: information.HandleCount,
+            process_count: information.ProcessCount,
+            thread_count: information.ThreadCount,
+        }
+    }
+}
+
+/// Gets the system-wide performance information: commit charge, memory, handle, process and thread totals.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getperformanceinfo
+///
+pub fn get_performance_info() -> Result<PerformanceInfo> {
+    call_BOOL! {
+        GetPerformanceInfo(
+            addr_of_mut!(information),
+            size_of::<PERFORMANCE_INFORMATION>() as u32
+        ) -> mut information: PERFORMANCE_INFORMATION
+    }
+    .map(PerformanceInfo::from)
+}
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// Specifies which modules are enumerated by [`get_modules`] and [`modules`].
+pub enum ModuleFilter {
+    /// The default behavior of `EnumProcessModules`: the modules that match the bitness of the caller.
+    #[default]
+    Default = LIST_MODULES_DEFAULT,
+    /// Only the 32-bit modules.
+    Bits32 = LIST_MODULES_32BIT,
+    /// Only the 64-bit modules.
+    Bits64 = LIST_MODULES_64BIT,
+    /// Every module.
+    All = LIST_MODULES_ALL,
+}
+
+impl To<u32> for ModuleFilter {
+    #[inline]
+    fn to(&self) -> u32 {
+        *self as u32
+    }
+}
+
+/// Gets the handles of the modules that are loaded into the specified process and match `filter`.
+///
+/// # Remarks
+///
+/// * A module handle is the base address of the module in the virtual address space of the process.
+/// * The buffer starts with `initial_buffer_len` elements, and it is resized to the required length
+///   until every module handle fits, up to [`MAX_MODULE_BUFFER_LEN`] elements.
+/// * The first module is the executable of the process.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * The process is a 64-bit process and the caller is a 32-bit process. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-enumprocessmodulesex
+///
+#[inline]
+pub fn get_modules<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    filter: ModuleFilter,
+    initial_buffer_len: usize,
+) -> Result<Box<[isize]>> {
+    get_modules_in(&Native, handle, filter, initial_buffer_len)
+}
+
+/// Gets the handles of the modules that are loaded into the specified process and match `filter`
+/// by using the specified [`Backend`]. See [`get_modules`].
+///
+/// # Remarks
+///
+/// * The buffer is reallocated if modules are loaded between the calls.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * There are more than [`MAX_MODULE_BUFFER_LEN`] modules. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
+///
+/// # Examples
+/// TODO
+///
+pub fn get_modules_in<B: Backend, const R: ProcessAccessRights>(
+    backend: &B,
+    handle: &ProcessHandle<R>,
+    filter: ModuleFilter,
+    initial_buffer_len: usize,
+) -> Result<Box<[isize]>> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>();
+    let policy = BufferPolicy::new(initial_buffer_len, MAX_MODULE_BUFFER_LEN);
+    negotiate(policy, |buffer| {
+        let mut bytes_needed = 0;
+        backend.enum_process_modules(handle, buffer, filter.to(), &mut bytes_needed)?;
+        let required_len = bytes_needed as usize / size_of::<isize>();
+        Ok(if required_len <= buffer.len() {
+            Fill::Written(required_len)
+        } else {
+            Fill::TooSmall(Some(required_len))
+        })
+    })
+    .map(Vec::into_boxed_slice)
+}
+
+/// Copies the handles of the modules that are loaded into the specified process and match `filter` to `buffer`,
+/// and returns the number of modules.
+///
+/// # Remarks
+///
+/// * If the returned `usize` is larger than the length of `buffer`, the handles were truncated.
+///   See the implementation of [`get_modules`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-enumprocessmodulesex
+///
+pub fn get_modules_with_buffer<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    filter: ModuleFilter,
+    buffer: &mut [isize],
+) -> Result<usize> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>();
+    let mut bytes_needed = 0;
+    Native.enum_process_modules(handle, buffer, filter.to(), &mut bytes_needed)?;
+    Ok(bytes_needed as usize / size_of::<isize>())
+}
+
+/// Gets the full path of the file that contains the specified module of the specified process.
+///
+/// # Remarks
+///
+/// * The buffer is grown until the path fits, up to [`MAX_MODULE_FILE_NAME_LEN`] characters.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `module` is not loaded into the process. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getmodulefilenameexw
+///
+#[inline]
+pub fn get_module_file_name<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    module: isize,
+) -> Result<U16String> {
+    get_module_file_name_in(&Native, handle, module)
+}
+
+/// Gets the full path of the file that contains the specified module of the specified process
+/// by using the specified [`Backend`]. See [`get_module_file_name`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `module` is not loaded into the process. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn get_module_file_name_in<B: Backend, const R: ProcessAccessRights>(
+    backend: &B,
+    handle: &ProcessHandle<R>,
+    module: isize,
+) -> Result<U16String> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>();
+    let policy = BufferPolicy::new(MAX_PATH as usize, MAX_MODULE_FILE_NAME_LEN);
+    negotiate(policy, |buffer| {
+        let len = backend.get_module_file_name(handle, module, buffer)?;
+        Ok(Fill::truncated(len, buffer.len()))
+    })
+    .map(U16String::from_vec)
+}
+
+/// Copies the full path of the file that contains the specified module of the specified process to `buffer`
+/// and returns the length of the path.
+///
+/// # Remarks
+///
+/// * The path is truncated and null-terminated if `buffer` is too small. If the returned length is at least
+///   `buffer.len() - 1`, the path was probably truncated. See the implementation of [`get_module_file_name_in`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `module` is not loaded into the process. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getmodulefilenameexw
+///
+pub fn get_module_file_name_with_buffer<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    module: isize,
+    buffer: &mut [u16],
+) -> Result<usize> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>();
+    Native.get_module_file_name(handle, module, buffer)
+}
+
+/// Gets the base name (the file name without the directory) of the specified module of the specified process.
+///
+/// # Remarks
+///
+/// * The buffer is grown until the name fits, up to [`MAX_MODULE_FILE_NAME_LEN`] characters.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `module` is not loaded into the process. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getmodulebasenamew
+///
+#[inline]
+pub fn get_module_base_name<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    module: isize,
+) -> Result<U16String> {
+    get_module_base_name_in(&Native, handle, module)
+}
+
+/// Gets the base name of the specified module of the specified process by using the specified [`Backend`].
+/// See [`get_module_base_name`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `module` is not loaded into the process. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn get_module_base_name_in<B: Backend, const R: ProcessAccessRights>(
+    backend: &B,
+    handle: &ProcessHandle<R>,
+    module: isize,
+) -> Result<U16String> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>();
+    let policy = BufferPolicy::new(MAX_PATH as usize, MAX_MODULE_FILE_NAME_LEN);
+    negotiate(policy, |buffer| {
+        let len = backend.get_module_base_name(handle, module, buffer)?;
+        Ok(Fill::truncated(len, buffer.len()))
+    })
+    .map(U16String::from_vec)
+}
+
+/// Copies the base name of the specified module of the specified process to `buffer` and returns the length of the name.
+///
+/// # Remarks
+///
+/// * The name is truncated and null-terminated if `buffer` is too small. If the returned length is at least
+///   `buffer.len() - 1`, the name was probably truncated. See the implementation of [`get_module_base_name_in`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `module` is not loaded into the process. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getmodulebasenamew
+///
+pub fn get_module_base_name_with_buffer<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    module: isize,
+    buffer: &mut [u16],
+) -> Result<usize> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>();
+    Native.get_module_base_name(handle, module, buffer)
+}
+
+/// Gets the base address, the size and the entry point of the specified module of the specified process.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `module` is not loaded into the process. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/psapi/nf-psapi-getmoduleinformation
+///
+pub fn get_module_information<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    module: isize,
+) -> Result<MODULEINFO> {
+    let handle = handle.require_rights::<{ PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>();
+    call_BOOL! {
+        GetModuleInformation(
+            handle.as_raw(),
+            module,
+            addr_of_mut!(information),
+            size_of::<MODULEINFO>() as u32
+        ) -> mut information: MODULEINFO
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A module that is loaded into a process. See [`modules`].
+pub struct ModuleInfo {
+    /// The handle of the module, which is also its base address.
+    pub handle: isize,
+    /// The base address of the module in the virtual address space of the process.
+    pub base: RemotePtr<u8>,
+    /// The size of the module image in bytes.
+    pub size: usize,
+    /// The entry point of the module, which is null if the module has none.
+    pub entry_point: RemotePtr<u8>,
+    /// The full path of the module file.
+    pub path: U16String,
+}
+
+impl ModuleInfo {
+    /// Gets the address after the last byte of the module image.
+    pub const fn end(&self) -> RemotePtr<u8> {
+        self.base.wrapping_byte_add(self.size)
+    }
+
+    /// Determines whether `address` is in the module image.
+    pub const fn contains(&self, address: RemotePtr<u8>) -> bool {
+        address.address() >= self.base.address()
+            && address.address() - self.base.address() < self.size
+    }
+}
+
+#[derive(Debug)]
+/// An iterator over the [`ModuleInfo`]s of the modules that are loaded into a process. See [`modules`].
+pub struct Modules<'handle, const R: ProcessAccessRights> {
+    /// The process handle.
+    handle: &'handle ProcessHandle<R>,
+    /// The enumerated module handles.
+    modules: Box<[isize]>,
+    /// The index of the next module handle.
+    next: usize,
+}
+
+impl<const R: ProcessAccessRights> Modules<'_, R> {
+    /// Gets the enumerated module handles, including the ones that were already yielded.
+    pub fn handles(&self) -> &[isize] {
+        &self.modules
+    }
+}
+
+impl<const R: ProcessAccessRights> Iterator for Modules<'_, R> {
+    type Item = Result<ModuleInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let module = *self.modules.get(self.next)?;
+        self.next += 1;
+        let information = match get_module_information(self.handle, module) {
+            Ok(information) => information,
+            Err(error) => return Some(Err(error)),
+        };
+        Some(
+            get_module_file_name(self.handle, module).map(|path| ModuleInfo {
+                handle: module,
+                base: RemotePtr::new(information.lpBaseOfDll as usize),
+                size: information.SizeOfImage as usize,
+                entry_point: RemotePtr::new(information.EntryPoint as usize),
+                path,
+            }),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.modules.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const R: ProcessAccessRights> ExactSizeIterator for Modules<'_, R> {}
+
+impl<const R: ProcessAccessRights> core::iter::FusedIterator for Modules<'_, R> {}
+
+/// Enumerates the modules that are loaded into the specified process and match `filter`,
+/// and returns an iterator that yields a [`ModuleInfo`] for each of them.
+///
+/// # Remarks
+///
+/// * The module handles are enumerated once using [`get_modules`]. The information and the path of each module
+///   are queried when the iterator yields it, so a module that was unloaded in the meantime yields an error.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the modules cannot be enumerated.
+/// The iterator yields a [`Win32Error`][`crate::win32::core::Win32Error`] if a module cannot be queried.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+pub fn modules<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    filter: ModuleFilter,
+) -> Result<Modules<'_, R>> {
+    Ok(Modules {
+        handle,
+        modules: get_modules(handle, filter, RECOMMENDED_INITIAL_MODULE_BUFFER_LENGTH)?,
+        next: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::win32::backend::mock::{fake_handle, ScriptedBackend, Step};
+    use crate::win32::core::Win32Error;
+    use crate::win32::system::threading::process::ProcessObject;
+    use alloc::vec;
+    use windows_sys::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_INSUFFICIENT_BUFFER};
+
+    #[test]
+    fn get_pids_grows_while_the_result_is_truncated() {
+        let pids: Vec<u32> = (1..=5).collect();
+        let backend = ScriptedBackend::new()
+            .then(Step::EnumProcesses(Ok(pids.clone())))
+            .then(Step::EnumProcesses(Ok(pids.clone())))
+            .then(Step::EnumProcesses(Ok(pids.clone())));
+
+        let result = get_pids_in(&backend, 2).unwrap();
+
+        assert_eq!(&*result, pids.as_slice());
+        assert!(backend.is_finished());
+        assert_eq!(backend.calls().len(), 3);
+    }
+
+    #[test]
+    fn get_pids_fails_at_the_max_len() {
+        let backend = ScriptedBackend::new()
+            .then(Step::EnumProcesses(Ok(vec![1, 2, 3, 4, 5])))
+            .then(Step::EnumProcesses(Ok(vec![1, 2, 3, 4, 5])));
+
+        let error = get_pids_with_policy_in(&backend, BufferPolicy::new(2, 4)).unwrap_err();
+
+        assert_eq!(error.code(), ERROR_INSUFFICIENT_BUFFER);
+        assert!(backend.is_finished());
+    }
+
+    #[test]
+    fn get_pids_passes_errors_through() {
+        let backend = ScriptedBackend::new()
+            .then(Step::EnumProcesses(Ok(vec![1, 2, 3])))
+            .then(Step::EnumProcesses(Err(Win32Error::new(
+                ERROR_ACCESS_DENIED,
+            )
+            .with_function("EnumProcesses"))));
+
+        let error = get_pids_in(&backend, 2).unwrap_err();
+
+        assert_eq!(error.code(), ERROR_ACCESS_DENIED);
+        assert_eq!(error.function(), Some("EnumProcesses"));
+        assert!(backend.is_finished());
+    }
+
+    /// Creates a path of `len` characters.
+    fn path(len: usize) -> Vec<u16> {
+        (0..len)
+            .map(|i| u16::from(b'a') + (i % 26) as u16)
+            .collect()
+    }
+
+    #[test]
+    fn get_mapped_file_name_returns_a_name_that_fits() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe { fake_handle::<ProcessObject, PROCESS_QUERY_INFORMATION>() };
+        let name = path(MAX_PATH as usize - 2);
+        let backend = ScriptedBackend::new().then(Step::GetMappedFileName(Ok(name.clone())));
+
+        let result = get_mapped_file_name_in(&backend, &handle, RemotePtr::new(0x1000)).unwrap();
+
+        assert_eq!(result.as_slice(), name.as_slice());
+        assert!(backend.is_finished());
+    }
+
+    #[test]
+    fn get_mapped_file_name_grows_a_possibly_truncated_name() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe { fake_handle::<ProcessObject, PROCESS_QUERY_INFORMATION>() };
+        // Native paths like `\Device\HarddiskVolume1\...` are often longer than `MAX_PATH`.
+        for len in [
+            MAX_PATH as usize - 1,
+            MAX_PATH as usize,
+            MAX_PATH as usize + 1,
+        ] {
+            let name = path(len);
+            let backend = ScriptedBackend::new()
+                .then(Step::GetMappedFileName(Ok(name.clone())))
+                .then(Step::GetMappedFileName(Ok(name.clone())));
+
+            let result =
+                get_mapped_file_name_in(&backend, &handle, RemotePtr::new(0x1000)).unwrap();
+
+            assert_eq!(result.as_slice(), name.as_slice(), "{len}");
+            assert!(backend.is_finished());
+        }
+    }
+
+    #[test]
+    fn get_module_file_name_returns_a_path_that_fits() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe {
+            fake_handle::<ProcessObject, { PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>()
+        };
+        let module_path = path(MAX_PATH as usize - 2);
+        let backend = ScriptedBackend::new().then(Step::GetModuleFileName(Ok(module_path.clone())));
+
+        let result = get_module_file_name_in(&backend, &handle, 0).unwrap();
+
+        assert_eq!(result.as_slice(), module_path.as_slice());
+        assert!(backend.is_finished());
+    }
+
+    #[test]
+    fn get_module_file_name_grows_a_possibly_truncated_path() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe {
+            fake_handle::<ProcessObject, { PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>()
+        };
+        // A path that fills the buffer up to the null terminator cannot be distinguished from a truncated one.
+        for len in [
+            MAX_PATH as usize - 1,
+            MAX_PATH as usize,
+            MAX_PATH as usize + 1,
+        ] {
+            let module_path = path(len);
+            let backend = ScriptedBackend::new()
+                .then(Step::GetModuleFileName(Ok(module_path.clone())))
+                .then(Step::GetModuleFileName(Ok(module_path.clone())));
+
+            let result = get_module_file_name_in(&backend, &handle, 0).unwrap();
+
+            assert_eq!(result.as_slice(), module_path.as_slice(), "{len}");
+            assert!(backend.is_finished());
+        }
+    }
+
+    #[test]
+    fn get_module_base_name_grows_a_possibly_truncated_name() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe {
+            fake_handle::<ProcessObject, { PROCESS_QUERY_INFORMATION | PROCESS_VM_READ }>()
+        };
+        for (len, calls) in [
+            (MAX_PATH as usize - 2, 1),
+            (MAX_PATH as usize - 1, 2),
+            (MAX_PATH as usize, 2),
+            (MAX_PATH as usize + 1, 2),
+        ] {
+            let name = path(len);
+            let mut backend = ScriptedBackend::new();
+            for _ in 0..calls {
+                backend = backend.then(Step::GetModuleBaseName(Ok(name.clone())));
+            }
+
+            let result = get_module_base_name_in(&backend, &handle, 0).unwrap();
+
+            assert_eq!(result.as_slice(), name.as_slice(), "{len}");
+            assert!(backend.is_finished(), "{len}");
+        }
+    }
 }
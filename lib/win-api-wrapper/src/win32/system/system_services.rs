@@ -0,0 +1,82 @@
+use windows_sys::Win32::System::SystemServices::{
+    LANG_ENGLISH, LANG_NEUTRAL, SUBLANG_DEFAULT, SUBLANG_ENGLISH_US, SUBLANG_NEUTRAL,
+    SUBLANG_SYS_DEFAULT,
+};
+
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// Represents a language identifier (`LANGID`) that is built from a primary language identifier and a sublanguage identifier.
+///
+/// # Remarks
+///
+/// * The lower 10 bits contain the primary language identifier and the upper 6 bits contain the sublanguage identifier.
+/// * The primary and sublanguage identifiers are defined by the `LANG_*` and `SUBLANG_*` constants of [`windows_sys::Win32::System::SystemServices`].
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/intl/language-identifiers
+///
+pub struct LangId(u16);
+
+impl LangId {
+    /// Language neutral identifier. Functions like [`format_message`][`crate::win32::system::diagnostics::debug::format_message`]
+    /// search for a message in the language neutral, thread, user default and system default languages (in this order).
+    pub const NEUTRAL: Self = Self::new(LANG_NEUTRAL as u16, SUBLANG_NEUTRAL as u16);
+    /// The default language of the user.
+    pub const USER_DEFAULT: Self = Self::new(LANG_NEUTRAL as u16, SUBLANG_DEFAULT as u16);
+    /// The default language of the system.
+    pub const SYSTEM_DEFAULT: Self = Self::new(LANG_NEUTRAL as u16, SUBLANG_SYS_DEFAULT as u16);
+    /// English (United States).
+    pub const ENGLISH_US: Self = Self::new(LANG_ENGLISH as u16, SUBLANG_ENGLISH_US as u16);
+
+    #[inline]
+    /// Creates a new [`LangId`] from the specified primary language identifier and sublanguage identifier like the `MAKELANGID` macro.
+    pub const fn new(primary_language: u16, sub_language: u16) -> Self {
+        Self((sub_language << 10) | (primary_language & 0x03FF))
+    }
+
+    #[inline]
+    /// Creates a new [`LangId`] from a raw `LANGID` value.
+    pub const fn from_raw(value: u16) -> Self {
+        Self(value)
+    }
+
+    #[inline]
+    /// Gets the raw `LANGID` value.
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+
+    #[inline]
+    /// Gets the primary language identifier like the `PRIMARYLANGID` macro.
+    pub const fn primary_language(self) -> u16 {
+        self.0 & 0x03FF
+    }
+
+    #[inline]
+    /// Gets the sublanguage identifier like the `SUBLANGID` macro.
+    pub const fn sub_language(self) -> u16 {
+        self.0 >> 10
+    }
+}
+
+impl From<u16> for LangId {
+    #[inline]
+    fn from(value: u16) -> Self {
+        Self::from_raw(value)
+    }
+}
+
+impl From<LangId> for u16 {
+    #[inline]
+    fn from(lang_id: LangId) -> Self {
+        lang_id.value()
+    }
+}
+
+impl From<LangId> for u32 {
+    #[inline]
+    fn from(lang_id: LangId) -> Self {
+        Self::from(lang_id.value())
+    }
+}
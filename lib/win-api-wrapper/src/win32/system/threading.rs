@@ -2,3 +2,5 @@
 pub mod process;
 /// `Win32::System::Threading::Thread`
 pub mod thread;
+/// Wait functions of `Win32::System::Threading`
+pub mod wait;
@@ -1,29 +1,44 @@
-use crate::common::{pcwstr_to_u16_string, To};
-use crate::win32::core::Result;
+use crate::common::buffer::{negotiate, BufferPolicy, Fill};
+use crate::common::wide::IntoWideCStr;
+use crate::common::To;
+use crate::default_sized;
+use crate::to_BOOL;
+use crate::win32::backend::{Backend, Native};
+use crate::win32::core::{Result, ResultExt, Win32Error};
+use crate::win32::foundation::{BorrowedHandle, KernelObject, ObjectHandle, OwnedHandle};
+use crate::win32::system::threading::thread::ThreadHandle;
+use crate::win32::system::threading::wait::{self, WaitOutcome};
 use crate::{call_BOOL, call_num};
-use crate::{from_BOOL, to_BOOL};
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::ffi::c_void;
 use core::mem::{size_of, transmute, zeroed};
 use core::ptr::{self, addr_of, addr_of_mut};
-use widestring::U16String;
-use windows_sys::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, FILETIME, HANDLE, STILL_ACTIVE};
+use core::time::Duration;
+use widestring::{U16CStr, U16String};
+use windows_sys::Win32::Foundation::{
+    ERROR_INVALID_PARAMETER, FILETIME, HANDLE, MAX_PATH, WAIT_TIMEOUT,
+};
 use windows_sys::Win32::System::Threading::{
-    ExitProcess, GetCurrentProcess, GetCurrentProcessId, GetExitCodeProcess, GetPriorityClass,
-    GetProcessAffinityMask, GetProcessDefaultCpuSets, GetProcessGroupAffinity,
-    GetProcessHandleCount, GetProcessId, GetProcessInformation, GetProcessIoCounters,
-    GetProcessPriorityBoost, GetProcessTimes, GetProcessVersion, GetProcessWorkingSetSize,
-    IsProcessCritical, OpenProcess, OpenProcessToken, ProcessAppMemoryInfo, ProcessLeapSecondInfo,
-    ProcessMemoryPriority, ProcessPowerThrottling, ProcessProtectionLevelInfo,
-    QueryFullProcessImageNameW, SetPriorityClass, SetProcessAffinityMask,
-    SetProcessAffinityUpdateMode, SetProcessDefaultCpuSets, SetProcessInformation,
-    SetProcessPriorityBoost, SetProcessWorkingSetSize, TerminateProcess, WaitForInputIdle,
-    PROCESS_AFFINITY_DISABLE_AUTO_UPDATE, PROCESS_AFFINITY_ENABLE_AUTO_UPDATE,
+    CreateProcessW, ExitProcess, GetCurrentProcess, GetCurrentProcessId, GetExitCodeProcess,
+    GetPriorityClass, GetProcessAffinityMask, GetProcessDefaultCpuSets, GetProcessHandleCount,
+    GetProcessId, GetProcessInformation, GetProcessIoCounters, GetProcessPriorityBoost,
+    GetProcessTimes, GetProcessVersion, GetProcessWorkingSetSize, IsProcessCritical, OpenProcess,
+    OpenProcessToken, ProcessAppMemoryInfo, ProcessLeapSecondInfo, ProcessMemoryPriority,
+    ProcessPowerThrottling, ProcessProtectionLevelInfo, QueryFullProcessImageNameW,
+    SetPriorityClass, SetProcessAffinityMask, SetProcessAffinityUpdateMode,
+    SetProcessDefaultCpuSets, SetProcessInformation, SetProcessPriorityBoost,
+    SetProcessWorkingSetSize, TerminateProcess, WaitForInputIdle,
+    PROCESS_AFFINITY_DISABLE_AUTO_UPDATE, PROCESS_AFFINITY_ENABLE_AUTO_UPDATE, PROCESS_INFORMATION,
+    STARTF_USESTDHANDLES, STARTUPINFOW,
 };
 
+pub use windows_sys::Win32::Foundation::STILL_ACTIVE;
 pub use windows_sys::Win32::System::Threading::{
     ABOVE_NORMAL_PRIORITY_CLASS, APP_MEMORY_INFORMATION, BELOW_NORMAL_PRIORITY_CLASS,
-    HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, IO_COUNTERS, MEMORY_PRIORITY,
+    CREATE_BREAKAWAY_FROM_JOB, CREATE_DEFAULT_ERROR_MODE, CREATE_NEW_CONSOLE,
+    CREATE_NEW_PROCESS_GROUP, CREATE_NO_WINDOW, CREATE_SUSPENDED, CREATE_UNICODE_ENVIRONMENT,
+    DETACHED_PROCESS, HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, IO_COUNTERS, MEMORY_PRIORITY,
     MEMORY_PRIORITY_BELOW_NORMAL, MEMORY_PRIORITY_INFORMATION, MEMORY_PRIORITY_LOW,
     MEMORY_PRIORITY_MEDIUM, MEMORY_PRIORITY_NORMAL, MEMORY_PRIORITY_VERY_LOW,
     NORMAL_PRIORITY_CLASS, PROCESS_ACCESS_RIGHTS as ProcessAccessRights, PROCESS_ALL_ACCESS,
@@ -45,7 +60,56 @@ pub use windows_sys::Win32::System::Threading::{
     REALTIME_PRIORITY_CLASS, THREAD_POWER_THROTTLING_CURRENT_VERSION,
 };
 
-use crate::win32::security::{self, TOKEN_ELEVATION, TOKEN_QUERY};
+use crate::win32::security::{self, TokenHandle, TOKEN_ACCESS_MASK, TOKEN_ELEVATION, TOKEN_QUERY};
+
+/// Command line, current directory and environment of other processes
+pub mod parameters;
+
+#[derive(Debug)]
+/// The process kernel object. See [`ProcessHandle`].
+pub struct ProcessObject;
+
+impl KernelObject for ProcessObject {
+    const IMPLIED_RIGHTS: &'static [(u32, u32)] = &[
+        (PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION),
+        (PROCESS_SET_INFORMATION, PROCESS_SET_LIMITED_INFORMATION),
+    ];
+}
+
+/// An owned process handle that has the `RIGHTS` access rights. See [`open`].
+pub type ProcessHandle<const RIGHTS: ProcessAccessRights = PROCESS_ALL_ACCESS> =
+    ObjectHandle<ProcessObject, RIGHTS>;
+
+#[derive(Debug, Clone, Copy, Default)]
+/// The standard handles of a process that is created by [`create`].
+///
+/// # Remarks
+///
+/// * The handles must be inheritable and `inherit_handles` must be `true`, otherwise the new process cannot use them.
+/// * A [`None`] handle means that the new process doesn't have the corresponding standard handle.
+///
+pub struct StdHandles<'handle> {
+    /// The standard input handle.
+    pub input: Option<BorrowedHandle<'handle>>,
+    /// The standard output handle.
+    pub output: Option<BorrowedHandle<'handle>>,
+    /// The standard error handle.
+    pub error: Option<BorrowedHandle<'handle>>,
+}
+
+#[derive(Debug)]
+/// A process that was created by [`create`] and its primary thread.
+pub struct CreatedProcess {
+    /// The handle of the new process. It has the [`PROCESS_ALL_ACCESS`] access right.
+    pub process: ProcessHandle,
+    /// The handle of the primary thread of the new process. It has the
+    /// [`THREAD_ALL_ACCESS`][`crate::win32::system::threading::thread::THREAD_ALL_ACCESS`] access right.
+    pub thread: ThreadHandle,
+    /// The identifier of the new process.
+    pub process_id: u32,
+    /// The identifier of the primary thread of the new process.
+    pub thread_id: u32,
+}
 
 /// Maximum number of characters allowed in a long path.
 const MAX_CHARS_IN_LONG_PATH: usize = u16::MAX as usize / size_of::<u16>();
@@ -77,13 +141,15 @@ pub fn exit_current(exit_code: u32) {
 ///
 /// * The returned value is a special constant, currently `-1`, that is interpreted as the current process handle.
 /// * The pseudo handle need not to be closed when it is no longer needed.
+/// * This handle has the [`PROCESS_ALL_ACCESS`] access right to the process object.
+/// * The returned handle is equivalent to [`BorrowedHandle::current_process`][`crate::win32::foundation::BorrowedHandle::current_process`].
 ///
 /// # Examples
 ///
 /// Getting the priority class of the calling process:
 /// ```
 /// let current_process = get_current_handle();
-/// match get_priority_class(current_process) {
+/// match get_priority_class(&current_process) {
 ///     Ok(priority_class) => println!("The current process priority class is {}", priority_class),
 ///     Err(error) => println!("Failed to get the current process priority class: {}", error),
 /// }
@@ -93,10 +159,10 @@ pub fn exit_current(exit_code: u32) {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getcurrentprocess
 ///
-pub fn get_current_handle() -> isize {
+pub fn get_current_handle() -> ProcessHandle {
     #[allow(clippy::undocumented_unsafe_blocks)]
     unsafe {
-        GetCurrentProcess()
+        ProcessHandle::from_owned(OwnedHandle::from_raw(GetCurrentProcess()))
     }
 }
 
@@ -121,21 +187,139 @@ pub fn get_current_id() -> u32 {
     }
 }
 
+/// Creates a new process and its primary thread.
+///
+/// # Arguments
+///
+/// * `application_name`: The path of the executable. If it is [`None`], the first token of `command_line` is used and searched in the `PATH`.
+/// * `command_line`: The command line of the new process (including the program name).
+/// * `inherit_handles`: Specifies whether the inheritable handles of the calling process are inherited by the new process.
+/// * `creation_flags`: A combination of the process creation flags (e.g. [`CREATE_SUSPENDED`] or [`CREATE_NEW_CONSOLE`]) and a priority class (e.g. [`IDLE_PRIORITY_CLASS`]).
+/// * `environment`: The double-null-terminated UTF-16 environment block of the new process. If it is [`None`], the environment of the calling process is inherited.
+/// * `current_directory`: The current directory of the new process. If it is [`None`], the current directory of the calling process is inherited.
+/// * `std_handles`: The standard handles of the new process. If it is [`None`], the standard handles are not redirected.
+///
+/// # Remarks
+///
+/// * [`CREATE_UNICODE_ENVIRONMENT`] is added to `creation_flags` when `environment` is specified.
+/// * The command line is copied before the call, because `CreateProcessW` may modify it.
+/// * The returned handles are closed when they are dropped. Closing them doesn't terminate the process.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * Both `application_name` and `command_line` are [`None`]. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * `environment` is not double-null-terminated. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * `application_name`, `command_line` or `current_directory` contains a null character. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * The executable doesn't exist. ([`ErrorKind::NotFound`][`crate::win32::core::ErrorKind::NotFound`])
+/// * The caller is not allowed to execute the executable. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-createprocessw
+///
+pub fn create<A, C, D>(
+    application_name: Option<&A>,
+    command_line: Option<&C>,
+    inherit_handles: bool,
+    creation_flags: PROCESS_CREATION_FLAGS,
+    environment: Option<&[u16]>,
+    current_directory: Option<&D>,
+    std_handles: Option<StdHandles<'_>>,
+) -> Result<CreatedProcess>
+where
+    A: IntoWideCStr + ?Sized,
+    C: IntoWideCStr + ?Sized,
+    D: IntoWideCStr + ?Sized,
+{
+    if application_name.is_none() && command_line.is_none() {
+        return Err(Win32Error::new(ERROR_INVALID_PARAMETER)
+            .with_function("CreateProcessW")
+            .with_context("no application name or command line"));
+    }
+    if environment.is_some_and(|environment| !environment.ends_with(&[0, 0])) {
+        return Err(Win32Error::new(ERROR_INVALID_PARAMETER)
+            .with_function("CreateProcessW")
+            .with_context("the environment block is not double-null-terminated"));
+    }
+
+    let application_name = application_name
+        .map(IntoWideCStr::to_wide_c_str)
+        .transpose()?;
+    let mut command_line = command_line
+        .map(|command_line| {
+            command_line
+                .to_wide_c_str()
+                .map(|command_line| command_line.as_slice_with_nul().to_vec())
+        })
+        .transpose()?;
+    let current_directory = current_directory
+        .map(IntoWideCStr::to_wide_c_str)
+        .transpose()?;
+    let mut startup_info = default_sized!(STARTUPINFOW);
+    startup_info.cb = size_of::<STARTUPINFOW>() as u32;
+    if let Some(std_handles) = std_handles {
+        let raw = |handle: Option<BorrowedHandle<'_>>| handle.map_or(0, |handle| handle.as_raw());
+        startup_info.dwFlags |= STARTF_USESTDHANDLES;
+        startup_info.hStdInput = raw(std_handles.input);
+        startup_info.hStdOutput = raw(std_handles.output);
+        startup_info.hStdError = raw(std_handles.error);
+    }
+    let creation_flags = if environment.is_some() {
+        creation_flags | CREATE_UNICODE_ENVIRONMENT
+    } else {
+        creation_flags
+    };
+
+    let mut information = default_sized!(PROCESS_INFORMATION);
+    call_BOOL! {
+        CreateProcessW(
+            application_name.as_deref().map_or(ptr::null(), U16CStr::as_ptr),
+            command_line.as_mut().map_or(ptr::null_mut(), Vec::as_mut_ptr),
+            ptr::null(),
+            ptr::null(),
+            to_BOOL!(inherit_handles),
+            creation_flags,
+            environment.map_or(ptr::null(), |environment| environment.as_ptr().cast::<c_void>()),
+            current_directory.as_deref().map_or(ptr::null(), U16CStr::as_ptr),
+            addr_of!(startup_info),
+            addr_of_mut!(information)
+        ) return Error
+    };
+
+    // Safety: The handles are newly created handles that have full access rights and they are not owned by anything else.
+    Ok(unsafe {
+        CreatedProcess {
+            process: ProcessHandle::from_owned(OwnedHandle::from_raw(information.hProcess)),
+            thread: ThreadHandle::from_owned(OwnedHandle::from_raw(information.hThread)),
+            process_id: information.dwProcessId,
+            thread_id: information.dwThreadId,
+        }
+    })
+}
+
 /// Opens an existing local process object.
 ///
 /// # Arguments
 ///
+/// * `RIGHTS`: One or more [`access rights`][`ProcessAccessRights`] to the process object
 /// * `pid`: The process identifier
-/// * `access`: One or more [`access rights`][`ProcessAccessRights`] to the process object
 /// * `inherit_handle`: Specifies whether processes created by this process should inherit it's handle
 ///
 /// # Result
 ///
-/// An open handle to the specified process.
+/// An open handle to the specified process that has the `RIGHTS` access rights.
 ///
 /// # Remarks
 ///
-/// * If the handle is not needed anymore close it using [`close_handle`](crate::win32::foundation::close_handle).
+/// * The returned handle is closed when it is dropped.
+/// * Passing the returned handle to a function that requires access rights which are not included in `RIGHTS` fails to compile.
 ///
 /// # Errors
 ///
@@ -143,10 +327,9 @@ pub fn get_current_id() -> u32 {
 ///
 /// ## Possible errors
 ///
-///
-/// * The process doesn't exist.
-/// * The specified process is the System Idle Process (`0x00000000`). ([ERROR_INVALID_PARAMETER])
-/// * The specified process is the System process or one of the Client Server Run-Time Subsystem (CSRSS) processes. ([ERROR_ACCESS_DENIED])
+/// * The process doesn't exist. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * The specified process is the System Idle Process (`0x00000000`). ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * The specified process is the System process or one of the Client Server Run-Time Subsystem (CSRSS) processes. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -155,11 +338,26 @@ pub fn get_current_id() -> u32 {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openprocess
 ///
-pub fn open(pid: u32, access: ProcessAccessRights, inherit_handle: bool) -> Result<isize> {
-    call_num! { OpenProcess(access, to_BOOL!(inherit_handle), pid) != 0 }
+pub fn open<const RIGHTS: ProcessAccessRights>(
+    pid: u32,
+    inherit_handle: bool,
+) -> Result<ProcessHandle<RIGHTS>> {
+    let handle = call_num! { OpenProcess(RIGHTS, to_BOOL!(inherit_handle), pid) != 0 }
+        .with_process_id(pid)?;
+    // Safety: `handle` is a newly opened process handle that has the `RIGHTS` access rights and is not owned by anything else.
+    Ok(unsafe { ProcessHandle::from_owned(OwnedHandle::from_raw(handle)) })
 }
 
-/// Determines whether the specified process is elevated.
+/// Opens the access token associated with the specified process.
+///
+/// # Arguments
+///
+/// * `RIGHTS`: One or more access rights to the access token
+/// * `handle`: A handle to the process whose access token is opened
+///
+/// # Remarks
+///
+/// * The returned handle is closed when it is dropped.
 ///
 /// # Errors
 ///
@@ -167,22 +365,47 @@ pub fn open(pid: u32, access: ProcessAccessRights, inherit_handle: bool) -> Resu
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
 ///
-pub fn is_elevated(handle: isize) -> bool {
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openprocesstoken
+///
+pub fn open_token<const RIGHTS: TOKEN_ACCESS_MASK, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<TokenHandle<RIGHTS>> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     let mut token_handle = HANDLE::default();
-    #[allow(clippy::undocumented_unsafe_blocks)]
-    unsafe {
-        if from_BOOL!(!OpenProcessToken(handle, TOKEN_QUERY, &mut token_handle)) {
-            return false;
-        }
-    }
+    call_BOOL! { OpenProcessToken(handle.as_raw(), RIGHTS, &mut token_handle) }
+        .with_handle(handle.as_raw())?;
+    // Safety: `token_handle` is a newly opened token handle that has the `RIGHTS` access rights and is not owned by anything else.
+    Ok(unsafe { TokenHandle::from_owned(OwnedHandle::from_raw(token_handle)) })
+}
+
+/// Determines whether the specified process is elevated.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+pub fn is_elevated<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> bool {
+    let Ok(token_handle) = open_token::<TOKEN_QUERY, R>(handle) else {
+        return false;
+    };
 
-    security::get_token_information::<TOKEN_ELEVATION>(token_handle)
+    security::get_token_information::<TOKEN_ELEVATION, TOKEN_QUERY>(&token_handle)
         .map_or(false, |info| info.TokenIsElevated > 0)
 }
 
@@ -191,7 +414,10 @@ pub fn is_elevated(handle: isize) -> bool {
 ///
 /// # Remarks
 ///
-/// * The returned full name is trimmed by [`trim_wide_end`][`crate::common::trim_wide_end`] which means it is not null-terminated.
+/// * The buffer starts with [`MAX_PATH`] characters and it is grown until the full name fits or the buffer reaches the maximum length of a long path (32767 characters).
+/// * The returned full name is not null-terminated.
+/// * Native paths (e.g. `\Device\HarddiskVolume3\Windows\notepad.exe`) can be translated to Win32 paths
+///   using the [`DeviceMap`][`crate::common::path::DeviceMap`] returned by [`get_device_map`][`crate::win32::storage::file_system::get_device_map`].
 ///
 /// # Errors
 ///
@@ -199,8 +425,8 @@ pub fn is_elevated(handle: isize) -> bool {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -209,19 +435,19 @@ pub fn is_elevated(handle: isize) -> bool {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-queryfullprocessimagenamew
 ///
-pub fn get_full_image_name(handle: isize, use_win32_path_format: bool) -> Result<U16String> {
-    let mut buffer_size = MAX_CHARS_IN_LONG_PATH as u32;
-    let mut buffer = [0; MAX_CHARS_IN_LONG_PATH];
-    call_BOOL! {
-        QueryFullProcessImageNameW(
+pub fn get_full_image_name<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    use_win32_path_format: bool,
+) -> Result<U16String> {
+    let policy = BufferPolicy::new(MAX_PATH as usize, MAX_CHARS_IN_LONG_PATH);
+    let buffer = negotiate(policy, |buffer| {
+        Fill::written(get_full_image_name_with_buffer(
             handle,
-            if use_win32_path_format { PROCESS_NAME_WIN32 } else { PROCESS_NAME_NATIVE },
-            buffer.as_mut_ptr(),
-            &mut buffer_size) return Error
-    };
-
-    // Safety: `buffer` is valid `u16` array with a length of `MAX_CHARS_IN_LONG_PATH`.
-    Ok(unsafe { pcwstr_to_u16_string(buffer.as_ptr(), buffer.len(), true) })
+            buffer,
+            use_win32_path_format,
+        ))
+    })?;
+    Ok(U16String::from_vec(buffer))
 }
 
 /// Gets the full name of the executable image for the specified process.
@@ -233,9 +459,9 @@ pub fn get_full_image_name(handle: isize, use_win32_path_format: bool) -> Result
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
-/// * `buffer` is too small. ([`ERROR_INSUFFICIENT_BUFFER`])
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `buffer` is too small. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
 ///
 ///
 /// # Examples
@@ -245,19 +471,22 @@ pub fn get_full_image_name(handle: isize, use_win32_path_format: bool) -> Result
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-queryfullprocessimagenamew
 ///
-pub fn get_full_image_name_with_buffer(
-    handle: isize,
+pub fn get_full_image_name_with_buffer<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
     buffer: &mut [u16],
     use_win32_path_format: bool,
 ) -> Result<usize> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     let mut buffer_size = buffer.len() as u32;
     call_BOOL! {
         QueryFullProcessImageNameW(
-            handle,
+            handle.as_raw(),
             if use_win32_path_format { PROCESS_NAME_WIN32 } else { PROCESS_NAME_NATIVE },
             buffer.as_mut_ptr(),
-            &mut buffer_size) return Error
-    };
+            &mut buffer_size
+        )
+    }
+    .with_handle(handle.as_raw())?;
     Ok(buffer_size as usize)
 }
 
@@ -269,8 +498,8 @@ pub fn get_full_image_name_with_buffer(
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -279,10 +508,13 @@ pub fn get_full_image_name_with_buffer(
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-getprocessaffinitymask
 ///
-pub fn get_affinity_mask(handle: isize) -> Result<(usize, usize)> {
+pub fn get_affinity_mask<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<(usize, usize)> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     call_BOOL! {
         GetProcessAffinityMask(
-            handle,
+            handle.as_raw(),
             &mut process_mask,
             &mut system_mask) -> mut (process_mask, system_mask): (usize, usize)
     }
@@ -297,8 +529,8 @@ pub fn get_affinity_mask(handle: isize) -> Result<(usize, usize)> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -307,22 +539,27 @@ pub fn get_affinity_mask(handle: isize) -> Result<(usize, usize)> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocessdefaultcpusets
 ///
-pub fn get_default_cpu_sets(handle: isize) -> Result<Option<Box<[u32]>>> {
-    let mut count = 0;
-    call_BOOL! { GetProcessDefaultCpuSets(handle, ptr::null_mut(), count, &mut count) return Error };
-    if count == 0 {
-        return Ok(None);
-    }
-
-    let mut buffer = vec![0_u32; count as usize];
-    call_BOOL! {
-        GetProcessDefaultCpuSets(
-            handle,
-            buffer.as_mut_ptr(),
-            buffer.len() as u32,
-            &mut count) return Error
-    };
-    Ok(Some(buffer.into()))
+pub fn get_default_cpu_sets<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<Option<Box<[u32]>>> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    let policy = BufferPolicy::new(0, u32::MAX as usize);
+    let buffer = negotiate(policy, |buffer| {
+        let mut count = 0;
+        let result = call_BOOL! {
+            GetProcessDefaultCpuSets(
+                handle.as_raw(),
+                if buffer.is_empty() { ptr::null_mut() } else { buffer.as_mut_ptr() },
+                buffer.len() as u32,
+                &mut count)
+        };
+        Fill::required(result, count as usize)
+    })?;
+    Ok(if buffer.is_empty() {
+        None
+    } else {
+        Some(buffer.into_boxed_slice())
+    })
 }
 
 /// Determines whether the specified process is running.
@@ -333,8 +570,8 @@ pub fn get_default_cpu_sets(handle: isize) -> Result<Option<Box<[u32]>>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -343,9 +580,11 @@ pub fn get_default_cpu_sets(handle: isize) -> Result<Option<Box<[u32]>>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getexitcodeprocess
 ///
-pub fn is_running(handle: isize) -> Result<bool> {
+pub fn is_running<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<bool> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     let mut exit_code = 0;
-    call_BOOL!(GetExitCodeProcess(handle, &mut exit_code) return Error);
+    call_BOOL! { GetExitCodeProcess(handle.as_raw(), &mut exit_code) }
+        .with_handle(handle.as_raw())?;
     Ok(exit_code == STILL_ACTIVE as u32)
 }
 
@@ -357,8 +596,8 @@ pub fn is_running(handle: isize) -> Result<bool> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -367,9 +606,12 @@ pub fn is_running(handle: isize) -> Result<bool> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getexitcodeprocess
 ///
-pub fn get_exit_code(handle: isize) -> Result<Option<u32>> {
+pub fn get_exit_code<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<Option<u32>> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     let mut exit_code = 0;
-    call_BOOL! { GetExitCodeProcess(handle, &mut exit_code) return Error };
+    call_BOOL! { GetExitCodeProcess(handle.as_raw(), &mut exit_code) return Error };
     if exit_code != STILL_ACTIVE as u32 {
         Ok(Some(exit_code))
     } else {
@@ -385,9 +627,8 @@ pub fn get_exit_code(handle: isize) -> Result<Option<u32>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
-/// * 'buffer' is too small.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -396,23 +637,44 @@ pub fn get_exit_code(handle: isize) -> Result<Option<u32>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processtopologyapi/nf-processtopologyapi-getprocessgroupaffinity
 ///
-pub fn get_group_affinity(handle: isize) -> Result<Box<[u16]>> {
-    let mut count = 0;
-    call_BOOL! {
-        GetProcessGroupAffinity(
-            handle,
-            &mut count,
-            ptr::null_mut()) ->
-            if Error == ERROR_INSUFFICIENT_BUFFER return;
-            else return [].into();
-    };
-    call_BOOL! { GetProcessGroupAffinity(
-        handle,
-        &mut count,
-        buffer.as_mut_ptr()) -> From {
-             mut buffer = vec![0; count as usize];
-        }
-    }
+#[inline]
+pub fn get_group_affinity<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<Box<[u16]>> {
+    get_group_affinity_in(&Native, handle)
+}
+
+/// Gets the processor group affinity of the specified process by using the specified [`Backend`].
+/// See [`get_group_affinity`].
+///
+/// # Remarks
+///
+/// * The buffer is reallocated if the number of processor groups changes between the calls.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+/// TODO
+///
+pub fn get_group_affinity_in<B: Backend, const R: ProcessAccessRights>(
+    backend: &B,
+    handle: &ProcessHandle<R>,
+) -> Result<Box<[u16]>> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    let policy = BufferPolicy::new(0, u16::MAX as usize);
+    negotiate(policy, |buffer| {
+        let mut count = 0;
+        let result = backend.get_process_group_affinity(handle, buffer, &mut count);
+        Fill::required(result, count as usize)
+    })
+    .map(Vec::into_boxed_slice)
 }
 
 /// Returns the number of processor group numbers put into `buffer` array, that are part of the proccessor group affinity of the specified process.
@@ -423,9 +685,9 @@ pub fn get_group_affinity(handle: isize) -> Result<Box<[u16]>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
-/// * `buffer` is too small. ([`ERROR_INSUFFICIENT_BUFFER`])
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `buffer` is too small. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
 ///
 /// # Examples
 /// TODO
@@ -434,13 +696,14 @@ pub fn get_group_affinity(handle: isize) -> Result<Box<[u16]>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processtopologyapi/nf-processtopologyapi-getprocessgroupaffinity
 ///
-pub fn get_group_affinity_with_buffer(handle: isize, buffer: &mut [u16]) -> Result<u16> {
-    call_BOOL! {
-        GetProcessGroupAffinity(
-            handle,
-            &mut count,
-            buffer.as_mut_ptr()) -> mut count = buffer.len() as u16
-    }
+pub fn get_group_affinity_with_buffer<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    buffer: &mut [u16],
+) -> Result<u16> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    let mut count = 0;
+    Native.get_process_group_affinity(handle, buffer, &mut count)?;
+    Ok(count)
 }
 
 /// Gets the number of open handles that belong to the specified process.
@@ -451,8 +714,8 @@ pub fn get_group_affinity_with_buffer(handle: isize, buffer: &mut [u16]) -> Resu
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -461,8 +724,9 @@ pub fn get_group_affinity_with_buffer(handle: isize, buffer: &mut [u16]) -> Resu
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocesshandlecount
 ///
-pub fn get_handle_count(handle: isize) -> Result<u32> {
-    call_BOOL! { GetProcessHandleCount(handle, &mut count) -> mut count: u32 }
+pub fn get_handle_count<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<u32> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    call_BOOL! { GetProcessHandleCount(handle.as_raw(), &mut count) -> mut count: u32 }
 }
 
 /// Determines whether the specified process is considered critical.
@@ -473,8 +737,8 @@ pub fn get_handle_count(handle: isize) -> Result<u32> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -483,8 +747,9 @@ pub fn get_handle_count(handle: isize) -> Result<u32> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-isprocesscritical
 ///
-pub fn is_critical(handle: isize) -> Result<bool> {
-    call_BOOL! { IsProcessCritical(handle, &mut is_critical) -> mut is_critical }
+pub fn is_critical<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<bool> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    call_BOOL! { IsProcessCritical(handle.as_raw(), &mut is_critical) -> mut is_critical }
 }
 
 /// Gets the process identifier of the specified process.
@@ -495,8 +760,8 @@ pub fn is_critical(handle: isize) -> Result<bool> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -505,8 +770,9 @@ pub fn is_critical(handle: isize) -> Result<bool> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocessid
 ///
-pub fn get_id(handle: isize) -> Result<u32> {
-    call_num! { GetProcessId(handle) != 0 }
+pub fn get_id<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<u32> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    call_num! { GetProcessId(handle.as_raw()) != 0 }
 }
 
 /// Gets the information of all I/O operations performed by the specified process.
@@ -517,8 +783,8 @@ pub fn get_id(handle: isize) -> Result<u32> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -527,8 +793,11 @@ pub fn get_id(handle: isize) -> Result<u32> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-getprocessiocounters
 ///
-pub fn get_io_counters(handle: isize) -> Result<IO_COUNTERS> {
-    call_BOOL! { GetProcessIoCounters(handle, &mut counters) -> mut counters: IO_COUNTERS }
+pub fn get_io_counters<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<IO_COUNTERS> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    call_BOOL! { GetProcessIoCounters(handle.as_raw(), &mut counters) -> mut counters: IO_COUNTERS }
 }
 
 /// Returns whether the specified proces has priority boost enabled.
@@ -539,8 +808,8 @@ pub fn get_io_counters(handle: isize) -> Result<IO_COUNTERS> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -549,8 +818,9 @@ pub fn get_io_counters(handle: isize) -> Result<IO_COUNTERS> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocesspriorityboost
 ///
-pub fn has_priority_boost(handle: isize) -> Result<bool> {
-    call_BOOL! { GetProcessPriorityBoost(handle, &mut disabled) -> mut !disabled }
+pub fn has_priority_boost<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<bool> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    call_BOOL! { GetProcessPriorityBoost(handle.as_raw(), &mut disabled) -> mut !disabled }
 }
 
 /// Gets the priority class for the specified process.
@@ -561,8 +831,8 @@ pub fn has_priority_boost(handle: isize) -> Result<bool> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -571,8 +841,11 @@ pub fn has_priority_boost(handle: isize) -> Result<bool> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getpriorityclass
 ///
-pub fn get_priority_class(handle: isize) -> Result<PROCESS_CREATION_FLAGS> {
-    call_num! { GetPriorityClass(handle) != 0 as PROCESS_CREATION_FLAGS }
+pub fn get_priority_class<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<PROCESS_CREATION_FLAGS> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
+    call_num! { GetPriorityClass(handle.as_raw()) != 0 as PROCESS_CREATION_FLAGS }
 }
 
 /// Gets the timing information for the specified process.
@@ -592,8 +865,8 @@ pub fn get_priority_class(handle: isize) -> Result<PROCESS_CREATION_FLAGS> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -602,10 +875,11 @@ pub fn get_priority_class(handle: isize) -> Result<PROCESS_CREATION_FLAGS> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocesstimes
 ///
-pub fn get_times(handle: isize) -> Result<[FILETIME; 4]> {
+pub fn get_times<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<[FILETIME; 4]> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     call_BOOL! {
         GetProcessTimes(
-            handle,
+            handle.as_raw(),
             &mut times[0],
             &mut times[1],
             &mut times[2],
@@ -623,7 +897,7 @@ pub fn get_times(handle: isize) -> Result<[FILETIME; 4]> {
 ///
 /// ## Possible errors
 ///
-/// * `pid` is invalid.
+/// * `pid` is invalid. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
 ///
 /// # Examples
 /// TODO
@@ -644,8 +918,8 @@ pub fn get_version(pid: u32) -> Result<(u16, u16)> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -654,10 +928,13 @@ pub fn get_version(pid: u32) -> Result<(u16, u16)> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-getprocessworkingsetsize
 ///
-pub fn get_working_set_size(handle: isize) -> Result<(usize, usize)> {
+pub fn get_working_set_size<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<(usize, usize)> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     call_BOOL! {
         GetProcessWorkingSetSize(
-            handle,
+            handle.as_raw(),
             &mut min_size,
             &mut max_size) -> mut (min_size, max_size): (usize, usize)
     }
@@ -671,8 +948,8 @@ pub fn get_working_set_size(handle: isize) -> Result<(usize, usize)> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_TERMINATE`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_TERMINATE`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -681,8 +958,12 @@ pub fn get_working_set_size(handle: isize) -> Result<(usize, usize)> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-terminateprocess
 ///
-pub fn terminate(handle: isize, exit_code: u32) -> Result<()> {
-    call_BOOL! { TerminateProcess(handle, exit_code) }
+pub fn terminate<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    exit_code: u32,
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_TERMINATE>();
+    call_BOOL! { TerminateProcess(handle.as_raw(), exit_code) }
 }
 
 /// A member of the [`PROCESS_INFORMATION_CLASS`] enumeration.
@@ -734,8 +1015,8 @@ impl ProcessInformation for PROCESS_LEAP_SECOND_INFO {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -744,10 +1025,13 @@ impl ProcessInformation for PROCESS_LEAP_SECOND_INFO {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocessinformation
 ///
-pub fn get_information<T: Copy + ProcessInformation>(handle: isize) -> Result<T> {
+pub fn get_information<T: Copy + ProcessInformation, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<T> {
+    let handle = handle.require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>();
     call_BOOL! {
         GetProcessInformation(
-            handle,
+            handle.as_raw(),
             T::information_class(),
             addr_of_mut!(information).cast::<c_void>(),
             size_of::<T>() as u32
@@ -763,8 +1047,8 @@ pub fn get_information<T: Copy + ProcessInformation>(handle: isize) -> Result<T>
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -773,10 +1057,14 @@ pub fn get_information<T: Copy + ProcessInformation>(handle: isize) -> Result<T>
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setprocessinformation
 ///
-pub fn set_information<T: Copy + ProcessInformation>(handle: isize, information: T) -> Result<()> {
+pub fn set_information<T: Copy + ProcessInformation, const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    information: T,
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_INFORMATION>();
     call_BOOL! {
         SetProcessInformation(
-            handle,
+            handle.as_raw(),
             T::information_class(),
             addr_of!(information).cast::<c_void>(),
             size_of::<T>() as u32)
@@ -791,8 +1079,8 @@ pub fn set_information<T: Copy + ProcessInformation>(handle: isize, information:
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -801,8 +1089,12 @@ pub fn set_information<T: Copy + ProcessInformation>(handle: isize, information:
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setpriorityclass
 ///
-pub fn set_priority_class(handle: isize, class: PROCESS_CREATION_FLAGS) -> Result<()> {
-    call_BOOL! { SetPriorityClass(handle, class) }
+pub fn set_priority_class<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    class: PROCESS_CREATION_FLAGS,
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_INFORMATION>();
+    call_BOOL! { SetPriorityClass(handle.as_raw(), class) }
 }
 
 /// Enables the affinity update mode of the current process if `enable_auto_update` is `true`. If `enable_auto_update` is `false`, then disables it.
@@ -838,8 +1130,8 @@ pub fn set_current_affinity_update_mode(enable_auto_update: bool) -> Result<()>
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -848,8 +1140,12 @@ pub fn set_current_affinity_update_mode(enable_auto_update: bool) -> Result<()>
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setprocesspriorityboost
 ///
-pub fn set_priority_boost(handle: isize, enable: bool) -> Result<()> {
-    call_BOOL! { SetProcessPriorityBoost(handle, to_BOOL!(!enable)) }
+pub fn set_priority_boost<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    enable: bool,
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_INFORMATION>();
+    call_BOOL! { SetProcessPriorityBoost(handle.as_raw(), to_BOOL!(!enable)) }
 }
 
 /// Sets `mask` as the processor affinity mask for the threads of the specified process.
@@ -860,8 +1156,8 @@ pub fn set_priority_boost(handle: isize, enable: bool) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -870,8 +1166,12 @@ pub fn set_priority_boost(handle: isize, enable: bool) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-setprocessaffinitymask
 ///
-pub fn set_affinity_mask(handle: isize, mask: usize) -> Result<()> {
-    call_BOOL! { SetProcessAffinityMask(handle, mask) }
+pub fn set_affinity_mask<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    mask: usize,
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_INFORMATION>();
+    call_BOOL! { SetProcessAffinityMask(handle.as_raw(), mask) }
 }
 
 /// Sets `min_size` as the minimum and `max_size` as the maximum working set size for the specified process.
@@ -882,8 +1182,8 @@ pub fn set_affinity_mask(handle: isize, mask: usize) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_QUOTA`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_QUOTA`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -892,8 +1192,13 @@ pub fn set_affinity_mask(handle: isize, mask: usize) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-setprocessworkingsetsize
 ///
-pub fn set_working_set_size(handle: isize, min_size: usize, max_size: usize) -> Result<()> {
-    call_BOOL! { SetProcessWorkingSetSize(handle, min_size, max_size) }
+pub fn set_working_set_size<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    min_size: usize,
+    max_size: usize,
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_QUOTA>();
+    call_BOOL! { SetProcessWorkingSetSize(handle.as_raw(), min_size, max_size) }
 }
 
 /// Removes as many pages as possible from the working set of the specified process.
@@ -904,8 +1209,8 @@ pub fn set_working_set_size(handle: isize, min_size: usize, max_size: usize) ->
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_QUOTA`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_QUOTA`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -914,9 +1219,10 @@ pub fn set_working_set_size(handle: isize, min_size: usize, max_size: usize) ->
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/memoryapi/nf-memoryapi-setprocessworkingsetsize
 ///
-pub fn shrink_working_set(handle: isize) -> Result<()> {
+pub fn shrink_working_set<const R: ProcessAccessRights>(handle: &ProcessHandle<R>) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_QUOTA>();
     call_BOOL! {
-        SetProcessWorkingSetSize(handle, usize::MAX, usize::MAX)
+        SetProcessWorkingSetSize(handle.as_raw(), usize::MAX, usize::MAX)
     }
 }
 
@@ -928,8 +1234,8 @@ pub fn shrink_working_set(handle: isize) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// ## Examples
 /// TODO
@@ -938,8 +1244,12 @@ pub fn shrink_working_set(handle: isize) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setprocessdefaultcpusets
 ///
-pub fn set_default_cpu_sets(handle: isize, cpu_sets: &[u32]) -> Result<()> {
-    call_BOOL! { SetProcessDefaultCpuSets(handle, cpu_sets.as_ptr(), cpu_sets.len() as u32) }
+pub fn set_default_cpu_sets<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    cpu_sets: &[u32],
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_LIMITED_INFORMATION>();
+    call_BOOL! { SetProcessDefaultCpuSets(handle.as_raw(), cpu_sets.as_ptr(), cpu_sets.len() as u32) }
 }
 
 /// Clears the default CPU Sets for the specified process.
@@ -950,19 +1260,27 @@ pub fn set_default_cpu_sets(handle: isize, cpu_sets: &[u32]) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`PROCESS_SET_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_SET_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// For more information see the official [documentation].
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setprocessdefaultcpusets
 ///
-pub fn clear_default_cpu_sets(handle: isize) -> Result<()> {
-    call_BOOL! { SetProcessDefaultCpuSets(handle, ptr::null(), 0) }
+pub fn clear_default_cpu_sets<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<()> {
+    let handle = handle.require_rights::<PROCESS_SET_LIMITED_INFORMATION>();
+    call_BOOL! { SetProcessDefaultCpuSets(handle.as_raw(), ptr::null(), 0) }
 }
 
 /// The current thread waits until the specified process has finished processing its initial input
-/// and is waiting for user input with no input pending, or until `timeout_ms` interval has elapsed.
+/// and is waiting for user input with no input pending, or until `timeout` elapses.
+///
+/// # Remarks
+///
+/// * `timeout` is converted using [`wait::timeout_to_ms`]. [`None`] waits indefinitely.
+/// * The result is [`WaitOutcome::Signaled`] if the process is idle, or [`WaitOutcome::Timeout`] if `timeout` elapsed.
 ///
 /// # Errors
 ///
@@ -970,8 +1288,7 @@ pub fn clear_default_cpu_sets(handle: isize) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * The wait was terminated, because the time-out interval elapsed. ([`WAIT_TIMEOUT`][`windows_sys::Win32::Foundation::WAIT_TIMEOUT`])
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
 ///
 /// # Examples
 ///
@@ -981,6 +1298,36 @@ pub fn clear_default_cpu_sets(handle: isize) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-waitforinputidle
 ///
-pub fn wait_for_input_idle(handle: isize, timeout_ms: u32) -> Result<()> {
-    call_num! { WaitForInputIdle(handle, timeout_ms) == 0 }
+pub fn wait_for_input_idle<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+    timeout: Option<Duration>,
+) -> Result<WaitOutcome> {
+    let handle = handle.as_handle();
+    // Safety: `handle` is a valid process handle for the duration of the call.
+    match unsafe { WaitForInputIdle(handle.as_raw(), wait::timeout_to_ms(timeout)) } {
+        0 => Ok(WaitOutcome::Signaled(0)),
+        WAIT_TIMEOUT => Ok(WaitOutcome::Timeout),
+        _ => Err(Win32Error::get_last_from("WaitForInputIdle")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::win32::backend::mock::{fake_handle, ScriptedBackend, Step};
+    use alloc::vec;
+
+    #[test]
+    fn get_group_affinity_retries_with_the_required_len() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe { fake_handle::<ProcessObject, PROCESS_QUERY_LIMITED_INFORMATION>() };
+        let backend = ScriptedBackend::new()
+            .then(Step::GetProcessGroupAffinity(Ok(vec![0, 1])))
+            .then(Step::GetProcessGroupAffinity(Ok(vec![0, 1])));
+
+        let groups = get_group_affinity_in(&backend, &handle).unwrap();
+
+        assert_eq!(&*groups, &[0, 1]);
+        assert!(backend.is_finished());
+    }
 }
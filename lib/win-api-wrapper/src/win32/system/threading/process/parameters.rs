@@ -0,0 +1,434 @@
+use crate::common::buffer::{negotiate, BufferPolicy, Fill};
+use crate::common::pod::{self, Pod};
+use crate::win32::core::{NtStatus, Result, Win32Error};
+use crate::win32::system::diagnostics::debug::process_memory::{self, RemotePtr};
+use crate::win32::system::environment::EnvironmentBlock;
+use crate::win32::system::threading::process::{
+    ProcessAccessRights, ProcessHandle, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem::{size_of, size_of_val};
+use core::ptr::addr_of_mut;
+use widestring::U16String;
+use windows_sys::Win32::Foundation::{
+    ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_DATA, HANDLE, NTSTATUS, STATUS_BUFFER_OVERFLOW,
+    STATUS_BUFFER_TOO_SMALL, STATUS_INFO_LENGTH_MISMATCH,
+};
+use windows_sys::Win32::System::Threading::{
+    NtQueryInformationProcess, ProcessBasicInformation, ProcessCommandLineInformation,
+    ProcessWow64Information, PROCESSINFOCLASS,
+};
+#[cfg(target_pointer_width = "32")]
+use windows_sys::Win32::{Foundation::ERROR_NOT_SUPPORTED, System::Threading::GetCurrentProcess};
+
+/// The maximum number of characters in an environment block read by [`get_environment`].
+pub const MAX_ENVIRONMENT_BLOCK_LEN: usize = 16 * 1024 * 1024;
+
+/// The number of characters of the environment block read at once by [`get_environment`].
+const ENVIRONMENT_CHUNK_LEN: usize = 4096;
+
+/// The initial length of the buffer used by [`get_command_line`], in `usize` elements.
+const INITIAL_COMMAND_LINE_BUFFER_LEN: usize = 512;
+
+/// The maximum length of the buffer used by [`get_command_line`], in `usize` elements.
+/// It fits a `UNICODE_STRING` followed by the longest string it can describe.
+const MAX_COMMAND_LINE_BUFFER_LEN: usize =
+    (size_of::<UnicodeString>() + u16::MAX as usize).div_ceil(size_of::<usize>());
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+/// `PROCESS_BASIC_INFORMATION` with the pointers stored as addresses.
+struct BasicInformation {
+    /// `ExitStatus` and the padding after it.
+    exit_status: usize,
+    /// `PebBaseAddress`.
+    peb_base_address: usize,
+    /// `AffinityMask`, `BasePriority`, `UniqueProcessId` and `InheritedFromUniqueProcessId`.
+    reserved: [usize; 4],
+}
+
+// Safety: Every field is an integer.
+unsafe impl Pod for BasicInformation {}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+/// `UNICODE_STRING` of the calling process with the buffer stored as an address.
+struct UnicodeString {
+    /// `Length` in bytes.
+    length: u16,
+    /// `MaximumLength` in bytes.
+    maximum_length: u16,
+    /// `Buffer`.
+    buffer: usize,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+/// `UNICODE_STRING` of a 32-bit process.
+struct UnicodeString32 {
+    /// `Length` in bytes.
+    length: u16,
+    /// `MaximumLength` in bytes.
+    maximum_length: u16,
+    /// `Buffer`.
+    buffer: u32,
+}
+
+// Safety: Every field is an integer.
+unsafe impl Pod for UnicodeString32 {}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+/// `UNICODE_STRING` of a 64-bit process.
+struct UnicodeString64 {
+    /// `Length` in bytes.
+    length: u16,
+    /// `MaximumLength` in bytes.
+    maximum_length: u16,
+    /// The padding before `Buffer`.
+    padding: u32,
+    /// `Buffer`.
+    buffer: u64,
+}
+
+// Safety: Every field is an integer.
+unsafe impl Pod for UnicodeString64 {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The bitness of the `PEB` and `RTL_USER_PROCESS_PARAMETERS` structures of a process.
+enum Layout {
+    /// The structures of a 32-bit process, including WOW64 processes.
+    Bits32,
+    /// The structures of a 64-bit process.
+    Bits64,
+}
+
+impl Layout {
+    /// The offset of `PEB::ProcessParameters`.
+    const fn process_parameters_offset(self) -> usize {
+        match self {
+            Self::Bits32 => 0x10,
+            Self::Bits64 => 0x20,
+        }
+    }
+
+    /// The offset of `RTL_USER_PROCESS_PARAMETERS::CurrentDirectory::DosPath`.
+    const fn current_directory_offset(self) -> usize {
+        match self {
+            Self::Bits32 => 0x24,
+            Self::Bits64 => 0x38,
+        }
+    }
+
+    /// The offset of `RTL_USER_PROCESS_PARAMETERS::Environment`.
+    const fn environment_offset(self) -> usize {
+        match self {
+            Self::Bits32 => 0x48,
+            Self::Bits64 => 0x80,
+        }
+    }
+
+    /// Reads the pointer at `address` in the specified process.
+    fn read_pointer<const R: ProcessAccessRights>(
+        self,
+        handle: &ProcessHandle<R>,
+        address: usize,
+    ) -> Result<usize> {
+        match self {
+            Self::Bits32 => {
+                Ok(process_memory::read_value(handle, RemotePtr::<u32>::new(address))? as usize)
+            }
+            // The 64-bit layout is only used by 64-bit callers, so the address fits into `usize`.
+            Self::Bits64 => {
+                Ok(process_memory::read_value(handle, RemotePtr::<u64>::new(address))? as usize)
+            }
+        }
+    }
+
+    /// Reads the `UNICODE_STRING` at `address` and the characters it describes in the specified process.
+    fn read_unicode_string<const R: ProcessAccessRights>(
+        self,
+        handle: &ProcessHandle<R>,
+        address: usize,
+    ) -> Result<U16String> {
+        let (length, buffer) = match self {
+            Self::Bits32 => {
+                let string: UnicodeString32 =
+                    process_memory::read_value(handle, RemotePtr::new(address))?;
+                (string.length, string.buffer as usize)
+            }
+            Self::Bits64 => {
+                let string: UnicodeString64 =
+                    process_memory::read_value(handle, RemotePtr::new(address))?;
+                (string.length, string.buffer as usize)
+            }
+        };
+
+        let mut chars = vec![0; usize::from(length) / size_of::<u16>()];
+        process_memory::read(handle, RemotePtr::new(buffer), &mut chars)?;
+        Ok(U16String::from_vec(chars))
+    }
+}
+
+/// Calls `NtQueryInformationProcess` and returns the number of written or required bytes.
+///
+/// # Safety
+///
+/// `information` must be a valid buffer of `length` bytes for the specified class.
+///
+unsafe fn query_information(
+    handle: HANDLE,
+    class: PROCESSINFOCLASS,
+    information: *mut u8,
+    length: usize,
+) -> core::result::Result<usize, (NtStatus, usize)> {
+    let mut return_length = 0;
+    // Safety: The caller guarantees that `information` is valid for the class.
+    let status: NTSTATUS = unsafe {
+        NtQueryInformationProcess(
+            handle,
+            class,
+            information.cast(),
+            length as u32,
+            addr_of_mut!(return_length),
+        )
+    };
+    let status = NtStatus(status);
+    if status.is_failure() {
+        return Err((status, return_length as usize));
+    }
+
+    Ok(return_length as usize)
+}
+
+/// Calls `NtQueryInformationProcess` with a fixed-size `T` and converts failures to a [`Win32Error`].
+fn query_value<T: Pod>(handle: HANDLE, class: PROCESSINFOCLASS) -> Result<T> {
+    let mut value = pod::zeroed::<T>();
+    // Safety: `value` is a valid buffer of its size.
+    unsafe { query_information(handle, class, addr_of_mut!(value).cast(), size_of::<T>()) }
+        .map_err(|(status, _)| status.to_win32().with_function("NtQueryInformationProcess"))?;
+    Ok(value)
+}
+
+/// Finds the `RTL_USER_PROCESS_PARAMETERS` of the specified process and the layout of its structures.
+///
+/// # Remarks
+///
+/// * The structures of a WOW64 process are read from its 32-bit `PEB`, which is the one its code uses.
+///
+fn locate_process_parameters<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<(usize, Layout)> {
+    let raw_handle = handle
+        .require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>()
+        .as_raw();
+
+    let wow64_peb: usize = query_value(raw_handle, ProcessWow64Information)?;
+    let (peb, layout) = if wow64_peb != 0 {
+        (wow64_peb, Layout::Bits32)
+    } else {
+        #[cfg(target_pointer_width = "32")]
+        {
+            // A WOW64 caller can't read the 64-bit address space of a native process with `ReadProcessMemory`.
+            // Safety: The pseudo handle of the current process is always valid.
+            let current_process = unsafe { GetCurrentProcess() };
+            let current_wow64_peb: usize = query_value(current_process, ProcessWow64Information)?;
+            if current_wow64_peb != 0 {
+                return Err(Win32Error::new(ERROR_NOT_SUPPORTED));
+            }
+        }
+
+        let information: BasicInformation = query_value(raw_handle, ProcessBasicInformation)?;
+        let layout = if cfg!(target_pointer_width = "64") {
+            Layout::Bits64
+        } else {
+            Layout::Bits32
+        };
+        (information.peb_base_address, layout)
+    };
+
+    let parameters = layout.read_pointer(handle, peb + layout.process_parameters_offset())?;
+    Ok((parameters, layout))
+}
+
+/// Gets the command line of the specified process.
+///
+/// # Remarks
+///
+/// * The command line is queried with `ProcessCommandLineInformation`, so it's available even without [`PROCESS_VM_READ`] access right.
+/// * The result is the command line the process was started with, even if the process modified its own copy later.
+/// * The returned command line is not null-terminated.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_INFORMATION`][`crate::win32::system::threading::process::PROCESS_QUERY_INFORMATION`] or [`PROCESS_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * The process has exited. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winternl/nf-winternl-ntqueryinformationprocess
+///
+pub fn get_command_line<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<U16String> {
+    let raw_handle = handle
+        .require_rights::<PROCESS_QUERY_LIMITED_INFORMATION>()
+        .as_raw();
+    let policy = BufferPolicy::new(INITIAL_COMMAND_LINE_BUFFER_LEN, MAX_COMMAND_LINE_BUFFER_LEN);
+    let buffer = negotiate(policy, |buffer: &mut [usize]| {
+        // Safety: `buffer` is valid for writes of its whole size.
+        let result = unsafe {
+            query_information(
+                raw_handle,
+                ProcessCommandLineInformation,
+                buffer.as_mut_ptr().cast(),
+                size_of_val(buffer),
+            )
+        };
+        match result {
+            Ok(_) => Ok(Fill::Written(buffer.len())),
+            Err((status, required))
+                if matches!(
+                    status.0,
+                    STATUS_INFO_LENGTH_MISMATCH | STATUS_BUFFER_TOO_SMALL | STATUS_BUFFER_OVERFLOW
+                ) =>
+            {
+                let required = required.div_ceil(size_of::<usize>());
+                Ok(Fill::TooSmall(
+                    (required > buffer.len()).then_some(required),
+                ))
+            }
+            Err((status, _)) => Err(status.to_win32().with_function("NtQueryInformationProcess")),
+        }
+    })?;
+
+    // Safety: `buffer` is at least as large as a `UNICODE_STRING`, and every bit pattern of it is valid.
+    let string = unsafe { buffer.as_ptr().cast::<UnicodeString>().read() };
+    // An empty string might not point into `buffer` (e.g. a null `Buffer`).
+    if string.length == 0 {
+        return Ok(U16String::new());
+    }
+
+    // Safety: Every byte of `buffer` is initialized.
+    let bytes = unsafe {
+        core::slice::from_raw_parts(buffer.as_ptr().cast::<u8>(), size_of_val(buffer.as_slice()))
+    };
+    let chars = string
+        .buffer
+        .checked_sub(buffer.as_ptr() as usize)
+        .and_then(|start| bytes.get(start..start + usize::from(string.length)))
+        .ok_or_else(|| {
+            Win32Error::new(ERROR_INVALID_DATA).with_function("NtQueryInformationProcess")
+        })?;
+
+    Ok(chars
+        .chunks_exact(size_of::<u16>())
+        .map(|pair| u16::from_ne_bytes([pair[0], pair[1]]))
+        .collect::<Vec<_>>()
+        .into())
+}
+
+/// Gets the current directory of the specified process.
+///
+/// # Remarks
+///
+/// * The current directory is read from the `RTL_USER_PROCESS_PARAMETERS` of the process.
+///   For a WOW64 process the 32-bit structures are used.
+/// * The returned path ends with a backslash, and it's not null-terminated.
+/// * The process can change its current directory at any time, so the result might be outdated.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_LIMITED_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * The process has exited, or it's still initializing. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+/// * The caller is a 32-bit process and the target is a 64-bit process. ([`ErrorKind::Unsupported`][`crate::win32::core::ErrorKind::Unsupported`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winternl/ns-winternl-peb
+///
+pub fn get_current_directory<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<U16String> {
+    handle.require_rights::<{ PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ }>();
+    let (parameters, layout) = locate_process_parameters(handle)?;
+    layout.read_unicode_string(handle, parameters + layout.current_directory_offset())
+}
+
+/// Gets the environment variables of the specified process.
+///
+/// # Remarks
+///
+/// * The environment block is read from the `RTL_USER_PROCESS_PARAMETERS` of the process.
+///   For a WOW64 process the 32-bit structures are used.
+/// * The block is read in chunks until the empty string that ends it, or until the end of the readable memory.
+/// * The process can change its environment at any time, so the result might be outdated or incomplete.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`PROCESS_QUERY_LIMITED_INFORMATION`] and [`PROCESS_VM_READ`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * The process has exited, or it's still initializing. ([`ErrorKind::PartialCopy`][`crate::win32::core::ErrorKind::PartialCopy`])
+/// * The caller is a 32-bit process and the target is a 64-bit process. ([`ErrorKind::Unsupported`][`crate::win32::core::ErrorKind::Unsupported`])
+/// * The block is longer than [`MAX_ENVIRONMENT_BLOCK_LEN`]. ([`ErrorKind::InsufficientBuffer`][`crate::win32::core::ErrorKind::InsufficientBuffer`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winternl/ns-winternl-peb
+///
+pub fn get_environment<const R: ProcessAccessRights>(
+    handle: &ProcessHandle<R>,
+) -> Result<EnvironmentBlock> {
+    handle.require_rights::<{ PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ }>();
+    let (parameters, layout) = locate_process_parameters(handle)?;
+    let environment = layout.read_pointer(handle, parameters + layout.environment_offset())?;
+
+    let mut block: Vec<u16> = Vec::new();
+    loop {
+        let address = RemotePtr::<u16>::new(environment).wrapping_add(block.len());
+        let chunk = match process_memory::read_vec(handle, address, ENVIRONMENT_CHUNK_LEN) {
+            Ok(chunk) => chunk,
+            // The previous chunk ended at the end of the readable memory.
+            Err(_) if !block.is_empty() => break,
+            Err(error) => return Err(error),
+        };
+
+        // The terminating empty string might start in the previous chunk.
+        let search_start = block.len().saturating_sub(1);
+        let is_partial = chunk.len() < ENVIRONMENT_CHUNK_LEN;
+        block.extend_from_slice(&chunk);
+        if is_partial || block[search_start..].windows(2).any(|pair| pair == [0, 0]) {
+            break;
+        }
+
+        if block.len() >= MAX_ENVIRONMENT_BLOCK_LEN {
+            return Err(Win32Error::new(ERROR_INSUFFICIENT_BUFFER));
+        }
+    }
+
+    Ok(EnvironmentBlock::parse(&block))
+}
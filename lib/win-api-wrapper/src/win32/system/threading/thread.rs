@@ -1,14 +1,17 @@
 use super::super::kernel::PROCESSOR_NUMBER;
-use crate::common::{get_pcwstr_len, pcwstr_to_u16_string};
-use crate::win32::core::Result;
+use crate::common::buffer::{negotiate, BufferPolicy, Fill};
+use crate::common::wide::{self, IntoWideCStr, U16String};
+use crate::win32::backend::{Backend, Native};
+use crate::win32::core::{Error, HResult, Result, ResultExt};
+use crate::win32::foundation::{KernelObject, ObjectHandle, OwnedHandle};
 use crate::win32::system::memory::{get_local_handle, local_free};
 use crate::{call_BOOL, call_num, to_BOOL};
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::ffi::c_void;
 use core::mem::{size_of, zeroed};
 use core::ptr;
 use core::ptr::{addr_of, addr_of_mut};
-use widestring::{U16Str, U16String};
 use windows_sys::Win32::Foundation::STILL_ACTIVE;
 use windows_sys::Win32::System::Threading::{
     ExitThread, GetCurrentThread, GetCurrentThreadId, GetExitCodeThread, GetProcessIdOfThread,
@@ -36,22 +39,38 @@ pub use windows_sys::Win32::System::Threading::{
     THREAD_WRITE_OWNER,
 };
 
+#[derive(Debug)]
+/// The thread kernel object. See [`ThreadHandle`].
+pub struct ThreadObject;
+
+impl KernelObject for ThreadObject {
+    const IMPLIED_RIGHTS: &'static [(u32, u32)] = &[
+        (THREAD_QUERY_INFORMATION, THREAD_QUERY_LIMITED_INFORMATION),
+        (THREAD_SET_INFORMATION, THREAD_SET_LIMITED_INFORMATION),
+    ];
+}
+
+/// An owned thread handle that has the `RIGHTS` access rights. See [`open`].
+pub type ThreadHandle<const RIGHTS: ThreadAccessRights = THREAD_ALL_ACCESS> =
+    ObjectHandle<ThreadObject, RIGHTS>;
+
 #[allow(clippy::undocumented_unsafe_blocks)]
 /// Opens an existing thread object.
 ///
 /// # Arguments
 ///
+/// * `RIGHTS`: One or more [`access rights`][`ThreadAccessRights`] to the thread object
 /// * `id`: The thread identifier
-/// * `access`: One or more access rights to the thread object
 /// * `inherit_handle`: Specifies whether processes created by this thread should inherit it's handle
 ///
 /// # Result
 ///
-/// An open handle to the specified thread.
+/// An open handle to the specified thread that has the `RIGHTS` access rights.
 ///
 /// # Remarks
 ///
-/// * If the handle is not needed anymore close it using [`close_handle`](crate::win32::foundation::close_handle).
+/// * The returned handle is closed when it is dropped.
+/// * Passing the returned handle to a function that requires access rights which are not included in `RIGHTS` fails to compile.
 ///  
 /// # Errors
 ///
@@ -59,7 +78,7 @@ pub use windows_sys::Win32::System::Threading::{
 ///
 /// # Possible error
 ///
-/// * `id` is not a valid thread identifier.
+/// * `id` is not a valid thread identifier. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
 ///
 /// # Examples
 ///
@@ -69,8 +88,14 @@ pub use windows_sys::Win32::System::Threading::{
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openthread
 ///
-pub fn open(id: u32, access_rights: ThreadAccessRights, inherit_handle: bool) -> Result<isize> {
-    call_num! { OpenThread(access_rights, to_BOOL!(inherit_handle), id) != 0 }
+pub fn open<const RIGHTS: ThreadAccessRights>(
+    id: u32,
+    inherit_handle: bool,
+) -> Result<ThreadHandle<RIGHTS>> {
+    let handle =
+        call_num! { OpenThread(RIGHTS, to_BOOL!(inherit_handle), id) != 0 }.with_thread_id(id)?;
+    // Safety: `handle` is a newly opened thread handle that has the `RIGHTS` access rights and is not owned by anything else.
+    Ok(unsafe { ThreadHandle::from_owned(OwnedHandle::from_raw(handle)) })
 }
 
 #[allow(clippy::undocumented_unsafe_blocks)]
@@ -93,13 +118,14 @@ pub fn exit_current(exit_code: u32) {
 /// * The calling thread can use this handle to specify itself whenever a thread handle is required.
 /// * Pseudo handles are not inherited by child processes.
 /// * This handle has the [`THREAD_ALL_ACCESS`] access right to the thread object.
+/// * The returned handle is equivalent to [`BorrowedHandle::current_thread`][`crate::win32::foundation::BorrowedHandle::current_thread`].
 ///
 /// For more information see the official [documentation].
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getcurrentthread
 ///
-pub fn get_current_handle() -> isize {
-    unsafe { GetCurrentThread() }
+pub fn get_current_handle() -> ThreadHandle {
+    unsafe { ThreadHandle::from_owned(OwnedHandle::from_raw(GetCurrentThread())) }
 }
 
 #[allow(clippy::undocumented_unsafe_blocks)]
@@ -121,8 +147,8 @@ pub fn get_current_id() -> u32 {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -131,9 +157,10 @@ pub fn get_current_id() -> u32 {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getexitcodethread
 ///
-pub fn is_running(handle: isize) -> Result<bool> {
+pub fn is_running<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<bool> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
     let mut exit_code = 0;
-    call_BOOL!(GetExitCodeThread(handle, &mut exit_code) return Error);
+    call_BOOL!(GetExitCodeThread(handle.as_raw(), &mut exit_code) return Error);
     Ok(exit_code == STILL_ACTIVE as u32)
 }
 
@@ -145,8 +172,8 @@ pub fn is_running(handle: isize) -> Result<bool> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 /// TODO
@@ -155,9 +182,10 @@ pub fn is_running(handle: isize) -> Result<bool> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getexitcodethread
 ///
-pub fn get_exit_code(handle: isize) -> Result<Option<u32>> {
+pub fn get_exit_code<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<Option<u32>> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
     let mut exit_code = 0;
-    call_BOOL!(GetExitCodeThread(handle, &mut exit_code) return Error);
+    call_BOOL!(GetExitCodeThread(handle.as_raw(), &mut exit_code) return Error);
     if exit_code != STILL_ACTIVE as u32 {
         Ok(Some(exit_code))
     } else {
@@ -173,8 +201,8 @@ pub fn get_exit_code(handle: isize) -> Result<Option<u32>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -184,20 +212,21 @@ pub fn get_exit_code(handle: isize) -> Result<Option<u32>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocessidofthread
 ///
-pub fn get_process_id(handle: isize) -> Result<u32> {
-    call_num! { GetProcessIdOfThread(handle) != 0 }
+pub fn get_process_id<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<u32> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
+    call_num! { GetProcessIdOfThread(handle.as_raw()) != 0 }
 }
 
 /// Gets the description that was assigned to a thread by [`set_description`].
 ///
 /// # Errors
 ///
-/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+/// Returns an [`Error`] if the function fails.
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -208,15 +237,23 @@ pub fn get_process_id(handle: isize) -> Result<u32> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreaddescription
 ///
-pub fn get_description(handle: isize) -> Result<U16String> {
+pub fn get_description<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+) -> Result<U16String, Error> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
     let mut description_ptr = ptr::null_mut::<u16>();
-    call_num! { (GetThreadDescription(handle, addr_of_mut!(description_ptr).cast()) == 0) => return Error };
-    // Safety: `description_ptr` points to an element of a wide string that is null-terminated.
-    let len = unsafe { get_pcwstr_len(description_ptr) };
-    // Safety: `description_ptr` points to the first element of a wide string that is `len` + 1 long.
-    let description = unsafe { pcwstr_to_u16_string(description_ptr, len, false) };
-    let handle = get_local_handle(description_ptr.cast())?;
-    local_free(handle)?;
+    // Safety: `description_ptr` is a valid pointer to a `PWSTR`.
+    let result = HResult::new(unsafe {
+        GetThreadDescription(handle.as_raw(), addr_of_mut!(description_ptr))
+    });
+    if result.is_failure() {
+        return Err(result.into());
+    }
+
+    // Safety: `description_ptr` points to a system allocated null-terminated wide string that is freed after the copy.
+    let description = unsafe { wide::from_ptr(description_ptr) }.to_ustring();
+    let local_handle = get_local_handle(description_ptr.cast())?;
+    local_free(local_handle)?;
     Ok(description)
 }
 
@@ -228,8 +265,8 @@ pub fn get_description(handle: isize) -> Result<U16String> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -239,9 +276,10 @@ pub fn get_description(handle: isize) -> Result<U16String> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadiopendingflag
 ///
-pub fn is_io_pending(handle: isize) -> Result<bool> {
+pub fn is_io_pending<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<bool> {
+    let handle = handle.require_rights::<THREAD_QUERY_INFORMATION>();
     call_BOOL! {
-        GetThreadIOPendingFlag(handle, &mut is_io_pending) -> mut is_io_pending
+        GetThreadIOPendingFlag(handle.as_raw(), &mut is_io_pending) -> mut is_io_pending
     }
 }
 
@@ -288,8 +326,8 @@ impl ThreadInformation for MEMORY_PRIORITY_INFORMATION {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -299,10 +337,13 @@ impl ThreadInformation for MEMORY_PRIORITY_INFORMATION {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadinformation
 ///
-pub fn get_information<T: Copy + ThreadInformation>(handle: isize) -> Result<T> {
+pub fn get_information<T: Copy + ThreadInformation, const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+) -> Result<T> {
+    let handle = handle.require_rights::<THREAD_QUERY_INFORMATION>();
     call_BOOL! {
         GetThreadInformation(
-            handle,
+            handle.as_raw(),
             T::information_class(),
             addr_of_mut!(information).cast::<c_void>(),
             size_of::<T>() as u32
@@ -321,8 +362,8 @@ pub fn get_information<T: Copy + ThreadInformation>(handle: isize) -> Result<T>
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -332,8 +373,9 @@ pub fn get_information<T: Copy + ThreadInformation>(handle: isize) -> Result<T>
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadpriority
 ///
-pub fn get_priority(handle: isize) -> Result<i32> {
-    call_num! { GetThreadPriority(handle) != 0 }
+pub fn get_priority<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<i32> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
+    call_num! { GetThreadPriority(handle.as_raw()) != 0 }
 }
 
 /// Determines whether the specified thread has dynamic boosting enabled.
@@ -344,8 +386,8 @@ pub fn get_priority(handle: isize) -> Result<i32> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -355,9 +397,10 @@ pub fn get_priority(handle: isize) -> Result<i32> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadpriorityboost
 ///
-pub fn has_priority_boost(handle: isize) -> Result<bool> {
+pub fn has_priority_boost<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<bool> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
     call_BOOL! {
-        GetThreadPriorityBoost(handle, &mut is_disabled) -> mut !is_disabled
+        GetThreadPriorityBoost(handle.as_raw(), &mut is_disabled) -> mut !is_disabled
     }
 }
 
@@ -369,8 +412,8 @@ pub fn has_priority_boost(handle: isize) -> Result<bool> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -380,8 +423,11 @@ pub fn has_priority_boost(handle: isize) -> Result<bool> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadselectedcpusets
 ///
-pub fn get_selected_cpu_set_count(handle: isize) -> Result<u32> {
-    call_BOOL! { GetThreadSelectedCpuSets(handle, ptr::null_mut(), 0, &mut count) -> mut count: u32 }
+pub fn get_selected_cpu_set_count<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+) -> Result<u32> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
+    call_BOOL! { GetThreadSelectedCpuSets(handle.as_raw(), ptr::null_mut(), 0, &mut count) -> mut count: u32 }
 }
 
 /// Gets the explicit CPU Set assignment of the specified thread, if any assignment was set using [`set_selected_cpu_sets`].
@@ -395,8 +441,8 @@ pub fn get_selected_cpu_set_count(handle: isize) -> Result<u32> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -406,20 +452,45 @@ pub fn get_selected_cpu_set_count(handle: isize) -> Result<u32> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadselectedcpusets
 ///
-pub fn get_selected_cpu_sets(handle: isize) -> Result<Box<[u32]>> {
-    let mut count = 0;
-    call_BOOL! { GetThreadSelectedCpuSets(handle, ptr::null_mut(), count, &mut count) return Error};
-    if count == 0 {
-        return Ok(Box::from([]));
-    }
+#[inline]
+pub fn get_selected_cpu_sets<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+) -> Result<Box<[u32]>> {
+    get_selected_cpu_sets_in(&Native, handle)
+}
 
-    call_BOOL! {
-        GetThreadSelectedCpuSets(
-            handle,
-            buffer.as_mut_ptr(),
-            buffer.len() as u32,
-            &mut count) -> From { mut buffer = vec![0; count as usize]; }
-    }
+/// Gets the explicit CPU Set assignment of the specified thread by using the specified [`Backend`].
+/// See [`get_selected_cpu_sets`].
+///
+/// # Remarks
+/// * If no explicit assignment is set, the result is any empty boxed array slice.
+/// * The buffer is reallocated if the assignment changes between the calls.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+///
+/// # Examples
+///
+/// TODO
+///
+pub fn get_selected_cpu_sets_in<B: Backend, const R: ThreadAccessRights>(
+    backend: &B,
+    handle: &ThreadHandle<R>,
+) -> Result<Box<[u32]>> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
+    let policy = BufferPolicy::new(0, u32::MAX as usize);
+    negotiate(policy, |buffer| {
+        let mut count = 0;
+        let result = backend.get_thread_selected_cpu_sets(handle, buffer, &mut count);
+        Fill::required(result, count as usize)
+    })
+    .map(Vec::into_boxed_slice)
 }
 
 /// Decrements a thread's suspend count. When the suspend count is decremented to `0`, the execution of the thread is resumed.
@@ -430,8 +501,8 @@ pub fn get_selected_cpu_sets(handle: isize) -> Result<Box<[u32]>> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SUSPEND_RESUME`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SUSPEND_RESUME`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -441,8 +512,9 @@ pub fn get_selected_cpu_sets(handle: isize) -> Result<Box<[u32]>> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-resumethread
 ///
-pub fn resume(handle: isize) -> Result<u32> {
-    call_num! { ResumeThread(handle) != u32::MAX }
+pub fn resume<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<u32> {
+    let handle = handle.require_rights::<THREAD_SUSPEND_RESUME>();
+    call_num! { ResumeThread(handle.as_raw()) != u32::MAX }
 }
 
 /// Suspends the specified thread.
@@ -453,8 +525,8 @@ pub fn resume(handle: isize) -> Result<u32> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SUSPEND_RESUME`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SUSPEND_RESUME`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -464,8 +536,9 @@ pub fn resume(handle: isize) -> Result<u32> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-suspendthread
 ///
-pub fn suspend(handle: isize) -> Result<u32> {
-    call_num! { SuspendThread(handle) != u32::MAX }
+pub fn suspend<const R: ThreadAccessRights>(handle: &ThreadHandle<R>) -> Result<u32> {
+    let handle = handle.require_rights::<THREAD_SUSPEND_RESUME>();
+    call_num! { SuspendThread(handle.as_raw()) != u32::MAX }
 }
 
 /// Sets a processor affinity mask for the specified thread.
@@ -476,9 +549,9 @@ pub fn suspend(handle: isize) -> Result<u32> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] or [`THREAD_SET_LIMITED_INFORMATION`] and [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access rights.
-/// * `affinity_mask` requests a processor that is not selected for the process affinity mask. ([ERROR_INVALID_PARAMETER])
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] or [`THREAD_SET_LIMITED_INFORMATION`] and [`THREAD_QUERY_INFORMATION`] or [`THREAD_QUERY_LIMITED_INFORMATION`] access rights. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `affinity_mask` requests a processor that is not selected for the process affinity mask. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
 ///
 /// # Examples
 ///
@@ -487,22 +560,31 @@ pub fn suspend(handle: isize) -> Result<u32> {
 /// For more information see the official [documentation].
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-setthreadaffinitymask
-/// [ERROR_INVALID_PARAMETER]: `windows_sys::Win32::Foundation::ERROR_INVALID_PARAMETER`
 ///
-pub fn set_affinity_mask(handle: isize, affinity_mask: usize) -> Result<usize> {
-    call_num! { SetThreadAffinityMask(handle, affinity_mask) != 0 }
+pub fn set_affinity_mask<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+    affinity_mask: usize,
+) -> Result<usize> {
+    let handle = handle
+        .require_rights::<{ THREAD_SET_LIMITED_INFORMATION | THREAD_QUERY_LIMITED_INFORMATION }>();
+    call_num! { SetThreadAffinityMask(handle.as_raw(), affinity_mask) != 0 }
 }
 
 /// Sets description to a thread.
 ///
+/// # Remarks
+///
+/// * `description` can be any [`IntoWideCStr`] value, e.g. [`&str`][`str`], [`&OsStr`][`std::ffi::OsStr`], [`&Path`][`std::path::Path`] or [`&U16Str`][`crate::common::wide::U16Str`].
+///
 /// # Errors
 ///
-/// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the function fails.
+/// Returns an [`Error`] if the function fails.
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SET_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
+/// * `description` contains a null character. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
 ///
 /// # Examples
 ///
@@ -511,8 +593,23 @@ pub fn set_affinity_mask(handle: isize, affinity_mask: usize) -> Result<usize> {
 /// For more information see the official [documentation].
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreaddescription
-pub fn set_description(handle: isize, description: &U16Str) -> Result<()> {
-    call_BOOL! { SetThreadDescription(handle, description.as_ptr()) }
+pub fn set_description<const R: ThreadAccessRights, D>(
+    handle: &ThreadHandle<R>,
+    description: &D,
+) -> Result<(), Error>
+where
+    D: IntoWideCStr + ?Sized,
+{
+    let handle = handle.require_rights::<THREAD_SET_LIMITED_INFORMATION>();
+    let description = description.to_wide_c_str()?;
+    // Safety: `description` is a valid null-terminated wide string.
+    let result =
+        HResult::new(unsafe { SetThreadDescription(handle.as_raw(), description.as_ptr()) });
+    if result.is_failure() {
+        return Err(result.into());
+    }
+
+    Ok(())
 }
 
 /// Gets the processor number of the ideal processor for the specified thread.
@@ -523,8 +620,8 @@ pub fn set_description(handle: isize, description: &U16Str) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -534,10 +631,13 @@ pub fn set_description(handle: isize, description: &U16Str) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadidealprocessorex
 ///
-pub fn get_ideal_processor(handle: isize) -> Result<PROCESSOR_NUMBER> {
+pub fn get_ideal_processor<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+) -> Result<PROCESSOR_NUMBER> {
+    let handle = handle.require_rights::<THREAD_QUERY_LIMITED_INFORMATION>();
     call_BOOL! {
         GetThreadIdealProcessorEx(
-            handle,
+            handle.as_raw(),
             &mut ideal_proc
         ) -> mut ideal_proc =
             // Safety: `PROCESS_NUMBER` is not a reference nor a pointer.
@@ -553,8 +653,8 @@ pub fn get_ideal_processor(handle: isize) -> Result<PROCESSOR_NUMBER> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -564,13 +664,14 @@ pub fn get_ideal_processor(handle: isize) -> Result<PROCESSOR_NUMBER> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadidealprocessorex
 ///
-pub fn set_ideal_processor(
-    handle: isize,
+pub fn set_ideal_processor<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
     ideal_processor: PROCESSOR_NUMBER,
 ) -> Result<PROCESSOR_NUMBER> {
+    let handle = handle.require_rights::<THREAD_SET_INFORMATION>();
     call_BOOL! {
         SetThreadIdealProcessorEx(
-            handle,
+            handle.as_raw(),
             &ideal_processor,
             &mut prev_ideal_proc
         ) -> mut prev_ideal_proc =
@@ -587,8 +688,8 @@ pub fn set_ideal_processor(
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_QUERY_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -598,8 +699,11 @@ pub fn set_ideal_processor(
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadselectedcpusets
 ///
-pub fn clear_selected_cpu_sets(handle: isize) -> Result<()> {
-    call_BOOL! { SetThreadSelectedCpuSets(handle, ptr::null_mut(), 0) }
+pub fn clear_selected_cpu_sets<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+) -> Result<()> {
+    let handle = handle.require_rights::<THREAD_SET_LIMITED_INFORMATION>();
+    call_BOOL! { SetThreadSelectedCpuSets(handle.as_raw(), ptr::null_mut(), 0) }
 }
 
 /// Sets the selected CPU Sets assignment for the specified thread. This assignment overrides the process default assignment, if one is set.
@@ -610,8 +714,8 @@ pub fn clear_selected_cpu_sets(handle: isize) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SET_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -621,8 +725,12 @@ pub fn clear_selected_cpu_sets(handle: isize) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadselectedcpusets
 ///
-pub fn set_selected_cpu_sets(handle: isize, cpu_sets: &[u32]) -> Result<()> {
-    call_BOOL! { SetThreadSelectedCpuSets(handle, cpu_sets.as_ptr(), cpu_sets.len() as u32) }
+pub fn set_selected_cpu_sets<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+    cpu_sets: &[u32],
+) -> Result<()> {
+    let handle = handle.require_rights::<THREAD_SET_LIMITED_INFORMATION>();
+    call_BOOL! { SetThreadSelectedCpuSets(handle.as_raw(), cpu_sets.as_ptr(), cpu_sets.len() as u32) }
 }
 
 /// Sets the priority value for the specified thread. This value, together with the priority class of the thread's process,
@@ -634,8 +742,8 @@ pub fn set_selected_cpu_sets(handle: isize, cpu_sets: &[u32]) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] or [`THREAD_SET_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] or [`THREAD_SET_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -645,8 +753,12 @@ pub fn set_selected_cpu_sets(handle: isize, cpu_sets: &[u32]) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadpriority
 ///
-pub fn set_priority(handle: isize, priority: i32) -> Result<()> {
-    call_BOOL! { SetThreadPriority(handle, priority) }
+pub fn set_priority<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+    priority: i32,
+) -> Result<()> {
+    let handle = handle.require_rights::<THREAD_SET_LIMITED_INFORMATION>();
+    call_BOOL! { SetThreadPriority(handle.as_raw(), priority) }
 }
 
 /// Enables or disables the ability of the system to temporarily boost the priority of a thread based on the value of `enable`.
@@ -657,8 +769,8 @@ pub fn set_priority(handle: isize, priority: i32) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] or [`THREAD_SET_LIMITED_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] or [`THREAD_SET_LIMITED_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -668,8 +780,12 @@ pub fn set_priority(handle: isize, priority: i32) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadpriorityboost
 ///
-pub fn set_priority_boost(handle: isize, enable: bool) -> Result<()> {
-    call_BOOL! { SetThreadPriorityBoost(handle, to_BOOL!(!enable)) }
+pub fn set_priority_boost<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+    enable: bool,
+) -> Result<()> {
+    let handle = handle.require_rights::<THREAD_SET_LIMITED_INFORMATION>();
+    call_BOOL! { SetThreadPriorityBoost(handle.as_raw(), to_BOOL!(!enable)) }
 }
 
 /// Sets information for the specified thread.
@@ -680,8 +796,8 @@ pub fn set_priority_boost(handle: isize, enable: bool) -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_SET_INFORMATION`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -691,10 +807,14 @@ pub fn set_priority_boost(handle: isize, enable: bool) -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadinformation
 ///
-pub fn set_information<T: Copy + ThreadInformation>(handle: isize, information: T) -> Result<()> {
+pub fn set_information<T: Copy + ThreadInformation, const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+    information: T,
+) -> Result<()> {
+    let handle = handle.require_rights::<THREAD_SET_INFORMATION>();
     call_BOOL! {
         SetThreadInformation(
-            handle,
+            handle.as_raw(),
             T::information_class(),
             addr_of!(information).cast::<c_void>(),
             size_of::<T>() as u32)
@@ -732,8 +852,8 @@ pub fn switch_to_another() -> Result<()> {
 ///
 /// ## Possible errors
 ///
-/// * `handle` is invalid.
-/// * `handle` doesn't have [`THREAD_TERMINATE`] access right.
+/// * `handle` is invalid. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+/// * `handle` doesn't have [`THREAD_TERMINATE`] access right. ([`ErrorKind::PermissionDenied`][`crate::win32::core::ErrorKind::PermissionDenied`])
 ///
 /// # Examples
 ///
@@ -743,6 +863,48 @@ pub fn switch_to_another() -> Result<()> {
 ///
 /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-terminatethread
 ///
-pub fn terminate(handle: isize, exit_code: u32) -> Result<()> {
-    call_BOOL! { TerminateThread(handle, exit_code) }
+pub fn terminate<const R: ThreadAccessRights>(
+    handle: &ThreadHandle<R>,
+    exit_code: u32,
+) -> Result<()> {
+    let handle = handle.require_rights::<THREAD_TERMINATE>();
+    call_BOOL! { TerminateThread(handle.as_raw(), exit_code) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::win32::backend::mock::{fake_handle, ScriptedBackend, Step};
+    use crate::win32::core::Win32Error;
+    use alloc::vec;
+    use windows_sys::Win32::Foundation::ERROR_INVALID_HANDLE;
+
+    #[test]
+    fn get_selected_cpu_sets_retries_with_the_required_len() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe { fake_handle::<ThreadObject, THREAD_QUERY_LIMITED_INFORMATION>() };
+        let backend = ScriptedBackend::new()
+            .then(Step::GetThreadSelectedCpuSets(Ok(vec![256, 257, 258])))
+            // The assignment grew between the calls, so the second call is too small as well.
+            .then(Step::GetThreadSelectedCpuSets(Ok(vec![256, 257, 258, 259])))
+            .then(Step::GetThreadSelectedCpuSets(Ok(vec![256, 257, 258, 259])));
+
+        let cpu_set_ids = get_selected_cpu_sets_in(&backend, &handle).unwrap();
+
+        assert_eq!(&*cpu_set_ids, &[256, 257, 258, 259]);
+        assert!(backend.is_finished());
+    }
+
+    #[test]
+    fn get_selected_cpu_sets_passes_errors_through() {
+        // Safety: The handle is only passed to the scripted backend.
+        let handle = unsafe { fake_handle::<ThreadObject, THREAD_QUERY_LIMITED_INFORMATION>() };
+        let backend = ScriptedBackend::new().then(Step::GetThreadSelectedCpuSets(Err(
+            Win32Error::new(ERROR_INVALID_HANDLE),
+        )));
+
+        let error = get_selected_cpu_sets_in(&backend, &handle).unwrap_err();
+
+        assert_eq!(error.code(), ERROR_INVALID_HANDLE);
+    }
 }
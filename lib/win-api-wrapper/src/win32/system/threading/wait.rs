@@ -0,0 +1,491 @@
+use crate::to_BOOL;
+use crate::win32::core::{Result, Win32Error};
+use crate::win32::foundation::BorrowedHandle;
+use core::time::Duration;
+use windows_sys::Win32::Foundation::{
+    ERROR_INVALID_PARAMETER, WAIT_ABANDONED_0, WAIT_FAILED, WAIT_IO_COMPLETION, WAIT_OBJECT_0,
+    WAIT_TIMEOUT,
+};
+use windows_sys::Win32::System::SystemServices::MAXIMUM_WAIT_OBJECTS;
+use windows_sys::Win32::System::Threading::{WaitForMultipleObjectsEx, WaitForSingleObjectEx};
+
+pub use windows_sys::Win32::System::Threading::INFINITE;
+
+#[cfg(feature = "std")]
+use crate::win32::foundation::OwnedHandle;
+#[cfg(feature = "async")]
+pub mod future;
+
+#[cfg(feature = "std")]
+use crate::{call_BOOL, call_num};
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::ptr;
+#[cfg(feature = "std")]
+use windows_sys::Win32::System::Threading::{CreateEventW, SetEvent};
+
+/// The maximum number of handles that can be passed to [`wait_for_multiple_objects`].
+pub const MAX_WAIT_OBJECTS: usize = MAXIMUM_WAIT_OBJECTS as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The outcome of a successful wait.
+pub enum WaitOutcome {
+    /// The object at the specified index is signaled.
+    /// If all objects were waited for, the index is `0` and every object is signaled.
+    Signaled(usize),
+    /// The object at the specified index is a mutex that was not released by the thread that owned it before it exited.
+    /// The ownership of the mutex is granted to the calling thread.
+    Abandoned(usize),
+    /// The time-out interval elapsed before the wait was satisfied.
+    Timeout,
+    /// The wait was ended by a user-mode APC or an I/O completion routine that was queued to the thread.
+    /// It only happens when the wait is alertable.
+    IoCompletion,
+}
+
+impl WaitOutcome {
+    #[inline]
+    /// Gets the index of the signaled or abandoned object, or [`None`] if the wait was not satisfied.
+    pub const fn index(&self) -> Option<usize> {
+        match self {
+            Self::Signaled(index) | Self::Abandoned(index) => Some(*index),
+            Self::Timeout | Self::IoCompletion => None,
+        }
+    }
+
+    #[inline]
+    /// Determines whether the wait ended, because the time-out interval elapsed.
+    pub const fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout)
+    }
+
+    /// Interprets the result of a wait function that waited for `count` objects.
+    fn from_result(result: u32, count: usize, function: &'static str) -> Result<Self> {
+        if result == WAIT_FAILED {
+            return Err(Win32Error::get_last_from(function));
+        }
+
+        Self::from_code(result, count, function)
+    }
+
+    /// Interprets a result other than `WAIT_FAILED` of a wait function that waited for `count` objects.
+    fn from_code(result: u32, count: usize, function: &'static str) -> Result<Self> {
+        let count = count as u32;
+        match result {
+            WAIT_TIMEOUT => Ok(Self::Timeout),
+            WAIT_IO_COMPLETION => Ok(Self::IoCompletion),
+            result if result.wrapping_sub(WAIT_OBJECT_0) < count => {
+                Ok(Self::Signaled((result - WAIT_OBJECT_0) as usize))
+            }
+            result if result.wrapping_sub(WAIT_ABANDONED_0) < count => {
+                Ok(Self::Abandoned((result - WAIT_ABANDONED_0) as usize))
+            }
+            result => Err(Win32Error::new(result).with_context(function)),
+        }
+    }
+}
+
+/// Converts a time-out interval to milliseconds that can be passed to the wait functions.
+///
+/// # Remarks
+///
+/// * [`None`] means that the wait never times out ([`INFINITE`]).
+/// * The interval is rounded up to the next millisecond, so the wait never ends before `timeout` elapses.
+/// * Intervals that are too long to be represented are capped at `INFINITE - 1` milliseconds (about 49.7 days),
+///   so a finite interval never turns into an infinite wait.
+///
+pub const fn timeout_to_ms(timeout: Option<Duration>) -> u32 {
+    let Some(timeout) = timeout else {
+        return INFINITE;
+    };
+
+    let ms = timeout.as_millis() + (timeout.subsec_nanos() % 1_000_000 != 0) as u128;
+    if ms < INFINITE as u128 {
+        ms as u32
+    } else {
+        INFINITE - 1
+    }
+}
+
+/// Waits until `handle` is signaled or `timeout` elapses. See [`wait_for_single_object_ex`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid or it doesn't have the `SYNCHRONIZE` access right. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/synchapi/nf-synchapi-waitforsingleobject
+///
+pub fn wait_for_single_object(
+    handle: BorrowedHandle<'_>,
+    timeout: Option<Duration>,
+) -> Result<WaitOutcome> {
+    wait_for_single_object_ex(handle, timeout, false)
+}
+
+/// Waits until `handle` is signaled, `timeout` elapses or, if `alertable` is `true`,
+/// an I/O completion routine or an APC is queued to the calling thread.
+///
+/// # Remarks
+///
+/// * `timeout` is converted using [`timeout_to_ms`].
+/// * The index of a signaled or abandoned outcome is always `0`.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handle` is invalid or it doesn't have the `SYNCHRONIZE` access right. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/synchapi/nf-synchapi-waitforsingleobjectex
+///
+pub fn wait_for_single_object_ex(
+    handle: BorrowedHandle<'_>,
+    timeout: Option<Duration>,
+    alertable: bool,
+) -> Result<WaitOutcome> {
+    // Safety: `handle` is a valid handle for the duration of the call.
+    let result = unsafe {
+        WaitForSingleObjectEx(handle.as_raw(), timeout_to_ms(timeout), to_BOOL!(alertable))
+    };
+    WaitOutcome::from_result(result, 1, "WaitForSingleObjectEx")
+}
+
+/// Waits until any or all of `handles` are signaled or `timeout` elapses. See [`wait_for_multiple_objects_ex`].
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handles` is empty or it contains more than [`MAX_WAIT_OBJECTS`] handles. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * A handle is invalid or it doesn't have the `SYNCHRONIZE` access right. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/synchapi/nf-synchapi-waitformultipleobjects
+///
+pub fn wait_for_multiple_objects(
+    handles: &[BorrowedHandle<'_>],
+    wait_all: bool,
+    timeout: Option<Duration>,
+) -> Result<WaitOutcome> {
+    wait_for_multiple_objects_ex(handles, wait_all, timeout, false)
+}
+
+/// Waits until any or all of `handles` are signaled, `timeout` elapses or, if `alertable` is `true`,
+/// an I/O completion routine or an APC is queued to the calling thread.
+///
+/// # Remarks
+///
+/// * `timeout` is converted using [`timeout_to_ms`].
+/// * If `wait_all` is `false` and multiple objects are signaled, the outcome contains the lowest index.
+/// * If `wait_all` is `true`, the index of a signaled outcome is always `0`.
+/// * Use [`wait_for_many_objects`] to wait for more than [`MAX_WAIT_OBJECTS`] handles.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handles` is empty or it contains more than [`MAX_WAIT_OBJECTS`] handles. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * A handle is invalid or it doesn't have the `SYNCHRONIZE` access right. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+/// For more information see the official [documentation].
+///
+/// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/synchapi/nf-synchapi-waitformultipleobjectsex
+///
+pub fn wait_for_multiple_objects_ex(
+    handles: &[BorrowedHandle<'_>],
+    wait_all: bool,
+    timeout: Option<Duration>,
+    alertable: bool,
+) -> Result<WaitOutcome> {
+    if handles.is_empty() || handles.len() > MAX_WAIT_OBJECTS {
+        return Err(Win32Error::new(ERROR_INVALID_PARAMETER)
+            .with_context("the number of handles must be between 1 and MAXIMUM_WAIT_OBJECTS"));
+    }
+
+    // Safety: `BorrowedHandle` is a transparent wrapper of a raw handle, so `handles` can be passed as an array of handles.
+    let result = unsafe {
+        WaitForMultipleObjectsEx(
+            handles.len() as u32,
+            handles.as_ptr().cast(),
+            to_BOOL!(wait_all),
+            timeout_to_ms(timeout),
+            to_BOOL!(alertable),
+        )
+    };
+    WaitOutcome::from_result(result, handles.len(), "WaitForMultipleObjectsEx")
+}
+
+#[cfg(feature = "std")]
+/// Waits until any or all of `handles` are signaled or `timeout` elapses.
+/// Unlike [`wait_for_multiple_objects`], any number of handles is supported.
+///
+/// # Remarks
+///
+/// * Up to [`MAX_WAIT_OBJECTS`] handles are waited for directly using [`wait_for_multiple_objects`].
+/// * If `wait_all` is `false`, the handles are split into chunks that are waited for by helper threads.
+///   The helper threads are stopped as soon as any of them is satisfied, and the lowest signaled index is returned.
+///   They are woken up by an event and they also check periodically whether they should stop, so stopping them never fails.
+///   Because each helper thread performs its own wait, the signaled state of multiple objects may be consumed
+///   (e.g. auto-reset events), and mutexes are acquired by the helper threads, which exit right away.
+///   It is intended for objects whose signaled state is not changed by a wait, like processes, threads and manual-reset events.
+/// * If `wait_all` is `true`, the chunks are waited for one after the other until the deadline,
+///   so the objects are not guaranteed to be signaled at the same time.
+///   If any of the objects is an abandoned mutex, the lowest abandoned index is returned.
+///
+/// # Errors
+///
+/// Returns a [`Win32Error`] if the function fails.
+///
+/// ## Possible errors
+///
+/// * `handles` is empty. ([`ErrorKind::InvalidParameter`][`crate::win32::core::ErrorKind::InvalidParameter`])
+/// * A handle is invalid or it doesn't have the `SYNCHRONIZE` access right. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+///
+/// # Examples
+/// TODO
+///
+pub fn wait_for_many_objects(
+    handles: &[BorrowedHandle<'_>],
+    wait_all: bool,
+    timeout: Option<Duration>,
+) -> Result<WaitOutcome> {
+    if handles.len() <= MAX_WAIT_OBJECTS {
+        wait_for_multiple_objects(handles, wait_all, timeout)
+    } else if wait_all {
+        wait_for_all_chunks(handles, timeout)
+    } else {
+        wait_for_any_chunk(handles, timeout)
+    }
+}
+
+#[cfg(feature = "std")]
+/// Waits for every chunk of `handles` one after the other until the deadline. See [`wait_for_many_objects`].
+fn wait_for_all_chunks(
+    handles: &[BorrowedHandle<'_>],
+    timeout: Option<Duration>,
+) -> Result<WaitOutcome> {
+    let start = std::time::Instant::now();
+    let mut abandoned = None;
+    for (chunk_index, chunk) in handles.chunks(MAX_WAIT_OBJECTS).enumerate() {
+        let remaining = timeout.map(|timeout| timeout.saturating_sub(start.elapsed()));
+        match wait_for_multiple_objects(chunk, true, remaining)? {
+            WaitOutcome::Abandoned(index) => {
+                abandoned = abandoned.or(Some(chunk_index * MAX_WAIT_OBJECTS + index));
+            }
+            WaitOutcome::Signaled(_) => {}
+            outcome => return Ok(outcome),
+        }
+    }
+
+    Ok(abandoned.map_or(WaitOutcome::Signaled(0), WaitOutcome::Abandoned))
+}
+
+#[cfg(feature = "std")]
+/// Waits for the chunks of `handles` on helper threads until any of them is satisfied. See [`wait_for_many_objects`].
+fn wait_for_any_chunk(
+    handles: &[BorrowedHandle<'_>],
+    timeout: Option<Duration>,
+) -> Result<WaitOutcome> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+    use std::thread;
+
+    // Every helper thread also waits for the cancel event, so the remaining threads can be stopped.
+    const CHUNK_LEN: usize = MAX_WAIT_OBJECTS - 1;
+    // The helper threads also check the stop flag periodically, so they stop even if the cancel event cannot be set.
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    let cancel = create_cancel_event()?;
+    let cancel_handle = cancel.as_handle();
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+    let first = thread::scope(|scope| {
+        for (chunk_index, chunk) in handles.chunks(CHUNK_LEN).enumerate() {
+            let sender = sender.clone();
+            let stop = &stop;
+            scope.spawn(move || {
+                let mut chunk_handles = Vec::with_capacity(chunk.len() + 1);
+                chunk_handles.push(cancel_handle);
+                chunk_handles.extend_from_slice(chunk);
+                let offset = chunk_index * CHUNK_LEN;
+                let outcome = loop {
+                    match wait_for_multiple_objects(&chunk_handles, false, Some(POLL_INTERVAL)) {
+                        Ok(WaitOutcome::Signaled(0)) => return,
+                        Ok(WaitOutcome::Timeout) if stop.load(Ordering::Acquire) => return,
+                        Ok(WaitOutcome::Timeout) => {}
+                        Ok(WaitOutcome::Signaled(index)) => {
+                            break Ok(WaitOutcome::Signaled(offset + index - 1))
+                        }
+                        Ok(WaitOutcome::Abandoned(index)) => {
+                            break Ok(WaitOutcome::Abandoned(offset + index - 1))
+                        }
+                        outcome => break outcome,
+                    }
+                };
+                // The receiver only stops listening after every helper thread has exited.
+                let _ = sender.send(outcome);
+            });
+        }
+        drop(sender);
+
+        let first = timeout.map_or_else(
+            || receiver.recv().ok(),
+            |timeout| receiver.recv_timeout(timeout).ok(),
+        );
+        // The helper threads are joined at the end of the scope, so they must be stopped even if nothing was received.
+        // Setting the event only wakes them up early: if it fails, they notice the flag within the poll interval.
+        stop.store(true, Ordering::Release);
+        let _ = set_event(cancel_handle);
+        first
+    });
+
+    // More chunks might have been satisfied before the helper threads were stopped, even after a time-out.
+    // A satisfied wait is preferred over the errors of other helper threads.
+    let mut outcome: Option<WaitOutcome> = None;
+    let mut error = None;
+    for received in first.into_iter().chain(receiver.try_iter()) {
+        match received {
+            Ok(other) if outcome.is_none_or(|outcome| other.index() < outcome.index()) => {
+                outcome = Some(other);
+            }
+            Ok(_) => {}
+            Err(other) => error = error.or(Some(other)),
+        }
+    }
+
+    match (outcome, error) {
+        (Some(outcome), _) => Ok(outcome),
+        (None, Some(error)) => Err(error),
+        (None, None) => Ok(WaitOutcome::Timeout),
+    }
+}
+
+#[cfg(feature = "std")]
+/// Creates an unnamed manual-reset event that is initially not signaled.
+fn create_cancel_event() -> Result<OwnedHandle> {
+    let handle = call_num! { CreateEventW(ptr::null(), 1, 0, ptr::null()) != 0 }?;
+    // Safety: `handle` is a new event handle that is not owned by anything else.
+    Ok(unsafe { OwnedHandle::from_raw(handle) })
+}
+
+#[cfg(feature = "std")]
+/// Sets the cancel event, so the helper threads of [`wait_for_any_chunk`] stop waiting.
+fn set_event(event: BorrowedHandle<'_>) -> Result<()> {
+    call_BOOL! { SetEvent(event.as_raw()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_to_ms_rounds_up() {
+        let cases = [
+            (None, INFINITE),
+            (Some(Duration::ZERO), 0),
+            (Some(Duration::from_nanos(1)), 1),
+            (Some(Duration::from_millis(5)), 5),
+            (Some(Duration::new(1, 1)), 1001),
+            (Some(Duration::from_micros(999)), 1),
+            (
+                Some(Duration::from_millis(u64::from(INFINITE) - 1)),
+                INFINITE - 1,
+            ),
+            // A finite interval never turns into an infinite wait.
+            (
+                Some(Duration::from_millis(u64::from(INFINITE))),
+                INFINITE - 1,
+            ),
+            (Some(Duration::MAX), INFINITE - 1),
+        ];
+
+        for (timeout, expected) in cases {
+            assert_eq!(timeout_to_ms(timeout), expected, "{timeout:?}");
+        }
+    }
+
+    #[test]
+    fn from_code_maps_the_index_ranges() {
+        let cases = [
+            (WAIT_OBJECT_0, 1, WaitOutcome::Signaled(0)),
+            (WAIT_OBJECT_0 + 2, 3, WaitOutcome::Signaled(2)),
+            (
+                WAIT_OBJECT_0 + MAX_WAIT_OBJECTS as u32 - 1,
+                MAX_WAIT_OBJECTS,
+                WaitOutcome::Signaled(MAX_WAIT_OBJECTS - 1),
+            ),
+            (WAIT_ABANDONED_0, 1, WaitOutcome::Abandoned(0)),
+            (WAIT_ABANDONED_0 + 2, 3, WaitOutcome::Abandoned(2)),
+            (
+                WAIT_ABANDONED_0 + MAX_WAIT_OBJECTS as u32 - 1,
+                MAX_WAIT_OBJECTS,
+                WaitOutcome::Abandoned(MAX_WAIT_OBJECTS - 1),
+            ),
+            (WAIT_TIMEOUT, 1, WaitOutcome::Timeout),
+            (WAIT_IO_COMPLETION, 1, WaitOutcome::IoCompletion),
+        ];
+
+        for (result, count, expected) in cases {
+            assert_eq!(
+                WaitOutcome::from_code(result, count, "Wait"),
+                Ok(expected),
+                "{result:#x}"
+            );
+        }
+    }
+
+    #[test]
+    fn from_code_rejects_out_of_range_codes() {
+        let cases = [
+            (WAIT_OBJECT_0 + 1, 1),
+            (WAIT_OBJECT_0 + 3, 3),
+            (WAIT_ABANDONED_0 + 1, 1),
+            (WAIT_ABANDONED_0 + 3, 3),
+            (WAIT_ABANDONED_0 - 1, 1),
+            (0x1234, 1),
+        ];
+
+        for (result, count) in cases {
+            let error = WaitOutcome::from_code(result, count, "Wait").unwrap_err();
+            assert_eq!(error.code(), result, "{result:#x}");
+        }
+    }
+
+    #[test]
+    fn index_is_only_set_for_satisfied_waits() {
+        assert_eq!(WaitOutcome::Signaled(3).index(), Some(3));
+        assert_eq!(WaitOutcome::Abandoned(4).index(), Some(4));
+        assert_eq!(WaitOutcome::Timeout.index(), None);
+        assert_eq!(WaitOutcome::IoCompletion.index(), None);
+        assert!(WaitOutcome::Timeout.is_timeout());
+        assert!(!WaitOutcome::Signaled(0).is_timeout());
+    }
+}
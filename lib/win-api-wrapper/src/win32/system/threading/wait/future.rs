@@ -0,0 +1,173 @@
+//! A [`Future`] that completes when a waitable handle is signaled, built on `RegisterWaitForSingleObject`.
+
+use super::{timeout_to_ms, WaitOutcome};
+use crate::call_BOOL;
+use crate::win32::core::Result;
+use crate::win32::foundation::BorrowedHandle;
+use alloc::sync::Arc;
+use core::ffi::c_void;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::ptr::addr_of_mut;
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use windows_sys::Win32::Foundation::{BOOLEAN, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::System::Threading::{
+    RegisterWaitForSingleObject, UnregisterWaitEx, WT_EXECUTEONLYONCE,
+};
+
+#[derive(Debug, Default)]
+/// The state that is shared between a [`WaitFuture`] and its wait callback.
+struct Shared {
+    /// The outcome of the wait and the waker of the task that polled the future last.
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+/// The mutable part of [`Shared`].
+struct State {
+    /// The outcome of the wait, or [`None`] if the callback has not been called yet.
+    outcome: Option<WaitOutcome>,
+    /// The waker of the task that polled the future last.
+    waker: Option<Waker>,
+}
+
+impl Shared {
+    /// Locks the state. A poisoned lock is recovered, because the state is always consistent.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Called by a thread pool thread when the handle is signaled or the time-out interval elapsed.
+///
+/// # Safety
+///
+/// * `context` must be the pointer to the [`Shared`] state that was registered by [`WaitFuture::new`].
+///
+unsafe extern "system" fn on_wait_completed(context: *mut c_void, timed_out: BOOLEAN) {
+    // Safety: The registered reference to the shared state is released only after the wait is unregistered,
+    // which waits for this callback to return.
+    let shared = unsafe { &*context.cast_const().cast::<Shared>() };
+    let waker = {
+        let mut state = shared.lock();
+        state.outcome = Some(if timed_out != 0 {
+            WaitOutcome::Timeout
+        } else {
+            WaitOutcome::Signaled(0)
+        });
+        state.waker.take()
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+#[derive(Debug)]
+/// A [`Future`] that completes when a handle is signaled or the time-out interval elapses.
+///
+/// # Remarks
+///
+/// * The wait is registered when the future is created, so the handle is waited for even if the future is not polled.
+/// * The output is [`WaitOutcome::Signaled`] with index `0`, or [`WaitOutcome::Timeout`].
+/// * No thread is blocked while waiting: the system thread pool calls back when the wait is satisfied.
+/// * Dropping the future unregisters the wait. If the callback is running at that moment, the drop blocks until it returns.
+/// * Like [`wait_for_many_objects`][`super::wait_for_many_objects`], the wait is performed by another thread,
+///   so it should not be used for objects whose signaled state is changed by a wait (e.g. mutexes).
+/// * Works with any executor, because the task is woken using its [`Waker`].
+///
+pub struct WaitFuture<'handle> {
+    /// The state that is shared with the wait callback.
+    shared: Arc<Shared>,
+    /// The reference to the shared state that is owned by the registered wait.
+    context: *const Shared,
+    /// The handle of the registered wait.
+    wait_handle: isize,
+    /// Ties the future to the lifetime of the waited handle.
+    _handle: PhantomData<BorrowedHandle<'handle>>,
+}
+
+// Safety: `context` is only dereferenced by the wait callback and released on drop, and `Shared` is thread-safe.
+unsafe impl Send for WaitFuture<'_> {}
+// Safety: `context` is never dereferenced through a shared reference to the future.
+unsafe impl Sync for WaitFuture<'_> {}
+
+impl<'handle> WaitFuture<'handle> {
+    /// Registers a wait for `handle` and returns a [`Future`] that completes when `handle` is signaled or `timeout` elapses.
+    ///
+    /// # Remarks
+    ///
+    /// * `timeout` is converted using [`timeout_to_ms`]. [`None`] waits indefinitely.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`crate::win32::core::Win32Error`] if the wait cannot be registered.
+    ///
+    /// ## Possible errors
+    ///
+    /// * `handle` is invalid or it doesn't have the `SYNCHRONIZE` access right. ([`ErrorKind::InvalidHandle`][`crate::win32::core::ErrorKind::InvalidHandle`])
+    ///
+    /// # Examples
+    /// TODO
+    ///
+    /// For more information see the official [documentation].
+    ///
+    /// [documentation]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-registerwaitforsingleobject
+    ///
+    pub fn new(handle: BorrowedHandle<'handle>, timeout: Option<Duration>) -> Result<Self> {
+        let shared = Arc::new(Shared::default());
+        let context = Arc::into_raw(Arc::clone(&shared));
+        let mut wait_handle = 0;
+        let registered = call_BOOL! {
+            RegisterWaitForSingleObject(
+                addr_of_mut!(wait_handle),
+                handle.as_raw(),
+                Some(on_wait_completed),
+                context.cast(),
+                timeout_to_ms(timeout),
+                WT_EXECUTEONLYONCE
+            )
+        };
+        if let Err(error) = registered {
+            // Safety: The wait was not registered, so the reference is still owned by this function.
+            drop(unsafe { Arc::from_raw(context) });
+            return Err(error);
+        }
+
+        Ok(Self {
+            shared,
+            context,
+            wait_handle,
+            _handle: PhantomData,
+        })
+    }
+}
+
+impl Future for WaitFuture<'_> {
+    type Output = WaitOutcome;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock();
+        if let Some(outcome) = state.outcome {
+            return Poll::Ready(outcome);
+        }
+
+        match &state.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => state.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitFuture<'_> {
+    fn drop(&mut self) {
+        // `INVALID_HANDLE_VALUE` waits for a running callback, so the reference to the shared state can be released.
+        if call_BOOL! { UnregisterWaitEx(self.wait_handle, INVALID_HANDLE_VALUE) }.is_ok() {
+            // Safety: The wait is unregistered and no callback is running, so the reference is no longer used.
+            drop(unsafe { Arc::from_raw(self.context) });
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#!/usr/bin/env python3
+"""Generates `src/win32/core/error_names.rs` from the windows-sys crate that the library depends on.
+
+Usage (from any directory):
+
+    python3 tools/gen_error_names.py
+
+The `ERROR_*` constants of type `WIN32_ERROR` are extracted from `Win32::Foundation` of the
+windows-sys version that `cargo metadata` resolves (i.e. the one in `Cargo.lock`).
+If an error code has more than one name, the first one in the windows-sys source is used.
+The output is formatted with rustfmt.
+"""
+
+import json
+import pathlib
+import re
+import subprocess
+
+CRATE_DIR = pathlib.Path(__file__).resolve().parent.parent
+OUTPUT = CRATE_DIR / "src" / "win32" / "core" / "error_names.rs"
+FOUNDATION = pathlib.Path("src", "Windows", "Win32", "Foundation", "mod.rs")
+CONSTANT = re.compile(r"^pub const (ERROR_\w+): WIN32_ERROR = (\d+)u32;$", re.MULTILINE)
+
+
+def find_windows_sys():
+    """Returns the version and the directory of the windows-sys package used by the library."""
+    metadata = json.loads(
+        subprocess.check_output(
+            ["cargo", "metadata", "--format-version", "1"], cwd=CRATE_DIR
+        )
+    )
+    for package in metadata["packages"]:
+        if package["name"] == "windows-sys":
+            return package["version"], pathlib.Path(package["manifest_path"]).parent
+    raise SystemExit("windows-sys is not a dependency of the library")
+
+
+def main():
+    version, package_dir = find_windows_sys()
+    source = (package_dir / FOUNDATION).read_text(encoding="utf-8")
+
+    names = {}
+    for name, code in CONSTANT.findall(source):
+        names.setdefault(int(code), name)
+
+    lines = [
+        "//! A table of the symbolic names of the documented Win32 error codes.",
+        "//!",
+        "//! Generated by `tools/gen_error_names.py` from the `ERROR_*` constants of type `WIN32_ERROR` in",
+        f"//! `{FOUNDATION.as_posix()}` of windows-sys {version}. Don't edit it by hand, run the script instead.",
+        "//! If an error code has more than one name, the first one is used.",
+        "//! The table is searched with a binary search, so it is sorted by the error code without duplicates.",
+        "",
+        "/// Symbolic names of the Win32 error codes sorted by the error code.",
+        "pub(super) static ERROR_NAMES: &[(u32, &str)] = &[",
+    ]
+    lines += [f'    ({code}, "{name}"),' for code, name in sorted(names.items())]
+    lines.append("];")
+
+    OUTPUT.write_text("\n".join(lines) + "\n", encoding="utf-8")
+    subprocess.check_call(["rustfmt", "--edition", "2021", str(OUTPUT)])
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,494 @@
+use crate::process::{Process, ProcessId};
+use std::time::Duration;
+use win_api_wrapper::common::wide::IntoWideCStr;
+use win_api_wrapper::win32::core::Result;
+use win_api_wrapper::win32::foundation::{BorrowedHandle, OwnedHandle};
+use win_api_wrapper::win32::system::io;
+use win_api_wrapper::win32::system::job_objects::{
+    self, CpuRateControl, JobHandle, JobNotification,
+    JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_ALL_ACCESS, JOB_OBJECT_LIMIT, JOB_OBJECT_LIMIT_ACTIVE_PROCESS,
+    JOB_OBJECT_LIMIT_BREAKAWAY_OK, JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION,
+    JOB_OBJECT_LIMIT_JOB_MEMORY, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+};
+use win_api_wrapper::win32::system::threading::process::ProcessAccessRights;
+
+/// The limit flags that are managed by [`JobLimits`]. The other limits of a job are left unchanged.
+const MANAGED_LIMITS: JOB_OBJECT_LIMIT = JOB_OBJECT_LIMIT_PROCESS_MEMORY
+    | JOB_OBJECT_LIMIT_JOB_MEMORY
+    | JOB_OBJECT_LIMIT_ACTIVE_PROCESS
+    | JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE
+    | JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION
+    | JOB_OBJECT_LIMIT_BREAKAWAY_OK;
+
+/// Converts a time in 100-nanosecond intervals to a [`Duration`].
+const fn ticks_to_duration(ticks: i64) -> Duration {
+    let ticks = if ticks < 0 { 0 } else { ticks as u64 };
+    Duration::new(ticks / 10_000_000, (ticks % 10_000_000) as u32 * 100)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// The limits of a job. See [`Job::set_limits`].
+///
+/// # Remarks
+///
+/// * The limits are stored in the [`JOBOBJECT_EXTENDED_LIMIT_INFORMATION`] of the job.
+///
+pub struct JobLimits {
+    /// The maximum committed memory of each process in bytes.
+    pub process_memory: Option<usize>,
+    /// The maximum committed memory of all processes of the job in bytes.
+    pub job_memory: Option<usize>,
+    /// The maximum number of simultaneously active processes.
+    pub active_processes: Option<u32>,
+    /// Determines whether the processes of the job are terminated when the last handle of the job is closed.
+    pub kill_on_job_close: bool,
+    /// Determines whether processes terminate on an unhandled exception instead of showing an error dialog.
+    pub die_on_unhandled_exception: bool,
+    /// Determines whether processes can be created outside of the job using `CREATE_BREAKAWAY_FROM_JOB`.
+    pub breakaway_ok: bool,
+}
+
+impl JobLimits {
+    /// Reads the limits from a [`JOBOBJECT_EXTENDED_LIMIT_INFORMATION`].
+    const fn from_information(information: &JOBOBJECT_EXTENDED_LIMIT_INFORMATION) -> Self {
+        let basic = &information.BasicLimitInformation;
+        let flags = basic.LimitFlags;
+        Self {
+            process_memory: if flags & JOB_OBJECT_LIMIT_PROCESS_MEMORY != 0 {
+                Some(information.ProcessMemoryLimit)
+            } else {
+                None
+            },
+            job_memory: if flags & JOB_OBJECT_LIMIT_JOB_MEMORY != 0 {
+                Some(information.JobMemoryLimit)
+            } else {
+                None
+            },
+            active_processes: if flags & JOB_OBJECT_LIMIT_ACTIVE_PROCESS != 0 {
+                Some(basic.ActiveProcessLimit)
+            } else {
+                None
+            },
+            kill_on_job_close: flags & JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE != 0,
+            die_on_unhandled_exception: flags & JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION != 0,
+            breakaway_ok: flags & JOB_OBJECT_LIMIT_BREAKAWAY_OK != 0,
+        }
+    }
+
+    /// Writes the limits to a [`JOBOBJECT_EXTENDED_LIMIT_INFORMATION`] without changing the other limits.
+    const fn apply(&self, information: &mut JOBOBJECT_EXTENDED_LIMIT_INFORMATION) {
+        let mut flags = information.BasicLimitInformation.LimitFlags & !MANAGED_LIMITS;
+        if let Some(limit) = self.process_memory {
+            flags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            information.ProcessMemoryLimit = limit;
+        }
+        if let Some(limit) = self.job_memory {
+            flags |= JOB_OBJECT_LIMIT_JOB_MEMORY;
+            information.JobMemoryLimit = limit;
+        }
+        if let Some(limit) = self.active_processes {
+            flags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+            information.BasicLimitInformation.ActiveProcessLimit = limit;
+        }
+        if self.kill_on_job_close {
+            flags |= JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        }
+        if self.die_on_unhandled_exception {
+            flags |= JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION;
+        }
+        if self.breakaway_ok {
+            flags |= JOB_OBJECT_LIMIT_BREAKAWAY_OK;
+        }
+        information.BasicLimitInformation.LimitFlags = flags;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// The accounting information of a job. See [`Job::accounting`].
+pub struct JobAccounting {
+    /// The user-mode execution time of all processes that have ever been in the job.
+    pub total_user_time: Duration,
+    /// The kernel-mode execution time of all processes that have ever been in the job.
+    pub total_kernel_time: Duration,
+    /// The number of page faults of all processes that have ever been in the job.
+    pub total_page_faults: u32,
+    /// The number of processes that have ever been in the job.
+    pub total_processes: u32,
+    /// The number of processes that are currently in the job.
+    pub active_processes: u32,
+    /// The number of processes that were terminated because of a limit violation.
+    pub total_terminated_processes: u32,
+    /// The number of read operations.
+    pub read_operations: u64,
+    /// The number of write operations.
+    pub write_operations: u64,
+    /// The number of I/O operations other than read and write operations.
+    pub other_operations: u64,
+    /// The number of bytes read.
+    pub read_bytes: u64,
+    /// The number of bytes written.
+    pub write_bytes: u64,
+    /// The number of bytes transferred by operations other than read and write operations.
+    pub other_bytes: u64,
+}
+
+impl From<JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION> for JobAccounting {
+    fn from(information: JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION) -> Self {
+        let basic = information.BasicInfo;
+        let io = information.IoInfo;
+        Self {
+            total_user_time: ticks_to_duration(basic.TotalUserTime),
+            total_kernel_time: ticks_to_duration(basic.TotalKernelTime),
+            total_page_faults: basic.TotalPageFaultCount,
+            total_processes: basic.TotalProcesses,
+            active_processes: basic.ActiveProcesses,
+            total_terminated_processes: basic.TotalTerminatedProcesses,
+            read_operations: io.ReadOperationCount,
+            write_operations: io.WriteOperationCount,
+            other_operations: io.OtherOperationCount,
+            read_bytes: io.ReadTransferCount,
+            write_bytes: io.WriteTransferCount,
+            other_bytes: io.OtherTransferCount,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A job object that has the `RIGHTS` access rights. It groups processes, so they can be limited and terminated together.
+///
+/// # Remarks
+///
+/// * The methods call the corresponding functions of [`job_objects`] with the owned handle.
+/// * Calling a method that requires access rights which are not included in `RIGHTS` fails to compile.
+/// * To make sure that a new process cannot escape the limits, create it suspended
+///   (see [`ProcessBuilder::suspended`][`crate::process::builder::ProcessBuilder::suspended`]),
+///   assign it to the job and then resume it.
+///
+pub struct Job<const RIGHTS: u32 = JOB_OBJECT_ALL_ACCESS> {
+    /// The owned handle of the job.
+    handle: JobHandle<RIGHTS>,
+}
+
+impl Job {
+    /// Creates a new unnamed job. See [`job_objects::create`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the job cannot be created.
+    ///
+    pub fn new() -> Result<Self> {
+        job_objects::create().map(Self::from_handle)
+    }
+
+    /// Creates a new named job, or opens the existing job with the same name. See [`job_objects::create_named`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the job cannot be created.
+    ///
+    pub fn create_named<N: IntoWideCStr + ?Sized>(name: &N) -> Result<Self> {
+        job_objects::create_named(name).map(Self::from_handle)
+    }
+}
+
+impl<const RIGHTS: u32> Job<RIGHTS> {
+    /// Opens an existing named job. See [`job_objects::open`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the job cannot be opened.
+    ///
+    /// ## Possible errors
+    ///
+    /// * The job doesn't exist. ([`ErrorKind::NotFound`][`win_api_wrapper::win32::core::ErrorKind::NotFound`])
+    /// * The caller is not allowed to open the job with the `RIGHTS` access rights. ([`ErrorKind::PermissionDenied`][`win_api_wrapper::win32::core::ErrorKind::PermissionDenied`])
+    ///
+    pub fn open<N: IntoWideCStr + ?Sized>(name: &N) -> Result<Self> {
+        job_objects::open::<RIGHTS, N>(name, false).map(Self::from_handle)
+    }
+
+    #[inline]
+    /// Creates a new [`Job`] that takes the ownership of `handle`.
+    pub const fn from_handle(handle: JobHandle<RIGHTS>) -> Self {
+        Self { handle }
+    }
+
+    #[inline]
+    /// Gets the owned handle of the job.
+    pub const fn handle(&self) -> &JobHandle<RIGHTS> {
+        &self.handle
+    }
+
+    #[inline]
+    /// Borrows the handle of the job.
+    pub const fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.handle.as_handle()
+    }
+
+    #[inline]
+    #[allow(clippy::missing_const_for_fn)]
+    /// Consumes the [`Job`] and returns its owned handle.
+    pub fn into_handle(self) -> JobHandle<RIGHTS> {
+        self.handle
+    }
+
+    /// Closes the handle of the job and reports the error that is ignored on drop.
+    ///
+    /// # Remarks
+    ///
+    /// * If [`JobLimits::kill_on_job_close`] is set and this is the last handle, the processes of the job are terminated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the handle cannot be closed.
+    ///
+    pub fn close(self) -> Result<()> {
+        self.handle.into_owned().close()
+    }
+
+    /// Assigns `process` to the job. See [`job_objects::assign_process`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn assign<const R: ProcessAccessRights>(&self, process: &Process<R>) -> Result<()> {
+        job_objects::assign_process(&self.handle, process.handle())
+    }
+
+    /// Determines whether `process` is in the job. See [`job_objects::is_process_in_job`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn contains<const R: ProcessAccessRights>(&self, process: &Process<R>) -> Result<bool> {
+        job_objects::is_process_in_job(process.handle(), &self.handle)
+    }
+
+    /// Gets the identifiers of the processes that are in the job. See [`job_objects::get_process_ids`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn process_ids(&self) -> Result<Vec<ProcessId>> {
+        Ok(job_objects::get_process_ids(&self.handle)?
+            .into_iter()
+            .map(ProcessId)
+            .collect())
+    }
+
+    /// Terminates every process of the job with the given exit code. See [`job_objects::terminate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn terminate(&self, exit_code: u32) -> Result<()> {
+        job_objects::terminate(&self.handle, exit_code)
+    }
+
+    /// Gets the limits of the job. See [`JobLimits`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn limits(&self) -> Result<JobLimits> {
+        let information = job_objects::get_information::<
+            JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            RIGHTS,
+        >(&self.handle)?;
+        Ok(JobLimits::from_information(&information))
+    }
+
+    /// Sets the limits of the job. The limits that are not part of [`JobLimits`] are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    /// ## Possible errors
+    ///
+    /// * A memory limit is too small. ([`ErrorKind::InvalidParameter`][`win_api_wrapper::win32::core::ErrorKind::InvalidParameter`])
+    ///
+    pub fn set_limits(&self, limits: &JobLimits) -> Result<()> {
+        let mut information = job_objects::get_information::<
+            JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            RIGHTS,
+        >(&self.handle)?;
+        limits.apply(&mut information);
+        job_objects::set_information(&self.handle, information)
+    }
+
+    /// Gets the CPU rate control of the job, or [`None`] if it is disabled. See [`job_objects::get_cpu_rate_control`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn cpu_rate_control(&self) -> Result<Option<CpuRateControl>> {
+        job_objects::get_cpu_rate_control(&self.handle)
+    }
+
+    /// Enables the CPU rate control of the job, or disables it if `control` is [`None`].
+    /// See [`job_objects::set_cpu_rate_control`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn set_cpu_rate_control(&self, control: Option<CpuRateControl>) -> Result<()> {
+        job_objects::set_cpu_rate_control(&self.handle, control)
+    }
+
+    /// Gets the accounting information and the I/O counters of the job.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn accounting(&self) -> Result<JobAccounting> {
+        job_objects::get_information::<JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION, RIGHTS>(
+            &self.handle,
+        )
+        .map(JobAccounting::from)
+    }
+
+    /// Creates a completion port that receives the notifications of the job.
+    /// See [`job_objects::associate_completion_port`].
+    ///
+    /// # Remarks
+    ///
+    /// * A job can only be associated with a single completion port, so this method succeeds only once per job.
+    /// * The notifications are queued from the moment the port is associated, so call it before assigning processes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the port cannot be created or associated.
+    ///
+    pub fn notifications(&self) -> Result<JobNotifications> {
+        let port = io::create_io_completion_port(1)?;
+        job_objects::associate_completion_port(&self.handle, port.as_handle(), 0)?;
+        Ok(JobNotifications { port })
+    }
+}
+
+#[derive(Debug)]
+/// A completion port that receives the notifications of a job. See [`Job::notifications`].
+pub struct JobNotifications {
+    /// The owned handle of the completion port.
+    port: OwnedHandle,
+}
+
+impl JobNotifications {
+    /// Waits for the next notification of the job, or until `timeout` elapses. See [`job_objects::get_notification`].
+    /// The result is [`None`] if `timeout` elapsed.
+    ///
+    /// # Remarks
+    ///
+    /// * [`None`] as `timeout` waits indefinitely. A zero `timeout` only checks for a queued notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn next(&self, timeout: Option<Duration>) -> Result<Option<JobNotification>> {
+        Ok(
+            job_objects::get_notification(self.port.as_handle(), timeout)?
+                .map(|(_, notification)| notification),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use win_api_wrapper::win32::system::job_objects::{
+        JOB_OBJECT_LIMIT_AFFINITY, JOB_OBJECT_LIMIT_PRIORITY_CLASS,
+    };
+
+    /// Creates a zeroed [`JOBOBJECT_EXTENDED_LIMIT_INFORMATION`] with the specified limit flags.
+    fn information(flags: JOB_OBJECT_LIMIT) -> JOBOBJECT_EXTENDED_LIMIT_INFORMATION {
+        // Safety: Every field of the structure is an integer.
+        let mut information: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+        information.BasicLimitInformation.LimitFlags = flags;
+        information
+    }
+
+    #[test]
+    fn limits_round_trip() {
+        let limits = JobLimits {
+            process_memory: Some(1 << 20),
+            job_memory: Some(1 << 30),
+            active_processes: Some(3),
+            kill_on_job_close: true,
+            die_on_unhandled_exception: true,
+            breakaway_ok: true,
+        };
+        let mut information = information(0);
+
+        limits.apply(&mut information);
+
+        assert_eq!(information.BasicLimitInformation.LimitFlags, MANAGED_LIMITS);
+        assert_eq!(JobLimits::from_information(&information), limits);
+    }
+
+    #[test]
+    fn from_information_ignores_values_without_flags() {
+        let mut information = information(JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE);
+        information.ProcessMemoryLimit = 1 << 20;
+        information.JobMemoryLimit = 1 << 30;
+        information.BasicLimitInformation.ActiveProcessLimit = 3;
+
+        assert_eq!(
+            JobLimits::from_information(&information),
+            JobLimits {
+                kill_on_job_close: true,
+                ..JobLimits::default()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_preserves_the_unmanaged_limits() {
+        let mut information = information(
+            MANAGED_LIMITS | JOB_OBJECT_LIMIT_AFFINITY | JOB_OBJECT_LIMIT_PRIORITY_CLASS,
+        );
+        information.BasicLimitInformation.Affinity = 0b101;
+        information.BasicLimitInformation.PriorityClass = 0x40;
+
+        JobLimits {
+            active_processes: Some(2),
+            ..JobLimits::default()
+        }
+        .apply(&mut information);
+
+        assert_eq!(
+            information.BasicLimitInformation.LimitFlags,
+            JOB_OBJECT_LIMIT_ACTIVE_PROCESS
+                | JOB_OBJECT_LIMIT_AFFINITY
+                | JOB_OBJECT_LIMIT_PRIORITY_CLASS
+        );
+        assert_eq!(information.BasicLimitInformation.Affinity, 0b101);
+        assert_eq!(information.BasicLimitInformation.PriorityClass, 0x40);
+        assert_eq!(information.BasicLimitInformation.ActiveProcessLimit, 2);
+    }
+
+    #[test]
+    fn ticks_to_duration_converts_100ns_intervals() {
+        assert_eq!(ticks_to_duration(0), Duration::ZERO);
+        assert_eq!(ticks_to_duration(1), Duration::from_nanos(100));
+        assert_eq!(ticks_to_duration(10_000_000), Duration::from_secs(1));
+        assert_eq!(ticks_to_duration(25_000_001), Duration::new(2, 500_000_100));
+        assert_eq!(
+            ticks_to_duration(i64::MAX),
+            Duration::new(922_337_203_685, 477_580_700)
+        );
+        // Negative times are clamped to zero.
+        assert_eq!(ticks_to_duration(-1), Duration::ZERO);
+    }
+}
@@ -1,4 +1,8 @@
 //! A library that offers an ergonomic interface for interacting with Windows API.
+//!
+//! # Features
+//!
+//! * `async`: Enables `Process::wait_exit`, which waits for a process to exit without blocking a thread.
 
 #![warn(
     clippy::await_holding_lock,
@@ -10,7 +14,7 @@
     clippy::debug_assert_with_mut_call,
     clippy::doc_link_with_quotes,
     clippy::doc_markdown,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::empty_line_after_outer_attr,
     clippy::empty_structs_with_brackets,
     clippy::enum_glob_use,
@@ -42,12 +46,10 @@
     clippy::map_err_ignore,
     clippy::map_flatten,
     clippy::map_unwrap_or,
-    clippy::match_on_vec_items,
     clippy::match_same_arms,
     clippy::match_wild_err_arm,
     clippy::match_wildcard_for_single_variants,
     clippy::mem_forget,
-    clippy::mismatched_target_os,
     clippy::missing_const_for_fn,
     clippy::missing_docs_in_private_items,
     clippy::missing_enforced_import_renames,
@@ -78,7 +80,6 @@
     clippy::string_add_assign,
     clippy::string_add,
     clippy::string_lit_as_bytes,
-    clippy::string_to_string,
     clippy::suspicious_operation_groupings,
     clippy::todo,
     clippy::trait_duplication_in_bounds,
@@ -107,5 +108,10 @@
     nonstandard_style
 )]
 
+/// High-level job objects that own their handle.
+pub mod job;
+/// High-level process objects that own their handle.
+pub mod process;
+
 /// TODO: remove re-export
 pub use win_api_wrapper;
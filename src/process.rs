@@ -0,0 +1,806 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use win_api_wrapper::common::path::eq_ignore_case;
+use win_api_wrapper::common::pod::Pod;
+use win_api_wrapper::common::wide::{IntoWideCStr, U16String};
+use win_api_wrapper::common::To;
+use win_api_wrapper::win32::core::{Result, Win32Error};
+use win_api_wrapper::win32::foundation::{
+    BorrowedHandle, ERROR_INVALID_PARAMETER, ERROR_NOT_FOUND,
+};
+use win_api_wrapper::win32::security::{TokenHandle, TOKEN_ACCESS_MASK};
+use win_api_wrapper::win32::system::diagnostics::debug::process_memory::{self, RemotePtr};
+use win_api_wrapper::win32::system::diagnostics::toolhelp::{
+    self, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
+use win_api_wrapper::win32::system::environment::EnvironmentBlock;
+use win_api_wrapper::win32::system::memory::map::{self, MemoryMap, Regions};
+use win_api_wrapper::win32::system::process_status::{self, MemoryCounters, ModuleFilter, Modules};
+use win_api_wrapper::win32::system::threading::process::parameters;
+#[cfg(feature = "async")]
+use win_api_wrapper::win32::system::threading::process::STILL_ACTIVE;
+use win_api_wrapper::win32::system::threading::process::{
+    self, ProcessAccessRights, ProcessHandle, ProcessInformation, IO_COUNTERS, PROCESS_ALL_ACCESS,
+    PROCESS_CREATION_FLAGS,
+};
+#[cfg(feature = "async")]
+use win_api_wrapper::win32::system::threading::wait::future::WaitFuture;
+use win_api_wrapper::win32::system::threading::wait::WaitOutcome;
+use win_api_wrapper::win32::system::time::FILETIME;
+
+/// Process creation with [`ProcessBuilder`][`builder::ProcessBuilder`].
+pub mod builder;
+/// Process trees built from snapshots with [`ProcessTree`][`tree::ProcessTree`].
+pub mod tree;
+
+/// The number of seconds between the Windows epoch (1601-01-01) and the Unix epoch (1970-01-01).
+const WINDOWS_TO_UNIX_EPOCH_SECS: u64 = 11_644_473_600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The identifier of a process.
+///
+/// # Remarks
+///
+/// * Process identifiers are reused by the system after the process exits and every handle to it is closed.
+///   Use [`ProcessIdentity`] to refer to a specific process over a longer period of time.
+///
+pub struct ProcessId(pub u32);
+
+impl ProcessId {
+    #[inline]
+    /// Gets the identifier of the calling process.
+    pub fn current() -> Self {
+        Self(process::get_current_id())
+    }
+
+    #[inline]
+    /// Gets the raw value of the identifier.
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+
+    /// Gets the identifier of every running process whose executable file name (e.g. `notepad.exe`) is `name`.
+    /// The name is compared ignoring the case.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the process snapshot cannot be taken or `name` contains a null character.
+    ///
+    pub fn find_by_name<N: IntoWideCStr + ?Sized>(name: &N) -> Result<Vec<Self>> {
+        let name = name.to_wide_c_str()?;
+        let snapshot = toolhelp::create_snapshot(TH32CS_SNAPPROCESS, 0)?;
+        let mut pids = Vec::new();
+        let mut entry = toolhelp::first_process(&snapshot)?;
+        while let Some(process) = entry {
+            if eq_ignore_case(exe_file(&process), name.as_slice()) {
+                pids.push(Self(process.th32ProcessID));
+            }
+            entry = toolhelp::next_process(&snapshot)?;
+        }
+
+        Ok(pids)
+    }
+}
+
+impl From<u32> for ProcessId {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ProcessId> for u32 {
+    #[inline]
+    fn from(pid: ProcessId) -> Self {
+        pid.0
+    }
+}
+
+impl fmt::Display for ProcessId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Gets the executable file name of a process snapshot entry without the terminating null characters.
+fn exe_file(entry: &PROCESSENTRY32W) -> &[u16] {
+    let len = entry
+        .szExeFile
+        .iter()
+        .position(|&unit| unit == 0)
+        .unwrap_or(entry.szExeFile.len());
+    &entry.szExeFile[..len]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The identity of a process: its [`ProcessId`] and its creation time.
+///
+/// # Remarks
+///
+/// * A process identifier alone can refer to a different process after the original one exits,
+///   but a process identifier and a creation time together cannot.
+/// * The identity is cheap to copy and store, and it doesn't keep the process object alive.
+///
+pub struct ProcessIdentity {
+    /// The identifier of the process.
+    pid: ProcessId,
+    /// The creation time of the process in 100-nanosecond intervals since 1601-01-01 (UTC).
+    creation_time: u64,
+}
+
+impl ProcessIdentity {
+    #[inline]
+    /// Creates a new [`ProcessIdentity`] from a process identifier and a creation time
+    /// in 100-nanosecond intervals since 1601-01-01 (UTC) (e.g. from a snapshot taken earlier).
+    pub const fn new(pid: ProcessId, creation_time: u64) -> Self {
+        Self { pid, creation_time }
+    }
+
+    #[inline]
+    /// Gets the identifier of the process.
+    pub const fn pid(&self) -> ProcessId {
+        self.pid
+    }
+
+    #[inline]
+    /// Gets the creation time of the process in 100-nanosecond intervals since 1601-01-01 (UTC).
+    pub const fn creation_time(&self) -> u64 {
+        self.creation_time
+    }
+
+    /// Opens the process if it is still the same process that the identity was taken from.
+    ///
+    /// # Remarks
+    ///
+    /// * `RIGHTS` must include [`PROCESS_QUERY_LIMITED_INFORMATION`][`win_api_wrapper::win32::system::threading::process::PROCESS_QUERY_LIMITED_INFORMATION`],
+    ///   because the creation time of the opened process is compared to the identity.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the process cannot be opened.
+    ///
+    /// ## Possible errors
+    ///
+    /// * The process has exited and its identifier is not in use. ([`ErrorKind::InvalidParameter`][`win_api_wrapper::win32::core::ErrorKind::InvalidParameter`])
+    /// * The identifier was reused by a different process. ([`ErrorKind::InvalidParameter`][`win_api_wrapper::win32::core::ErrorKind::InvalidParameter`])
+    /// * The caller is not allowed to open the process with the `RIGHTS` access rights. ([`ErrorKind::PermissionDenied`][`win_api_wrapper::win32::core::ErrorKind::PermissionDenied`])
+    ///
+    pub fn open<const RIGHTS: ProcessAccessRights>(&self) -> Result<Process<RIGHTS>> {
+        let process = Process::<RIGHTS>::open(self.pid)?;
+        if process.identity()? == *self {
+            Ok(process)
+        } else {
+            Err(Win32Error::new(ERROR_INVALID_PARAMETER)
+                .with_process_id(self.pid.0)
+                .with_context("the process identifier was reused by a different process"))
+        }
+    }
+
+    /// Determines whether `process` is the process that the identity was taken from.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the identity of `process` cannot be retrieved.
+    ///
+    pub fn matches<const R: ProcessAccessRights>(&self, process: &Process<R>) -> Result<bool> {
+        Ok(process.identity()? == *self)
+    }
+}
+
+impl fmt::Display for ProcessIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{:#x}", self.pid, self.creation_time)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The timing information of a process. See [`Process::times`].
+pub struct ProcessTimes {
+    /// The creation time of the process.
+    pub creation_time: SystemTime,
+    /// The exit time of the process, or [`None`] if the process is still running.
+    pub exit_time: Option<SystemTime>,
+    /// The amount of time that the process has executed in kernel mode.
+    pub kernel_time: Duration,
+    /// The amount of time that the process has executed in user mode.
+    pub user_time: Duration,
+}
+
+/// Converts a [`FILETIME`] to the number of 100-nanosecond intervals since 1601-01-01 (UTC).
+const fn file_time_to_u64(file_time: FILETIME) -> u64 {
+    (file_time.dwHighDateTime as u64) << 32 | file_time.dwLowDateTime as u64
+}
+
+/// Converts a [`FILETIME`] that contains a point in time to a [`SystemTime`].
+fn file_time_to_system_time(file_time: FILETIME) -> SystemTime {
+    let since_windows_epoch: Duration = file_time.to();
+    let windows_epoch = Duration::from_secs(WINDOWS_TO_UNIX_EPOCH_SECS);
+    since_windows_epoch.checked_sub(windows_epoch).map_or_else(
+        || UNIX_EPOCH - (windows_epoch - since_windows_epoch),
+        |since_unix_epoch| UNIX_EPOCH + since_unix_epoch,
+    )
+}
+
+#[derive(Debug)]
+/// An open process that has the `RIGHTS` access rights.
+///
+/// # Remarks
+///
+/// * The methods call the corresponding functions of [`process`] with the owned handle.
+/// * Calling a method that requires access rights which are not included in `RIGHTS` fails to compile.
+/// * The handle of the process is closed when the [`Process`] is dropped.
+///
+pub struct Process<const RIGHTS: ProcessAccessRights = PROCESS_ALL_ACCESS> {
+    /// The owned handle of the process.
+    handle: ProcessHandle<RIGHTS>,
+}
+
+impl Process {
+    #[inline]
+    /// Gets the calling process. See [`process::get_current_handle`].
+    pub fn current() -> Self {
+        Self {
+            handle: process::get_current_handle(),
+        }
+    }
+}
+
+impl<const RIGHTS: ProcessAccessRights> Process<RIGHTS> {
+    /// Opens an existing process. See [`process::open`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the process cannot be opened.
+    ///
+    /// ## Possible errors
+    ///
+    /// * The process doesn't exist. ([`ErrorKind::InvalidParameter`][`win_api_wrapper::win32::core::ErrorKind::InvalidParameter`])
+    /// * The caller is not allowed to open the process with the `RIGHTS` access rights. ([`ErrorKind::PermissionDenied`][`win_api_wrapper::win32::core::ErrorKind::PermissionDenied`])
+    ///
+    pub fn open<P: Into<ProcessId>>(pid: P) -> Result<Self> {
+        process::open::<RIGHTS>(pid.into().0, false).map(Self::from_handle)
+    }
+
+    /// Opens the first running process whose executable file name (e.g. `notepad.exe`) is `name`.
+    /// See [`ProcessId::find_by_name`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the process cannot be found or opened.
+    ///
+    /// ## Possible errors
+    ///
+    /// * There is no running process with the specified name. ([`ErrorKind::NotFound`][`win_api_wrapper::win32::core::ErrorKind::NotFound`])
+    /// * The caller is not allowed to open the process with the `RIGHTS` access rights. ([`ErrorKind::PermissionDenied`][`win_api_wrapper::win32::core::ErrorKind::PermissionDenied`])
+    ///
+    pub fn open_by_name<N: IntoWideCStr + ?Sized>(name: &N) -> Result<Self> {
+        let pid = ProcessId::find_by_name(name)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Win32Error::new(ERROR_NOT_FOUND).with_context("no process with the specified name")
+            })?;
+        Self::open(pid)
+    }
+
+    #[inline]
+    /// Creates a new [`Process`] that takes the ownership of `handle`.
+    pub const fn from_handle(handle: ProcessHandle<RIGHTS>) -> Self {
+        Self { handle }
+    }
+
+    #[inline]
+    /// Gets the owned handle of the process.
+    pub const fn handle(&self) -> &ProcessHandle<RIGHTS> {
+        &self.handle
+    }
+
+    #[inline]
+    /// Borrows the handle of the process.
+    pub const fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.handle.as_handle()
+    }
+
+    #[inline]
+    #[allow(clippy::missing_const_for_fn)]
+    /// Consumes the [`Process`] and returns its owned handle.
+    pub fn into_handle(self) -> ProcessHandle<RIGHTS> {
+        self.handle
+    }
+
+    /// Closes the handle of the process and reports the error that is ignored on drop.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the handle cannot be closed.
+    ///
+    pub fn close(self) -> Result<()> {
+        self.handle.into_owned().close()
+    }
+
+    #[inline]
+    /// Converts the process to a process that has a subset of its access rights.
+    /// See [`ObjectHandle::restrict`][`win_api_wrapper::win32::foundation::ObjectHandle::restrict`].
+    pub fn restrict<const RESTRICTED: ProcessAccessRights>(self) -> Process<RESTRICTED> {
+        Process::from_handle(self.handle.restrict())
+    }
+
+    /// Gets the identity of the process. See [`ProcessIdentity`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the identifier or the creation time cannot be retrieved.
+    ///
+    pub fn identity(&self) -> Result<ProcessIdentity> {
+        let pid = self.id()?;
+        let [creation_time, ..] = process::get_times(&self.handle)?;
+        Ok(ProcessIdentity::new(pid, file_time_to_u64(creation_time)))
+    }
+
+    /// Gets the identifier of the process. See [`process::get_id`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn id(&self) -> Result<ProcessId> {
+        process::get_id(&self.handle).map(ProcessId)
+    }
+
+    /// Gets the full name of the executable image of the process. See [`process::get_full_image_name`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn image_name(&self, use_win32_path_format: bool) -> Result<U16String> {
+        process::get_full_image_name(&self.handle, use_win32_path_format)
+    }
+
+    /// Gets the command line of the process. See [`parameters::get_command_line`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn command_line(&self) -> Result<U16String> {
+        parameters::get_command_line(&self.handle)
+    }
+
+    /// Gets the current directory of the process. See [`parameters::get_current_directory`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn current_directory(&self) -> Result<U16String> {
+        parameters::get_current_directory(&self.handle)
+    }
+
+    /// Gets the environment variables of the process. See [`parameters::get_environment`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn environment(&self) -> Result<EnvironmentBlock> {
+        parameters::get_environment(&self.handle)
+    }
+
+    /// Determines whether the process is elevated. See [`process::is_elevated`].
+    pub fn is_elevated(&self) -> bool {
+        process::is_elevated(&self.handle)
+    }
+
+    /// Opens the access token of the process. See [`process::open_token`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn open_token<const TOKEN_RIGHTS: TOKEN_ACCESS_MASK>(
+        &self,
+    ) -> Result<TokenHandle<TOKEN_RIGHTS>> {
+        process::open_token::<TOKEN_RIGHTS, RIGHTS>(&self.handle)
+    }
+
+    /// Gets the process affinity mask and the system affinity mask. See [`process::get_affinity_mask`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn affinity(&self) -> Result<(usize, usize)> {
+        process::get_affinity_mask(&self.handle)
+    }
+
+    /// Sets the processor affinity mask of the threads of the process. See [`process::set_affinity_mask`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn set_affinity(&self, mask: usize) -> Result<()> {
+        process::set_affinity_mask(&self.handle, mask)
+    }
+
+    /// Gets the processor group affinity of the process. See [`process::get_group_affinity`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn group_affinity(&self) -> Result<Box<[u16]>> {
+        process::get_group_affinity(&self.handle)
+    }
+
+    /// Gets the default CPU Sets of the process. See [`process::get_default_cpu_sets`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn default_cpu_sets(&self) -> Result<Option<Box<[u32]>>> {
+        process::get_default_cpu_sets(&self.handle)
+    }
+
+    /// Sets the default CPU Sets of the process. See [`process::set_default_cpu_sets`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn set_default_cpu_sets(&self, cpu_sets: &[u32]) -> Result<()> {
+        process::set_default_cpu_sets(&self.handle, cpu_sets)
+    }
+
+    /// Removes the default CPU Sets of the process. See [`process::clear_default_cpu_sets`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn clear_default_cpu_sets(&self) -> Result<()> {
+        process::clear_default_cpu_sets(&self.handle)
+    }
+
+    /// Determines whether the process is still running. See [`process::is_running`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn is_running(&self) -> Result<bool> {
+        process::is_running(&self.handle)
+    }
+
+    /// Gets the exit code of the process, or [`None`] if it is still running. See [`process::get_exit_code`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn exit_code(&self) -> Result<Option<u32>> {
+        process::get_exit_code(&self.handle)
+    }
+
+    #[cfg(feature = "async")]
+    /// Waits asynchronously until the process exits and returns its exit code.
+    /// See [`WaitFuture`][`win_api_wrapper::win32::system::threading::wait::future::WaitFuture`].
+    ///
+    /// # Remarks
+    ///
+    /// * No thread is blocked while waiting, so it can be awaited on any executor (e.g. `tokio`).
+    /// * Dropping the returned future cancels the wait.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the wait cannot be registered or the exit code cannot be retrieved.
+    ///
+    pub async fn wait_exit(&self) -> Result<u32> {
+        let handle = self
+            .handle
+            .require_rights::<{ process::PROCESS_SYNCHRONIZE }>();
+        WaitFuture::new(handle, None)?.await;
+        // The process has exited, so the only exit code that is reported as still running is `STILL_ACTIVE` itself.
+        Ok(self.exit_code()?.unwrap_or(STILL_ACTIVE as u32))
+    }
+
+    /// Gets the number of open handles of the process. See [`process::get_handle_count`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn handle_count(&self) -> Result<u32> {
+        process::get_handle_count(&self.handle)
+    }
+
+    /// Determines whether the process is critical. See [`process::is_critical`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn is_critical(&self) -> Result<bool> {
+        process::is_critical(&self.handle)
+    }
+
+    /// Gets the I/O counters of the process. See [`process::get_io_counters`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn io_counters(&self) -> Result<IO_COUNTERS> {
+        process::get_io_counters(&self.handle)
+    }
+
+    /// Determines whether the dynamic priority boost is enabled for the process. See [`process::has_priority_boost`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn has_priority_boost(&self) -> Result<bool> {
+        process::has_priority_boost(&self.handle)
+    }
+
+    /// Enables or disables the dynamic priority boost of the process. See [`process::set_priority_boost`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn set_priority_boost(&self, enable: bool) -> Result<()> {
+        process::set_priority_boost(&self.handle, enable)
+    }
+
+    /// Gets the priority class of the process. See [`process::get_priority_class`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn priority_class(&self) -> Result<PROCESS_CREATION_FLAGS> {
+        process::get_priority_class(&self.handle)
+    }
+
+    /// Sets the priority class of the process. See [`process::set_priority_class`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn set_priority_class(&self, class: PROCESS_CREATION_FLAGS) -> Result<()> {
+        process::set_priority_class(&self.handle, class)
+    }
+
+    /// Gets the timing information of the process. See [`process::get_times`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn times(&self) -> Result<ProcessTimes> {
+        let [creation_time, exit_time, kernel_time, user_time] = process::get_times(&self.handle)?;
+        let is_running = file_time_to_u64(exit_time) == 0;
+        Ok(ProcessTimes {
+            creation_time: file_time_to_system_time(creation_time),
+            exit_time: (!is_running).then(|| file_time_to_system_time(exit_time)),
+            kernel_time: kernel_time.to(),
+            user_time: user_time.to(),
+        })
+    }
+
+    /// Gets the major and minor version of the system that the process expects. See [`process::get_version`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn version(&self) -> Result<(u16, u16)> {
+        process::get_version(self.id()?.0)
+    }
+
+    /// Gets the minimum and maximum working set sizes of the process in bytes. See [`process::get_working_set_size`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn working_set_size(&self) -> Result<(usize, usize)> {
+        process::get_working_set_size(&self.handle)
+    }
+
+    /// Gets the memory usage of the process, e.g. its private bytes and its current and peak working set sizes.
+    /// See [`process_status::get_memory_counters`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn memory_counters(&self) -> Result<MemoryCounters> {
+        process_status::get_memory_counters(&self.handle)
+    }
+
+    /// Sets the minimum and maximum working set sizes of the process in bytes. See [`process::set_working_set_size`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn set_working_set_size(&self, min_size: usize, max_size: usize) -> Result<()> {
+        process::set_working_set_size(&self.handle, min_size, max_size)
+    }
+
+    /// Removes as many pages as possible from the working set of the process. See [`process::shrink_working_set`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn shrink_working_set(&self) -> Result<()> {
+        process::shrink_working_set(&self.handle)
+    }
+
+    /// Gets information about the process. See [`process::get_information`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn information<T: Copy + ProcessInformation>(&self) -> Result<T> {
+        process::get_information(&self.handle)
+    }
+
+    /// Sets information of the process. See [`process::set_information`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn set_information<T: Copy + ProcessInformation>(&self, information: T) -> Result<()> {
+        process::set_information(&self.handle, information)
+    }
+
+    /// Waits until the process is waiting for user input with no input pending, or until `timeout` elapses.
+    /// See [`process::wait_for_input_idle`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn wait_for_input_idle(&self, timeout: Option<Duration>) -> Result<WaitOutcome> {
+        process::wait_for_input_idle(&self.handle, timeout)
+    }
+
+    /// Gets an iterator over the memory regions of the process, including the free ones. See [`map::regions`].
+    pub const fn memory_regions(&self) -> Regions<'_, RIGHTS> {
+        map::regions(&self.handle)
+    }
+
+    /// Queries the memory map of the process. See [`MemoryMap::query`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if a memory region cannot be queried.
+    ///
+    pub fn memory_map(&self) -> Result<MemoryMap> {
+        MemoryMap::query(&self.handle)
+    }
+
+    /// Enumerates the modules that are loaded into the process and match `filter`. See [`process_status::modules`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the modules cannot be enumerated.
+    ///
+    pub fn modules(&self, filter: ModuleFilter) -> Result<Modules<'_, RIGHTS>> {
+        process_status::modules(&self.handle, filter)
+    }
+
+    /// Reads `buffer.len()` values starting at `address` in the process. See [`process_memory::read`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn read_memory<T: Pod>(&self, address: RemotePtr<T>, buffer: &mut [T]) -> Result<()> {
+        process_memory::read(&self.handle, address, buffer)
+    }
+
+    /// Reads the readable beginning of `buffer.len()` values starting at `address` in the process,
+    /// and returns the number of bytes that were read. See [`process_memory::read_partial`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails for any reason other than a partial copy.
+    ///
+    pub fn read_memory_partial<T: Pod>(
+        &self,
+        address: RemotePtr<T>,
+        buffer: &mut [T],
+    ) -> Result<usize> {
+        process_memory::read_partial(&self.handle, address, buffer)
+    }
+
+    /// Reads up to `len` values starting at `address` in the process. See [`process_memory::read_vec`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn read_memory_vec<T: Pod>(&self, address: RemotePtr<T>, len: usize) -> Result<Vec<T>> {
+        process_memory::read_vec(&self.handle, address, len)
+    }
+
+    /// Reads the value at `address` in the process. See [`process_memory::read_value`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn read_value<T: Pod>(&self, address: RemotePtr<T>) -> Result<T> {
+        process_memory::read_value(&self.handle, address)
+    }
+
+    /// Writes `values` starting at `address` in the process. See [`process_memory::write`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn write_memory<T: Pod>(&self, address: RemotePtr<T>, values: &[T]) -> Result<()> {
+        process_memory::write(&self.handle, address, values)
+    }
+
+    /// Writes the writable beginning of `values` starting at `address` in the process,
+    /// and returns the number of bytes that were written. See [`process_memory::write_partial`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails for any reason other than a partial copy.
+    ///
+    pub fn write_memory_partial<T: Pod>(
+        &self,
+        address: RemotePtr<T>,
+        values: &[T],
+    ) -> Result<usize> {
+        process_memory::write_partial(&self.handle, address, values)
+    }
+
+    /// Writes `value` at `address` in the process. See [`process_memory::write_value`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn write_value<T: Pod>(&self, address: RemotePtr<T>, value: T) -> Result<()> {
+        process_memory::write_value(&self.handle, address, value)
+    }
+
+    /// Terminates the process and all of its threads. See [`process::terminate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`] if the function fails.
+    ///
+    pub fn terminate(&self, exit_code: u32) -> Result<()> {
+        process::terminate(&self.handle, exit_code)
+    }
+}
+
+impl<const RIGHTS: ProcessAccessRights> From<ProcessHandle<RIGHTS>> for Process<RIGHTS> {
+    #[inline]
+    fn from(handle: ProcessHandle<RIGHTS>) -> Self {
+        Self::from_handle(handle)
+    }
+}
+
+impl<const RIGHTS: ProcessAccessRights> From<Process<RIGHTS>> for ProcessHandle<RIGHTS> {
+    #[inline]
+    fn from(process: Process<RIGHTS>) -> Self {
+        process.into_handle()
+    }
+}
@@ -0,0 +1,358 @@
+use super::{Process, ProcessId};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use win_api_wrapper::common::command_line;
+use win_api_wrapper::common::wide::{IntoWideCStr, U16CString};
+use win_api_wrapper::win32::core::Result;
+use win_api_wrapper::win32::foundation::BorrowedHandle;
+use win_api_wrapper::win32::system::console::{
+    self, STD_ERROR_HANDLE, STD_INPUT_HANDLE, STD_OUTPUT_HANDLE,
+};
+use win_api_wrapper::win32::system::environment::{self, EnvironmentBlock};
+use win_api_wrapper::win32::system::threading::process::{
+    self, StdHandles, CREATE_NEW_CONSOLE, CREATE_SUSPENDED, PROCESS_CREATION_FLAGS,
+};
+use win_api_wrapper::win32::system::threading::thread::{self, ThreadHandle};
+
+/// The priority class flags that are mutually exclusive in the creation flags.
+const PRIORITY_CLASS_MASK: PROCESS_CREATION_FLAGS = process::IDLE_PRIORITY_CLASS
+    | process::BELOW_NORMAL_PRIORITY_CLASS
+    | process::NORMAL_PRIORITY_CLASS
+    | process::ABOVE_NORMAL_PRIORITY_CLASS
+    | process::HIGH_PRIORITY_CLASS
+    | process::REALTIME_PRIORITY_CLASS;
+
+#[derive(Debug, Clone)]
+/// A builder that configures and creates a new process. See [`process::create`].
+///
+/// # Remarks
+///
+/// * Unlike [`std::process::Command`], the created [`Child`] keeps the handle of the primary thread,
+///   so a process created with [`ProcessBuilder::suspended`] can be resumed.
+/// * The standard handles are not redirected by default. The new process inherits the console of the calling process
+///   unless [`ProcessBuilder::new_console`] is set.
+///
+pub struct ProcessBuilder<'handle> {
+    /// The program that is the first argument of the command line.
+    program: OsString,
+    /// The path of the executable that is passed as the application name.
+    application_name: Option<PathBuf>,
+    /// The arguments that follow the program in the command line.
+    args: Vec<OsString>,
+    /// The current directory of the new process.
+    current_dir: Option<PathBuf>,
+    /// Determines whether the environment of the calling process is discarded.
+    env_clear: bool,
+    /// The environment variables that are set ([`Some`]) or removed ([`None`]) in order.
+    env_changes: Vec<(OsString, Option<OsString>)>,
+    /// The process creation flags.
+    creation_flags: PROCESS_CREATION_FLAGS,
+    /// Determines whether the inheritable handles are inherited.
+    inherit_handles: bool,
+    /// The redirected standard handles.
+    std_handles: StdHandles<'handle>,
+}
+
+impl<'handle> ProcessBuilder<'handle> {
+    /// Creates a new [`ProcessBuilder`] that starts `program`.
+    ///
+    /// # Remarks
+    ///
+    /// * `program` is the first argument of the command line and it is searched like `CreateProcessW` does
+    ///   (e.g. in the directory of the calling executable, the system directories and the `PATH`),
+    ///   unless the application name is set using [`ProcessBuilder::application_name`].
+    ///
+    pub fn new<S: AsRef<OsStr>>(program: S) -> Self {
+        Self {
+            program: program.as_ref().to_owned(),
+            application_name: None,
+            args: Vec::new(),
+            current_dir: None,
+            env_clear: false,
+            env_changes: Vec::new(),
+            creation_flags: 0,
+            inherit_handles: false,
+            std_handles: StdHandles::default(),
+        }
+    }
+
+    /// Sets the path of the executable, so the program is not searched.
+    /// The program is still passed as the first argument of the command line.
+    pub fn application_name<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.application_name = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Appends an argument to the command line. The argument is quoted if necessary. See [`command_line::quote_arg`].
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    /// Appends multiple arguments to the command line. See [`ProcessBuilder::arg`].
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_owned()));
+        self
+    }
+
+    /// Sets the current directory of the new process.
+    pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        self.current_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    /// Sets an environment variable of the new process. The name is compared ignoring the case.
+    pub fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(&mut self, key: K, value: V) -> &mut Self {
+        self.env_changes
+            .push((key.as_ref().to_owned(), Some(value.as_ref().to_owned())));
+        self
+    }
+
+    /// Removes an environment variable from the environment of the new process. The name is compared ignoring the case.
+    pub fn env_remove<K: AsRef<OsStr>>(&mut self, key: K) -> &mut Self {
+        self.env_changes.push((key.as_ref().to_owned(), None));
+        self
+    }
+
+    /// Discards the inherited environment, so the new process only has the variables set by [`ProcessBuilder::env`].
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env_clear = true;
+        self.env_changes.clear();
+        self
+    }
+
+    /// Adds process creation flags (e.g. [`CREATE_NO_WINDOW`][`win_api_wrapper::win32::system::threading::process::CREATE_NO_WINDOW`]).
+    pub const fn creation_flags(&mut self, flags: PROCESS_CREATION_FLAGS) -> &mut Self {
+        self.creation_flags |= flags;
+        self
+    }
+
+    /// Determines whether the primary thread of the new process is created suspended ([`CREATE_SUSPENDED`]).
+    /// Use [`Child::resume`] to start it.
+    pub const fn suspended(&mut self, suspended: bool) -> &mut Self {
+        self.set_flag(CREATE_SUSPENDED, suspended)
+    }
+
+    /// Determines whether the new process gets a new console instead of inheriting the console of the calling process ([`CREATE_NEW_CONSOLE`]).
+    pub const fn new_console(&mut self, new_console: bool) -> &mut Self {
+        self.set_flag(CREATE_NEW_CONSOLE, new_console)
+    }
+
+    /// Sets the priority class of the new process (e.g. [`IDLE_PRIORITY_CLASS`][`win_api_wrapper::win32::system::threading::process::IDLE_PRIORITY_CLASS`]).
+    /// The previously set priority class is replaced.
+    pub const fn priority_class(&mut self, class: PROCESS_CREATION_FLAGS) -> &mut Self {
+        self.creation_flags = self.creation_flags & !PRIORITY_CLASS_MASK | class;
+        self
+    }
+
+    /// Determines whether the inheritable handles of the calling process are inherited by the new process.
+    pub const fn inherit_handles(&mut self, inherit_handles: bool) -> &mut Self {
+        self.inherit_handles = inherit_handles;
+        self
+    }
+
+    /// Redirects the standard input of the new process to `handle`. See [`ProcessBuilder::stdout`].
+    pub const fn stdin(&mut self, handle: BorrowedHandle<'handle>) -> &mut Self {
+        self.std_handles.input = Some(handle);
+        self
+    }
+
+    /// Redirects the standard output of the new process to `handle`.
+    ///
+    /// # Remarks
+    ///
+    /// * `handle` must be inheritable. Handle inheritance is enabled when any standard handle is redirected.
+    /// * The standard handles that are not redirected are set to the standard handles of the calling process.
+    ///
+    pub const fn stdout(&mut self, handle: BorrowedHandle<'handle>) -> &mut Self {
+        self.std_handles.output = Some(handle);
+        self
+    }
+
+    /// Redirects the standard error of the new process to `handle`. See [`ProcessBuilder::stdout`].
+    pub const fn stderr(&mut self, handle: BorrowedHandle<'handle>) -> &mut Self {
+        self.std_handles.error = Some(handle);
+        self
+    }
+
+    /// Creates the new process.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the process cannot be created.
+    ///
+    /// ## Possible errors
+    ///
+    /// * The program, an argument, an environment variable, the application name or the current directory contains a null character. ([`ErrorKind::InvalidParameter`][`win_api_wrapper::win32::core::ErrorKind::InvalidParameter`])
+    /// * An environment variable name is empty or it contains an equals sign after its first character. ([`ErrorKind::InvalidParameter`][`win_api_wrapper::win32::core::ErrorKind::InvalidParameter`])
+    /// * The program contains a quotation mark. ([`ErrorKind::InvalidParameter`][`win_api_wrapper::win32::core::ErrorKind::InvalidParameter`])
+    /// * The executable doesn't exist. ([`ErrorKind::NotFound`][`win_api_wrapper::win32::core::ErrorKind::NotFound`])
+    ///
+    pub fn spawn(&self) -> Result<Child> {
+        let command_line = self.command_line()?;
+        let environment = self.environment_block()?;
+        let std_handles = self.redirected_std_handles()?;
+
+        let created = process::create(
+            self.application_name.as_deref(),
+            Some(&command_line),
+            self.inherit_handles || std_handles.is_some(),
+            self.creation_flags,
+            environment.as_deref(),
+            self.current_dir.as_deref(),
+            std_handles,
+        )?;
+        Ok(Child {
+            process: Process::from_handle(created.process),
+            thread: created.thread,
+            pid: ProcessId(created.process_id),
+            thread_id: created.thread_id,
+        })
+    }
+
+    /// Adds or removes `flag` from the creation flags.
+    const fn set_flag(&mut self, flag: PROCESS_CREATION_FLAGS, enable: bool) -> &mut Self {
+        if enable {
+            self.creation_flags |= flag;
+        } else {
+            self.creation_flags &= !flag;
+        }
+        self
+    }
+
+    /// Builds the command line from the program and the arguments. See [`command_line::join`].
+    fn command_line(&self) -> Result<U16CString> {
+        let program = self.program.to_wide_c_str()?;
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.to_wide_c_str().map(|arg| arg.as_ustr().to_owned()))
+            .collect::<Result<Vec<_>>>()?;
+        let command_line = command_line::join(program.as_ustr(), args)?;
+        // Safety: The program and the arguments were checked for null characters.
+        Ok(unsafe { U16CString::from_ustr_unchecked(command_line) })
+    }
+
+    /// Builds the environment block of the new process, or [`None`] if the environment is inherited unchanged.
+    fn environment_block(&self) -> Result<Option<Vec<u16>>> {
+        if !self.env_clear && self.env_changes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut environment = if self.env_clear {
+            EnvironmentBlock::new()
+        } else {
+            environment::get_environment_strings()?
+        };
+        for (key, value) in &self.env_changes {
+            let key = key.to_wide_c_str()?;
+            match value {
+                Some(value) => {
+                    environment.set(key.as_ustr(), value.to_wide_c_str()?.as_ustr())?;
+                }
+                None => {
+                    environment.remove(key.as_ustr());
+                }
+            }
+        }
+
+        Ok(Some(environment.to_block()))
+    }
+
+    /// Gets the standard handles of the new process if any of them is redirected.
+    /// The handles that are not redirected are taken from the calling process.
+    fn redirected_std_handles(&self) -> Result<Option<StdHandles<'handle>>> {
+        let StdHandles {
+            input,
+            output,
+            error,
+        } = self.std_handles;
+        if input.is_none() && output.is_none() && error.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(StdHandles {
+            input: input.map_or_else(
+                || console::get_std_handle(STD_INPUT_HANDLE),
+                |h| Ok(Some(h)),
+            )?,
+            output: output.map_or_else(
+                || console::get_std_handle(STD_OUTPUT_HANDLE),
+                |h| Ok(Some(h)),
+            )?,
+            error: error.map_or_else(
+                || console::get_std_handle(STD_ERROR_HANDLE),
+                |h| Ok(Some(h)),
+            )?,
+        }))
+    }
+}
+
+#[derive(Debug)]
+/// A process that was created by [`ProcessBuilder::spawn`] and its primary thread.
+pub struct Child {
+    /// The new process.
+    process: Process,
+    /// The handle of the primary thread.
+    thread: ThreadHandle,
+    /// The identifier of the new process.
+    pid: ProcessId,
+    /// The identifier of the primary thread.
+    thread_id: u32,
+}
+
+impl Child {
+    #[inline]
+    /// Gets the new process.
+    pub const fn process(&self) -> &Process {
+        &self.process
+    }
+
+    #[inline]
+    /// Gets the handle of the primary thread.
+    pub const fn thread(&self) -> &ThreadHandle {
+        &self.thread
+    }
+
+    #[inline]
+    /// Gets the identifier of the new process.
+    pub const fn pid(&self) -> ProcessId {
+        self.pid
+    }
+
+    #[inline]
+    /// Gets the identifier of the primary thread.
+    pub const fn thread_id(&self) -> u32 {
+        self.thread_id
+    }
+
+    /// Resumes the primary thread of a process that was created suspended. See [`thread::resume`].
+    /// The result is the previous suspend count of the thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the function fails.
+    ///
+    pub fn resume(&self) -> Result<u32> {
+        thread::resume(&self.thread)
+    }
+
+    #[inline]
+    #[allow(clippy::missing_const_for_fn)]
+    /// Consumes the [`Child`] and returns the process and the handle of its primary thread.
+    pub fn into_parts(self) -> (Process, ThreadHandle) {
+        (self.process, self.thread)
+    }
+
+    #[inline]
+    #[allow(clippy::missing_const_for_fn)]
+    /// Consumes the [`Child`] and returns the process. The handle of the primary thread is closed.
+    pub fn into_process(self) -> Process {
+        self.process
+    }
+}
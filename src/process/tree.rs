@@ -0,0 +1,412 @@
+use super::{exe_file, file_time_to_u64, ProcessId, ProcessIdentity, WINDOWS_TO_UNIX_EPOCH_SECS};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use win_api_wrapper::common::wide::U16String;
+use win_api_wrapper::win32::core::Result;
+use win_api_wrapper::win32::system::diagnostics::toolhelp::{
+    self, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
+use win_api_wrapper::win32::system::threading::process::{self, PROCESS_QUERY_LIMITED_INFORMATION};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A process in a [`ProcessTree`].
+pub struct ProcessEntry {
+    /// The identifier of the process.
+    pub pid: ProcessId,
+    /// The identifier of the process that created the process. The parent might have exited since then,
+    /// and its identifier might have been reused by a different process.
+    pub parent_pid: ProcessId,
+    /// The creation time of the process in 100-nanosecond intervals since 1601-01-01 (UTC),
+    /// or [`None`] if it is unknown (e.g. the process cannot be opened).
+    pub creation_time: Option<u64>,
+    /// The executable file name of the process (e.g. `notepad.exe`).
+    pub exe_file: U16String,
+}
+
+impl ProcessEntry {
+    #[inline]
+    /// Gets the identity of the process, or [`None`] if its creation time is unknown.
+    pub const fn identity(&self) -> Option<ProcessIdentity> {
+        match self.creation_time {
+            Some(creation_time) => Some(ProcessIdentity::new(self.pid, creation_time)),
+            None => None,
+        }
+    }
+}
+
+impl From<&PROCESSENTRY32W> for ProcessEntry {
+    /// Creates a new [`ProcessEntry`] with an unknown creation time from a process snapshot entry.
+    fn from(entry: &PROCESSENTRY32W) -> Self {
+        Self {
+            pid: ProcessId(entry.th32ProcessID),
+            parent_pid: ProcessId(entry.th32ParentProcessID),
+            creation_time: None,
+            exe_file: U16String::from_vec(exe_file(entry)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A process and its links in a [`ProcessTree`].
+struct Node {
+    /// The process.
+    entry: ProcessEntry,
+    /// The index of the parent node, or [`None`] if the process is a root.
+    parent: Option<usize>,
+    /// The indices of the child nodes in the order of the entries.
+    children: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The processes of a snapshot linked to their parent and child processes.
+///
+/// # Remarks
+///
+/// * A process is linked to the process whose identifier is its parent identifier, unless the parent was created
+///   after the process. In that case the original parent has exited and its identifier was reused,
+///   so the process becomes a root.
+/// * A link is kept if the creation time of either process is unknown, except if it would close a cycle.
+/// * The tree is built purely from [`ProcessEntry`] values by [`ProcessTree::from_entries`],
+///   so it can also be built from entries that were collected earlier.
+///
+pub struct ProcessTree {
+    /// The processes in the order of the entries.
+    nodes: Vec<Node>,
+    /// The index of the node of every process identifier.
+    indices: HashMap<ProcessId, usize>,
+}
+
+impl ProcessTree {
+    /// Takes a snapshot of the running processes and builds a tree from it.
+    ///
+    /// # Remarks
+    ///
+    /// * The creation time of every process is queried with [`process::get_times`].
+    ///   It stays unknown if the process cannot be opened with [`PROCESS_QUERY_LIMITED_INFORMATION`] access right.
+    /// * A process that was created after the snapshot was started either reused the identifier of an exited process
+    ///   from the snapshot, or it was created while the snapshot was taken. In both cases its creation time is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Win32Error`][`win_api_wrapper::win32::core::Win32Error`] if the process snapshot cannot be taken.
+    ///
+    pub fn snapshot() -> Result<Self> {
+        // The time is taken first, so every process that might have reused an identifier after the snapshot is newer.
+        let snapshot_time = system_time_to_u64(SystemTime::now());
+        let snapshot = toolhelp::create_snapshot(TH32CS_SNAPPROCESS, 0)?;
+        let mut entries = Vec::new();
+        let mut entry = toolhelp::first_process(&snapshot)?;
+        while let Some(process) = entry {
+            let mut process_entry = ProcessEntry::from(&process);
+            process_entry.creation_time =
+                creation_time(process_entry.pid).filter(|&time| time <= snapshot_time);
+            entries.push(process_entry);
+            entry = toolhelp::next_process(&snapshot)?;
+        }
+
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Builds a tree from the specified processes.
+    ///
+    /// # Remarks
+    ///
+    /// * If an identifier occurs multiple times, only its first entry is kept.
+    /// * A process that is its own parent (e.g. the System Idle Process) is a root.
+    ///
+    pub fn from_entries<I: IntoIterator<Item = ProcessEntry>>(entries: I) -> Self {
+        let mut tree = Self::default();
+        for entry in entries {
+            if !tree.indices.contains_key(&entry.pid) {
+                tree.indices.insert(entry.pid, tree.nodes.len());
+                tree.nodes.push(Node {
+                    entry,
+                    parent: None,
+                    children: Vec::new(),
+                });
+            }
+        }
+
+        for index in 0..tree.nodes.len() {
+            let entry = &tree.nodes[index].entry;
+            let parent = tree
+                .indices
+                .get(&entry.parent_pid)
+                .copied()
+                .filter(|&parent| parent != index && !is_stale(&tree.nodes[parent].entry, entry));
+            tree.nodes[index].parent = parent;
+        }
+
+        // Links with unknown creation times can form cycles, which are cut at the process that closes them.
+        for index in 0..tree.nodes.len() {
+            let closes_cycle = tree
+                .ancestor_indices(index)
+                .take(tree.nodes.len())
+                .any(|ancestor| ancestor == index);
+            if closes_cycle {
+                tree.nodes[index].parent = None;
+            }
+        }
+
+        for index in 0..tree.nodes.len() {
+            if let Some(parent) = tree.nodes[index].parent {
+                tree.nodes[parent].children.push(index);
+            }
+        }
+
+        tree
+    }
+
+    #[inline]
+    /// Gets the number of processes in the tree.
+    pub const fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    /// Determines whether the tree contains no processes.
+    pub const fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Gets the process with the specified identifier.
+    pub fn get(&self, pid: ProcessId) -> Option<&ProcessEntry> {
+        self.indices
+            .get(&pid)
+            .map(|&index| &self.nodes[index].entry)
+    }
+
+    /// Gets every process in the tree in the order of the entries.
+    pub fn iter(&self) -> impl Iterator<Item = &ProcessEntry> + '_ {
+        self.nodes.iter().map(|node| &node.entry)
+    }
+
+    /// Gets every process that has no parent in the tree, because its parent has exited or it is its own parent.
+    pub fn roots(&self) -> impl Iterator<Item = &ProcessEntry> + '_ {
+        self.nodes
+            .iter()
+            .filter(|node| node.parent.is_none())
+            .map(|node| &node.entry)
+    }
+
+    /// Gets the parent of the specified process, or [`None`] if the process is a root or it is not in the tree.
+    pub fn parent(&self, pid: ProcessId) -> Option<&ProcessEntry> {
+        let parent = self.nodes[*self.indices.get(&pid)?].parent?;
+        Some(&self.nodes[parent].entry)
+    }
+
+    /// Gets the children of the specified process in the order of the entries.
+    pub fn children(&self, pid: ProcessId) -> impl Iterator<Item = &ProcessEntry> + '_ {
+        self.indices
+            .get(&pid)
+            .map_or(&[][..], |&index| &self.nodes[index].children)
+            .iter()
+            .map(|&child| &self.nodes[child].entry)
+    }
+
+    /// Gets the parent, grandparent, etc. of the specified process, starting with the parent.
+    pub fn ancestors(&self, pid: ProcessId) -> Ancestors<'_> {
+        Ancestors {
+            tree: self,
+            next: self
+                .indices
+                .get(&pid)
+                .and_then(|&index| self.nodes[index].parent),
+        }
+    }
+
+    /// Gets the children, grandchildren, etc. of the specified process in depth-first order.
+    pub fn descendants(&self, pid: ProcessId) -> Descendants<'_> {
+        let mut stack = Vec::new();
+        if let Some(&index) = self.indices.get(&pid) {
+            stack.extend(self.nodes[index].children.iter().rev());
+        }
+
+        Descendants { tree: self, stack }
+    }
+
+    /// Gets the indices of the ancestors of the node at `index`. The iterator doesn't end if the links form a cycle.
+    fn ancestor_indices(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.nodes[index].parent, |&ancestor| {
+            self.nodes[ancestor].parent
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An iterator over the ancestors of a process. See [`ProcessTree::ancestors`].
+pub struct Ancestors<'t> {
+    /// The tree of the process.
+    tree: &'t ProcessTree,
+    /// The index of the next ancestor.
+    next: Option<usize>,
+}
+
+impl<'t> Iterator for Ancestors<'t> {
+    type Item = &'t ProcessEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = &self.tree.nodes[self.next?];
+        self.next = node.parent;
+        Some(&node.entry)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An iterator over the descendants of a process. See [`ProcessTree::descendants`].
+pub struct Descendants<'t> {
+    /// The tree of the process.
+    tree: &'t ProcessTree,
+    /// The indices of the nodes that are not visited yet, the next one on top.
+    stack: Vec<usize>,
+}
+
+impl<'t> Iterator for Descendants<'t> {
+    type Item = &'t ProcessEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = &self.tree.nodes[self.stack.pop()?];
+        self.stack.extend(node.children.iter().rev());
+        Some(&node.entry)
+    }
+}
+
+/// Determines whether `parent` was created after `child`, so it cannot be the original parent of `child`.
+const fn is_stale(parent: &ProcessEntry, child: &ProcessEntry) -> bool {
+    matches!(
+        (parent.creation_time, child.creation_time),
+        (Some(parent_time), Some(child_time)) if parent_time > child_time
+    )
+}
+
+/// Gets the creation time of the process with the specified identifier, or [`None`] if it cannot be queried.
+fn creation_time(pid: ProcessId) -> Option<u64> {
+    let handle = process::open::<PROCESS_QUERY_LIMITED_INFORMATION>(pid.0, false).ok()?;
+    let [creation_time, ..] = process::get_times(&handle).ok()?;
+    Some(file_time_to_u64(creation_time))
+}
+
+/// Converts a [`SystemTime`] to the number of 100-nanosecond intervals since 1601-01-01 (UTC).
+fn system_time_to_u64(time: SystemTime) -> u64 {
+    let since_unix_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let since_windows_epoch = since_unix_epoch + Duration::from_secs(WINDOWS_TO_UNIX_EPOCH_SECS);
+    u64::try_from(since_windows_epoch.as_nanos() / 100).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an entry with the specified identifiers and creation time.
+    fn entry(pid: u32, parent_pid: u32, creation_time: Option<u64>) -> ProcessEntry {
+        ProcessEntry {
+            pid: ProcessId(pid),
+            parent_pid: ProcessId(parent_pid),
+            creation_time,
+            exe_file: U16String::from_str("test.exe"),
+        }
+    }
+
+    /// Collects the identifiers of the processes.
+    fn pids<'t>(entries: impl Iterator<Item = &'t ProcessEntry>) -> Vec<u32> {
+        entries.map(|entry| entry.pid.0).collect()
+    }
+
+    #[test]
+    fn newer_parent_is_rejected() {
+        let tree = ProcessTree::from_entries([
+            entry(4, 0, Some(10)),
+            entry(8, 4, Some(5)),
+            entry(12, 4, Some(20)),
+        ]);
+
+        assert_eq!(tree.parent(ProcessId(8)), None);
+        assert_eq!(
+            tree.parent(ProcessId(12)).map(|parent| parent.pid),
+            Some(ProcessId(4))
+        );
+        assert_eq!(pids(tree.roots()), [4, 8]);
+    }
+
+    #[test]
+    fn unknown_creation_time_keeps_the_link() {
+        let tree = ProcessTree::from_entries([
+            entry(4, 0, None),
+            entry(8, 4, Some(5)),
+            entry(12, 8, None),
+        ]);
+
+        assert_eq!(pids(tree.roots()), [4]);
+        assert_eq!(pids(tree.descendants(ProcessId(4))), [8, 12]);
+    }
+
+    #[test]
+    fn own_parent_is_a_root() {
+        let tree = ProcessTree::from_entries([entry(0, 0, None), entry(4, 0, Some(1))]);
+
+        assert_eq!(tree.parent(ProcessId(0)), None);
+        assert_eq!(pids(tree.roots()), [0]);
+        assert_eq!(pids(tree.children(ProcessId(0))), [4]);
+    }
+
+    #[test]
+    fn duplicate_pid_keeps_the_first_entry() {
+        let tree = ProcessTree::from_entries([
+            entry(4, 0, Some(1)),
+            entry(8, 4, Some(2)),
+            entry(8, 12, Some(3)),
+        ]);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get(ProcessId(8)), Some(&entry(8, 4, Some(2))));
+        assert_eq!(pids(tree.children(ProcessId(4))), [8]);
+    }
+
+    #[test]
+    fn cycle_is_cut_at_the_first_entry() {
+        let tree = ProcessTree::from_entries([
+            entry(30, 32, None),
+            entry(31, 30, None),
+            entry(32, 31, None),
+            entry(40, 41, None),
+            entry(41, 40, Some(1)),
+        ]);
+
+        assert_eq!(pids(tree.roots()), [30, 40]);
+        assert_eq!(pids(tree.descendants(ProcessId(30))), [31, 32]);
+        assert_eq!(pids(tree.ancestors(ProcessId(32))), [31, 30]);
+        assert_eq!(pids(tree.descendants(ProcessId(40))), [41]);
+    }
+
+    #[test]
+    fn ancestors_start_with_the_parent() {
+        let tree = ProcessTree::from_entries([
+            entry(0, 0, None),
+            entry(4, 0, Some(1)),
+            entry(10, 4, Some(2)),
+            entry(11, 10, Some(3)),
+            entry(13, 11, Some(4)),
+        ]);
+
+        assert_eq!(pids(tree.ancestors(ProcessId(13))), [11, 10, 4, 0]);
+        assert_eq!(pids(tree.ancestors(ProcessId(0))), []);
+        assert_eq!(pids(tree.ancestors(ProcessId(99))), []);
+    }
+
+    #[test]
+    fn descendants_are_in_depth_first_order() {
+        let tree = ProcessTree::from_entries([
+            entry(4, 0, Some(1)),
+            entry(10, 4, Some(2)),
+            entry(11, 10, Some(3)),
+            entry(12, 10, Some(4)),
+            entry(13, 11, Some(5)),
+            entry(20, 4, Some(6)),
+        ]);
+
+        assert_eq!(pids(tree.descendants(ProcessId(4))), [10, 11, 13, 12, 20]);
+        assert_eq!(pids(tree.children(ProcessId(10))), [11, 12]);
+        assert_eq!(pids(tree.descendants(ProcessId(13))), []);
+        assert_eq!(pids(tree.descendants(ProcessId(99))), []);
+    }
+}